@@ -0,0 +1,806 @@
+use crate::git;
+use crate::lockfile::{self, LockedPackage};
+use crate::workspace::{self, ManifestKind, Workspace};
+use moka::sync::Cache;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+const DATABASE_FILE: &str = "osv.json";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+  Unknown,
+  Low,
+  Moderate,
+  High,
+  Critical,
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let label = match self {
+      Severity::Unknown => "Unknown",
+      Severity::Low => "Low",
+      Severity::Moderate => "Moderate",
+      Severity::High => "High",
+      Severity::Critical => "Critical",
+    };
+    f.write_str(label)
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Ecosystem {
+  #[serde(rename = "npm")]
+  Npm,
+  #[serde(rename = "crates.io")]
+  Crates,
+}
+
+// OSV range events, in the order the advisory lists them
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+enum RangeEvent {
+  Introduced(String),
+  Fixed(String),
+  LastAffected(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct AffectedPackage {
+  ecosystem: Ecosystem,
+  name: String,
+  ranges: Vec<Vec<RangeEvent>>,
+  versions: Vec<String>,
+}
+
+// The parts of an OSV record the audit needs
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Advisory {
+  pub id: String,
+  pub aliases: Vec<String>,
+  pub summary: String,
+  pub severity: Severity,
+  pub cvss: Option<String>,
+  pub modified: String,
+  affected: Vec<AffectedPackage>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ImportSummary {
+  pub imported: usize,
+  pub total: usize,
+  // Advisories that were not in the database before
+  pub new: Vec<String>,
+  pub database: String,
+  // Findings of the new advisories in the monitored folders, for the frontend to announce
+  pub findings: Vec<NewFinding>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct NewFinding {
+  pub folder: String,
+  // Notification text
+  pub title: String,
+  pub body: String,
+  #[serde(flatten)]
+  pub finding: Finding,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Finding {
+  pub package: String,
+  pub dependency: String,
+  pub version: String,
+  pub advisory: String,
+  pub aliases: Vec<String>,
+  pub summary: String,
+  pub severity: Severity,
+  pub fixed: Vec<String>,
+  // From the workspace package down to the vulnerable dependency
+  pub path: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AuditReport {
+  pub advisories: usize,
+  pub findings: Vec<Finding>,
+}
+
+// A workspace package and the lock graph it resolves through, where `root` is its own node
+struct AuditTarget<'a> {
+  name: String,
+  ecosystem: Ecosystem,
+  root: String,
+  packages: &'a BTreeMap<String, LockedPackage>,
+}
+
+static DATABASE_CACHE: Lazy<Cache<PathBuf, Arc<Vec<Advisory>>>> = Lazy::new(|| {
+  Cache::builder()
+    .time_to_live(Duration::from_secs(600))
+    .initial_capacity(1)
+    .build()
+});
+
+pub fn clear_cache() {
+  DATABASE_CACHE.invalidate_all();
+}
+
+// Imports an OSV dump from a directory of JSON files or a zip of them, such as the per-ecosystem
+// `all.zip` exports. Advisories already in the database are replaced when the import is newer.
+// Findings of new advisories in the `monitored` folders are returned for the frontend to
+// announce, so they follow the notification settings
+#[tauri::command]
+pub fn import_advisories(
+  source: String,
+  monitored: Option<Vec<String>>,
+) -> Result<ImportSummary, String> {
+  let mut summary = import(&source)?;
+  if !summary.new.is_empty() {
+    for folder in monitored.unwrap_or_default() {
+      // Folders outside a workspace or without a lockfile have nothing to report
+      if let Ok(report) = audit(&folder) {
+        summary
+          .findings
+          .extend(new_findings(&folder, report, &summary.new));
+      }
+    }
+  }
+  Ok(summary)
+}
+
+pub fn import(source: &str) -> Result<ImportSummary, String> {
+  let records = read_source(Path::new(source))?;
+  let database = database_path()?;
+  let mut advisories: BTreeMap<String, Advisory> = load_database(&database)?
+    .iter()
+    .map(|advisory| (advisory.id.clone(), advisory.clone()))
+    .collect();
+
+  let mut imported = 0;
+  let mut new = Vec::new();
+  for record in records {
+    let Some(advisory) = parse_osv(&record) else {
+      continue;
+    };
+    match advisories.get(&advisory.id) {
+      Some(existing) if existing.modified >= advisory.modified => continue,
+      Some(_) => {}
+      None => new.push(advisory.id.clone()),
+    }
+    imported += 1;
+    advisories.insert(advisory.id.clone(), advisory);
+  }
+
+  if let Some(parent) = database.parent() {
+    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+  }
+  let advisories: Vec<Advisory> = advisories.into_values().collect();
+  let contents = serde_json::to_string(&advisories).map_err(|e| e.to_string())?;
+  std::fs::write(&database, contents).map_err(|e| e.to_string())?;
+  let total = advisories.len();
+  DATABASE_CACHE.insert(database.clone(), Arc::new(advisories));
+
+  Ok(ImportSummary {
+    imported,
+    total,
+    new,
+    database: database.to_string_lossy().into_owned(),
+    findings: Vec::new(),
+  })
+}
+
+// Audits the package at `path`, or every package when `path` is the workspace root
+#[tauri::command]
+pub fn audit_dependencies(path: String) -> Result<AuditReport, String> {
+  audit(&path)
+}
+
+pub fn audit(path: &str) -> Result<AuditReport, String> {
+  let advisories = load_database(&database_path()?)?;
+  if advisories.is_empty() {
+    return Err("The advisory database is empty, import an OSV dump first".to_string());
+  }
+  let workspace = workspace::load_workspace(path)?;
+  let only = workspace
+    .package_containing(Path::new(path))
+    .filter(|package| Path::new(&package.path) == Path::new(path))
+    .filter(|package| package.path != workspace.root)
+    .map(|package| package.name.clone());
+
+  // Advisories by ecosystem and package name, so each dependency is only checked against its own
+  let mut by_package: HashMap<(Ecosystem, &str), Vec<(&Advisory, &AffectedPackage)>> =
+    HashMap::new();
+  for advisory in advisories.iter() {
+    for affected in &advisory.affected {
+      by_package
+        .entry((affected.ecosystem, affected.name.as_str()))
+        .or_default()
+        .push((advisory, affected));
+    }
+  }
+
+  // Importers join the npm graph as versionless nodes, so paths go through workspace links
+  let npm = lockfile::load_lockfile(path).ok().map(|lockfile| {
+    let mut packages = lockfile.packages.clone();
+    let mut roots = Vec::new();
+    for importer in &lockfile.importers {
+      let node = format!("link:{}", importer.path);
+      let dependencies = importer
+        .dependencies
+        .iter()
+        .filter_map(|d| {
+          d.id
+            .clone()
+            .or_else(|| d.link.as_ref().map(|link| format!("link:{}", link)))
+        })
+        .collect();
+      let name = importer
+        .name
+        .clone()
+        .unwrap_or_else(|| importer.path.clone());
+      packages.insert(
+        node.clone(),
+        LockedPackage {
+          name: name.clone(),
+          version: String::new(),
+          dependencies,
+          integrity: None,
+        },
+      );
+      roots.push((name, node));
+    }
+    (roots, packages)
+  });
+  let cargo = cargo_packages(&workspace);
+  let mut targets = Vec::new();
+  if let Some((roots, packages)) = &npm {
+    for (name, root) in roots {
+      targets.push(AuditTarget {
+        name: name.clone(),
+        ecosystem: Ecosystem::Npm,
+        root: root.clone(),
+        packages,
+      });
+    }
+  }
+  if let Some(cargo) = &cargo {
+    for member in workspace
+      .packages
+      .iter()
+      .filter(|p| p.manifest == ManifestKind::Cargo)
+    {
+      let Some(version) = member.version.as_deref() else {
+        continue;
+      };
+      targets.push(AuditTarget {
+        name: member.name.clone(),
+        ecosystem: Ecosystem::Crates,
+        root: lockfile::package_id(&member.name, version),
+        packages: cargo,
+      });
+    }
+  }
+
+  let mut findings = Vec::new();
+  for target in targets
+    .iter()
+    .filter(|t| only.as_ref().is_none_or(|name| *name == t.name))
+  {
+    for (id, chain) in reachable(target) {
+      let package = &target.packages[&id];
+      let Ok(version) = semver::Version::parse(&package.version) else {
+        continue;
+      };
+      let candidates = by_package
+        .get(&(target.ecosystem, package.name.as_str()))
+        .into_iter()
+        .flatten();
+      for (advisory, affected) in candidates {
+        if !is_affected(affected, &package.version, &version) {
+          continue;
+        }
+        findings.push(Finding {
+          package: target.name.clone(),
+          dependency: package.name.clone(),
+          version: package.version.clone(),
+          advisory: advisory.id.clone(),
+          aliases: advisory.aliases.clone(),
+          summary: advisory.summary.clone(),
+          severity: advisory.severity,
+          fixed: fixed_versions(affected),
+          path: chain.clone(),
+        });
+      }
+    }
+  }
+  findings.sort_by(|a, b| {
+    b.severity
+      .cmp(&a.severity)
+      .then_with(|| a.package.cmp(&b.package))
+      .then_with(|| a.dependency.cmp(&b.dependency))
+  });
+
+  Ok(AuditReport {
+    advisories: advisories.len(),
+    findings,
+  })
+}
+
+// One finding per package and new advisory, however many paths lead to it
+fn new_findings(folder: &str, report: AuditReport, new: &[String]) -> Vec<NewFinding> {
+  let name = Path::new(folder)
+    .file_name()
+    .map(|n| n.to_string_lossy().into_owned())
+    .unwrap_or_else(|| folder.to_string());
+  let mut notified = HashSet::new();
+  report
+    .findings
+    .into_iter()
+    .filter(|f| new.contains(&f.advisory))
+    .filter(|f| notified.insert((f.package.clone(), f.advisory.clone())))
+    .map(|finding| NewFinding {
+      folder: folder.to_string(),
+      title: format!("New {} severity advisory in {}", finding.severity, name),
+      body: format!(
+        "{}@{} ({}): {}",
+        finding.dependency, finding.version, finding.advisory, finding.summary
+      ),
+      finding,
+    })
+    .collect()
+}
+
+fn database_path() -> Result<PathBuf, String> {
+  let dir = dirs::data_dir().ok_or("Could not find data directory")?;
+  Ok(
+    dir
+      .join("mr-manager")
+      .join("advisories")
+      .join(DATABASE_FILE),
+  )
+}
+
+fn load_database(path: &Path) -> Result<Arc<Vec<Advisory>>, String> {
+  if let Some(cached) = DATABASE_CACHE.get(path) {
+    return Ok(cached);
+  }
+  let advisories: Vec<Advisory> = match std::fs::read_to_string(path) {
+    Ok(contents) => serde_json::from_str(&contents).map_err(|e| e.to_string())?,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+    Err(e) => return Err(e.to_string()),
+  };
+  let advisories = Arc::new(advisories);
+  DATABASE_CACHE.insert(path.to_path_buf(), advisories.clone());
+  Ok(advisories)
+}
+
+// JSON documents of a directory (recursively), a zip archive or a single file
+fn read_source(source: &Path) -> Result<Vec<serde_json::Value>, String> {
+  let mut contents = Vec::new();
+  if source.is_dir() {
+    for entry in ignore::WalkBuilder::new(source)
+      .standard_filters(false)
+      .build()
+    {
+      let entry = entry.map_err(|e| e.to_string())?;
+      if entry.path().extension().is_some_and(|ext| ext == "json") {
+        contents.push(std::fs::read_to_string(entry.path()).map_err(|e| e.to_string())?);
+      }
+    }
+  } else if source.extension().is_some_and(|ext| ext == "zip") {
+    let file = std::fs::File::open(source).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip: {}", e))?;
+    for index in 0..archive.len() {
+      let mut entry = archive.by_index(index).map_err(|e| e.to_string())?;
+      if !entry.is_file() || !entry.name().ends_with(".json") {
+        continue;
+      }
+      let mut text = String::new();
+      entry
+        .read_to_string(&mut text)
+        .map_err(|e| format!("{}: {}", entry.name(), e))?;
+      contents.push(text);
+    }
+  } else if source.is_file() {
+    contents.push(std::fs::read_to_string(source).map_err(|e| e.to_string())?);
+  } else {
+    return Err("Advisory source does not exist".to_string());
+  }
+
+  // A file may hold one record or an array of them
+  let mut records = Vec::new();
+  for text in contents {
+    match serde_json::from_str::<serde_json::Value>(&text) {
+      Ok(serde_json::Value::Array(values)) => records.extend(values),
+      Ok(value) => records.push(value),
+      Err(_) => continue,
+    }
+  }
+  Ok(records)
+}
+
+// Only npm and crates.io entries are kept; withdrawn advisories are dropped
+fn parse_osv(record: &serde_json::Value) -> Option<Advisory> {
+  if record.get("withdrawn").is_some_and(|w| !w.is_null()) {
+    return None;
+  }
+  let strings = |value: Option<&serde_json::Value>| -> Vec<String> {
+    value
+      .and_then(|v| v.as_array())
+      .into_iter()
+      .flatten()
+      .filter_map(|v| v.as_str().map(String::from))
+      .collect()
+  };
+
+  let mut affected = Vec::new();
+  let mut database_severity = record
+    .get("database_specific")
+    .and_then(|d| d.get("severity"))
+    .and_then(|s| s.as_str())
+    .map(String::from);
+  for entry in record.get("affected")?.as_array()? {
+    let package = entry.get("package")?;
+    let ecosystem = match package.get("ecosystem")?.as_str()? {
+      "npm" => Ecosystem::Npm,
+      "crates.io" => Ecosystem::Crates,
+      _ => continue,
+    };
+    database_severity = database_severity.or_else(|| {
+      entry
+        .get("database_specific")?
+        .get("severity")?
+        .as_str()
+        .map(String::from)
+    });
+
+    let ranges = entry
+      .get("ranges")
+      .and_then(|r| r.as_array())
+      .into_iter()
+      .flatten()
+      .filter(|range| range.get("type").and_then(|t| t.as_str()) != Some("GIT"))
+      .map(|range| {
+        range
+          .get("events")
+          .and_then(|e| e.as_array())
+          .into_iter()
+          .flatten()
+          .filter_map(|event| {
+            let (key, value) = event.as_object()?.iter().next()?;
+            let value = value.as_str()?.to_string();
+            match key.as_str() {
+              "introduced" => Some(RangeEvent::Introduced(value)),
+              "fixed" => Some(RangeEvent::Fixed(value)),
+              "last_affected" => Some(RangeEvent::LastAffected(value)),
+              _ => None,
+            }
+          })
+          .collect()
+      })
+      .collect();
+
+    affected.push(AffectedPackage {
+      ecosystem,
+      name: package.get("name")?.as_str()?.to_string(),
+      ranges,
+      versions: strings(entry.get("versions")),
+    });
+  }
+  if affected.is_empty() {
+    return None;
+  }
+
+  let cvss = record
+    .get("severity")
+    .and_then(|s| s.as_array())
+    .into_iter()
+    .flatten()
+    .find(|s| s.get("type").and_then(|t| t.as_str()) == Some("CVSS_V3"))
+    .and_then(|s| s.get("score")?.as_str().map(String::from));
+  let severity = match database_severity.as_deref().map(str::to_ascii_uppercase) {
+    Some(level) if level == "CRITICAL" => Severity::Critical,
+    Some(level) if level == "HIGH" => Severity::High,
+    Some(level) if level == "MODERATE" || level == "MEDIUM" => Severity::Moderate,
+    Some(level) if level == "LOW" => Severity::Low,
+    _ => cvss
+      .as_deref()
+      .and_then(cvss3_score)
+      .map_or(Severity::Unknown, severity_from_score),
+  };
+
+  Some(Advisory {
+    id: record.get("id")?.as_str()?.to_string(),
+    aliases: strings(record.get("aliases")),
+    summary: record
+      .get("summary")
+      .or_else(|| record.get("details"))
+      .and_then(|s| s.as_str())
+      .map(|s| s.lines().next().unwrap_or_default().to_string())
+      .unwrap_or_default(),
+    severity,
+    cvss,
+    modified: record
+      .get("modified")
+      .and_then(|m| m.as_str())
+      .unwrap_or_default()
+      .to_string(),
+    affected,
+  })
+}
+
+fn is_affected(affected: &AffectedPackage, raw: &str, version: &semver::Version) -> bool {
+  if affected.versions.iter().any(|v| v == raw) {
+    return true;
+  }
+  affected.ranges.iter().any(|events| {
+    // Walk the events in order: introduced opens a range, fixed or last_affected close it
+    let mut inside = false;
+    for event in events {
+      match event {
+        RangeEvent::Introduced(introduced) => {
+          if introduced == "0" || parse_version(introduced).is_some_and(|i| *version >= i) {
+            inside = true;
+          }
+        }
+        RangeEvent::Fixed(fixed) => {
+          if inside && parse_version(fixed).is_some_and(|f| *version < f) {
+            return true;
+          }
+          inside = false;
+        }
+        RangeEvent::LastAffected(last) => {
+          if inside && parse_version(last).is_some_and(|l| *version <= l) {
+            return true;
+          }
+          inside = false;
+        }
+      }
+    }
+    inside
+  })
+}
+
+fn fixed_versions(affected: &AffectedPackage) -> Vec<String> {
+  affected
+    .ranges
+    .iter()
+    .flatten()
+    .filter_map(|event| match event {
+      RangeEvent::Fixed(fixed) => Some(fixed.clone()),
+      _ => None,
+    })
+    .collect()
+}
+
+fn parse_version(version: &str) -> Option<semver::Version> {
+  semver::Version::parse(version.trim_start_matches('v')).ok()
+}
+
+// Every package the target resolves to, with the shortest chain from the target leading to it
+fn reachable(target: &AuditTarget) -> Vec<(String, Vec<String>)> {
+  let mut parents: HashMap<&str, Option<&str>> = HashMap::new();
+  let mut queue: VecDeque<&str> = VecDeque::new();
+  parents.insert(&target.root, None);
+  queue.push_back(&target.root);
+
+  let mut found = Vec::new();
+  while let Some(id) = queue.pop_front() {
+    let Some(package) = target.packages.get(id) else {
+      continue;
+    };
+    let label = |package: &LockedPackage| match package.version.as_str() {
+      "" => package.name.clone(),
+      version => format!("{}@{}", package.name, version),
+    };
+    let mut chain = vec![label(package)];
+    let mut current = id;
+    while let Some(Some(parent)) = parents.get(current) {
+      chain.push(label(&target.packages[*parent]));
+      current = parent;
+    }
+    chain.reverse();
+    if id != target.root {
+      found.push((id.to_string(), chain));
+    }
+
+    for dependency in &package.dependencies {
+      if !parents.contains_key(dependency.as_str()) {
+        parents.insert(dependency, Some(id));
+        queue.push_back(dependency);
+      }
+    }
+  }
+  found
+}
+
+fn cargo_packages(workspace: &Workspace) -> Option<BTreeMap<String, LockedPackage>> {
+  let git_root = git::find_git_root(Path::new(&workspace.root)).map(PathBuf::from);
+  for dir in Path::new(&workspace.root).ancestors() {
+    if let Ok(contents) = std::fs::read_to_string(dir.join("Cargo.lock")) {
+      return lockfile::parse_cargo_lock(&contents).ok();
+    }
+    if git_root.as_deref() == Some(dir) {
+      break;
+    }
+  }
+  None
+}
+
+// CVSS 3.x base score from a vector like `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`
+fn cvss3_score(vector: &str) -> Option<f64> {
+  let metrics: HashMap<&str, &str> = vector
+    .split('/')
+    .skip(1)
+    .filter_map(|part| part.split_once(':'))
+    .collect();
+  let changed = *metrics.get("S")? == "C";
+
+  let attack_vector = match *metrics.get("AV")? {
+    "N" => 0.85,
+    "A" => 0.62,
+    "L" => 0.55,
+    _ => 0.2,
+  };
+  let complexity = if *metrics.get("AC")? == "L" {
+    0.77
+  } else {
+    0.44
+  };
+  let privileges = match (*metrics.get("PR")?, changed) {
+    ("N", _) => 0.85,
+    ("L", false) => 0.62,
+    ("L", true) => 0.68,
+    (_, false) => 0.27,
+    (_, true) => 0.5,
+  };
+  let interaction = if *metrics.get("UI")? == "N" {
+    0.85
+  } else {
+    0.62
+  };
+  let impact_of = |metric: &str| match metrics.get(metric).copied() {
+    Some("H") => 0.56,
+    Some("L") => 0.22,
+    _ => 0.0,
+  };
+
+  let base = 1.0 - (1.0 - impact_of("C")) * (1.0 - impact_of("I")) * (1.0 - impact_of("A"));
+  let impact = if changed {
+    7.52 * (base - 0.029) - 3.25 * (base - 0.02f64).powi(15)
+  } else {
+    6.42 * base
+  };
+  if impact <= 0.0 {
+    return Some(0.0);
+  }
+  let exploitability = 8.22 * attack_vector * complexity * privileges * interaction;
+  let score = if changed {
+    1.08 * (impact + exploitability)
+  } else {
+    impact + exploitability
+  };
+  Some((score.min(10.0) * 10.0).ceil() / 10.0)
+}
+
+fn severity_from_score(score: f64) -> Severity {
+  match score {
+    s if s >= 9.0 => Severity::Critical,
+    s if s >= 7.0 => Severity::High,
+    s if s >= 4.0 => Severity::Moderate,
+    s if s > 0.0 => Severity::Low,
+    _ => Severity::Unknown,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn affected(ranges: Vec<Vec<RangeEvent>>, versions: &[&str]) -> AffectedPackage {
+    AffectedPackage {
+      ecosystem: Ecosystem::Npm,
+      name: "lib".to_string(),
+      ranges,
+      versions: versions.iter().map(|v| v.to_string()).collect(),
+    }
+  }
+
+  fn check(affected: &AffectedPackage, version: &str) -> bool {
+    is_affected(affected, version, &parse_version(version).unwrap())
+  }
+
+  #[test]
+  fn matches_ranges_up_to_the_fix() {
+    let package = affected(
+      vec![vec![
+        RangeEvent::Introduced("0".to_string()),
+        RangeEvent::Fixed("1.2.3".to_string()),
+      ]],
+      &[],
+    );
+    assert!(check(&package, "0.1.0"));
+    assert!(check(&package, "1.2.2"));
+    assert!(!check(&package, "1.2.3"));
+    assert!(!check(&package, "2.0.0"));
+  }
+
+  #[test]
+  fn walks_several_ranges_in_order() {
+    let package = affected(
+      vec![vec![
+        RangeEvent::Introduced("1.0.0".to_string()),
+        RangeEvent::Fixed("1.0.5".to_string()),
+        RangeEvent::Introduced("2.0.0".to_string()),
+        RangeEvent::Fixed("2.1.0".to_string()),
+      ]],
+      &[],
+    );
+    assert!(!check(&package, "0.9.0"));
+    assert!(check(&package, "1.0.4"));
+    assert!(!check(&package, "1.0.7"));
+    assert!(check(&package, "2.0.1"));
+    assert!(!check(&package, "2.1.0"));
+  }
+
+  #[test]
+  fn includes_last_affected_and_open_ranges() {
+    let last = affected(
+      vec![vec![
+        RangeEvent::Introduced("0".to_string()),
+        RangeEvent::LastAffected("3.0.0".to_string()),
+      ]],
+      &[],
+    );
+    assert!(check(&last, "3.0.0"));
+    assert!(!check(&last, "3.0.1"));
+
+    let open = affected(vec![vec![RangeEvent::Introduced("4.0.0".to_string())]], &[]);
+    assert!(!check(&open, "3.9.9"));
+    assert!(check(&open, "5.0.0"));
+  }
+
+  #[test]
+  fn matches_listed_versions() {
+    let package = affected(Vec::new(), &["0.1.0-beta.1"]);
+    assert!(check(&package, "0.1.0-beta.1"));
+    assert!(!check(&package, "0.1.0"));
+  }
+
+  #[test]
+  fn scores_cvss3_vectors() {
+    assert_eq!(
+      cvss3_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"),
+      Some(9.8)
+    );
+    assert_eq!(
+      cvss3_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:U/C:L/I:L/A:N"),
+      Some(5.4)
+    );
+    assert_eq!(
+      cvss3_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N"),
+      Some(6.1)
+    );
+    assert_eq!(
+      cvss3_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N"),
+      Some(0.0)
+    );
+    assert_eq!(cvss3_score("CVSS:3.1/AC:L/PR:N/UI:N/S:U/C:H"), None);
+  }
+
+  #[test]
+  fn labels_severities() {
+    assert_eq!(severity_from_score(9.8), Severity::Critical);
+    assert_eq!(severity_from_score(5.4), Severity::Moderate);
+    assert_eq!(severity_from_score(0.0), Severity::Unknown);
+    assert_eq!(Severity::High.to_string(), "High");
+  }
+}
@@ -0,0 +1,200 @@
+use clap::{Parser, Subcommand};
+use mr_analyzer_lib::git::BasicCommit;
+use mr_analyzer_lib::{docs, docs_batch, git, workspace};
+use serde::Serialize;
+use std::process::ExitCode;
+
+// Headless access to the same operations the app exposes, for CI and shell scripts
+#[derive(Parser)]
+#[command(name = "mrm", version, about = "Monorepo Manager command line")]
+struct Cli {
+  /// Print results as JSON instead of text
+  #[arg(long, global = true)]
+  json: bool,
+  #[command(subcommand)]
+  command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+  /// List the packages of the workspace containing PATH
+  Packages { path: String },
+  /// List commits touching a package folder, newest first
+  Commits {
+    path: String,
+    #[arg(long)]
+    page: Option<usize>,
+    #[arg(long)]
+    per_page: Option<usize>,
+    #[arg(long)]
+    branch: Option<String>,
+    #[arg(long)]
+    remote: Option<String>,
+  },
+  /// Fetch and list upstream commits not yet in the local branch
+  NewCommits {
+    path: String,
+    #[arg(long, default_value = "origin")]
+    remote: String,
+    #[arg(long, default_value = "main")]
+    branch: String,
+  },
+  /// Show the files a commit changed under PATH
+  Show {
+    path: String,
+    commit: String,
+    /// Read the commit with `git show`, for commits only fetched from upstream
+    #[arg(long)]
+    upstream: bool,
+    /// Remote the commit was fetched from, for its web links
+    #[arg(long)]
+    remote: Option<String>,
+  },
+  /// Generate docs for the package at PATH, or for every package with --all
+  Docs {
+    path: String,
+    #[arg(long)]
+    generator: Option<String>,
+    #[arg(long)]
+    force: bool,
+    #[arg(long)]
+    all: bool,
+    #[arg(long, default_value_t = docs_batch::DEFAULT_CONCURRENCY)]
+    concurrency: usize,
+  },
+}
+
+fn main() -> ExitCode {
+  let cli = Cli::parse();
+  match run(cli.command, cli.json) {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(e) => {
+      eprintln!("error: {}", e);
+      ExitCode::FAILURE
+    }
+  }
+}
+
+fn run(command: Commands, json: bool) -> Result<(), String> {
+  match command {
+    Commands::Packages { path } => {
+      let packages = workspace::list_packages(path)?;
+      output(json, &packages, || {
+        for package in &packages {
+          println!(
+            "{}\t{}\t{}",
+            package.name,
+            package.version.as_deref().unwrap_or("-"),
+            package.relative_path
+          );
+        }
+      })
+    }
+    Commands::Commits {
+      path,
+      page,
+      per_page,
+      branch,
+      remote,
+    } => {
+      let commits = git::list_folder_commits(path, page, per_page, branch, remote)?;
+      output(json, &commits, || print_commits(&commits))
+    }
+    Commands::NewCommits {
+      path,
+      remote,
+      branch,
+    } => {
+      let commits = git::get_new_commits(path, remote, branch)?;
+      output(json, &commits, || print_commits(&commits))
+    }
+    Commands::Show {
+      path,
+      commit,
+      upstream,
+      remote,
+    } => {
+      let details = if upstream {
+        mr_analyzer_lib::get_new_commits_details(path, commit, remote)?
+      } else {
+        mr_analyzer_lib::get_commit_details(path, commit)?
+      };
+      output(json, &details, || {
+        println!("commit {}", details.id);
+        println!("Author: {}", details.author);
+        println!("Date:   {}", format_date(details.date));
+        println!();
+        for line in details.message.trim_end().lines() {
+          println!("    {}", line);
+        }
+        println!();
+        for change in &details.changes {
+          println!("{}\t{}", change.status, change.file);
+        }
+      })
+    }
+    Commands::Docs {
+      path,
+      generator,
+      force,
+      all,
+      concurrency,
+    } => {
+      if all {
+        let result = docs_batch::build_all(&path, None, concurrency, force)?;
+        return output(json, &result, || {
+          for package in &result.packages {
+            println!(
+              "{:?}\t{}\t{}",
+              package.outcome,
+              package.name,
+              package
+                .error
+                .as_deref()
+                .or(package.docs_path.as_deref())
+                .unwrap_or("")
+            );
+          }
+          println!("{}", result.index);
+        });
+      }
+      let build = docs::build_docs(&path, generator.as_deref(), force, |mut command| {
+        command.output().map_err(|e| e.to_string())
+      })?;
+      output(json, &build, || {
+        let state = if build.skipped { "up to date" } else { "built" };
+        println!("{} ({}, {})", build.path, build.generator, state);
+      })
+    }
+  }
+}
+
+fn output<T: Serialize>(json: bool, value: &T, text: impl FnOnce()) -> Result<(), String> {
+  if json {
+    println!(
+      "{}",
+      serde_json::to_string_pretty(value).map_err(|e| e.to_string())?
+    );
+  } else {
+    text();
+  }
+  Ok(())
+}
+
+fn print_commits(commits: &[BasicCommit]) {
+  for commit in commits {
+    println!(
+      "{}  {}  {}  {}",
+      &commit.id[..commit.id.len().min(7)],
+      format_date(commit.date),
+      commit.author,
+      commit.message.lines().next().unwrap_or("")
+    );
+  }
+}
+
+fn format_date(timestamp: i64) -> String {
+  chrono::DateTime::from_timestamp(timestamp, 0)
+    .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+    .unwrap_or_default()
+}
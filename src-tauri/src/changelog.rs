@@ -0,0 +1,247 @@
+use crate::conventional::{self, ConventionalCommit};
+use crate::git::{self, BasicCommit};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Serialize)]
+pub struct Changelog {
+  pub markdown: String,
+  pub path: Option<String>,
+}
+
+// Section order in the generated Markdown, keyed by Conventional Commit type
+const SECTIONS: &[(&str, &str)] = &[
+  ("feat", "Features"),
+  ("fix", "Bug Fixes"),
+  ("perf", "Performance Improvements"),
+  ("revert", "Reverts"),
+  ("refactor", "Code Refactoring"),
+  ("docs", "Documentation"),
+];
+
+struct Entry<'a> {
+  commit: &'a BasicCommit,
+  parsed: Option<ConventionalCommit>,
+}
+
+#[tauri::command]
+pub fn generate_changelog(
+  path: String,
+  from: Option<String>,
+  to: Option<String>,
+  target: Option<String>,
+) -> Result<Changelog, String> {
+  let git_root = git::find_git_root(Path::new(&path)).ok_or("Could not find Git repository")?;
+  let relative_path = Path::new(&path)
+    .strip_prefix(&git_root)
+    .map_err(|_| "Failed to get relative path")?
+    .to_str()
+    .ok_or("Invalid path")?
+    .to_string();
+
+  let to = to.unwrap_or_else(|| "HEAD".to_string());
+  let commits = git::list_commits_in_range(&git_root, from.as_deref(), &to, &relative_path)?;
+  let markdown = render(&to, &commits);
+
+  let path = match target.as_deref() {
+    None => None,
+    Some("package") => {
+      let file = Path::new(&path).join("CHANGELOG.md");
+      let existing = std::fs::read_to_string(&file).unwrap_or_default();
+      std::fs::write(&file, prepend_section(&existing, &markdown))
+        .map_err(|e| format!("Failed to write changelog: {}", e))?;
+      Some(file)
+    }
+    Some("docs") => {
+      let docs_dir = crate::docs::docs_output_dir(&path)?;
+      std::fs::create_dir_all(&docs_dir)
+        .map_err(|e| format!("Failed to create docs directory: {}", e))?;
+      let file = docs_dir.join("CHANGELOG.md");
+      std::fs::write(&file, format!("# Changelog\n\n{}", markdown))
+        .map_err(|e| format!("Failed to write changelog: {}", e))?;
+      Some(file)
+    }
+    Some(other) => return Err(format!("Unsupported changelog target: {}", other)),
+  };
+
+  Ok(Changelog {
+    markdown,
+    path: path.map(|p| p.to_string_lossy().into_owned()),
+  })
+}
+
+pub fn render(title: &str, commits: &[BasicCommit]) -> String {
+  let entries: Vec<Entry> = commits
+    .iter()
+    .map(|commit| Entry {
+      commit,
+      parsed: conventional::parse(&commit.message),
+    })
+    .collect();
+
+  let date = commits
+    .first()
+    .and_then(|c| chrono::DateTime::from_timestamp(c.date, 0))
+    .unwrap_or_else(chrono::Utc::now)
+    .format("%Y-%m-%d");
+
+  let mut out = format!("## {} ({})\n", title, date);
+
+  let breaking: Vec<&Entry> = entries
+    .iter()
+    .filter(|e| e.parsed.as_ref().is_some_and(|p| p.breaking))
+    .collect();
+  if !breaking.is_empty() {
+    out.push_str("\n### BREAKING CHANGES\n\n");
+    for entry in breaking {
+      let parsed = entry.parsed.as_ref().unwrap();
+      let note = parsed.breaking_note().unwrap_or(&parsed.description);
+      out.push_str(&format_line(entry.commit, parsed.scope.as_deref(), note));
+    }
+  }
+
+  for (kind, heading) in SECTIONS {
+    let section: Vec<&Entry> = entries
+      .iter()
+      .filter(|e| e.parsed.as_ref().is_some_and(|p| p.kind == *kind))
+      .collect();
+    if section.is_empty() {
+      continue;
+    }
+    out.push_str(&format!("\n### {}\n\n", heading));
+    for entry in section {
+      let parsed = entry.parsed.as_ref().unwrap();
+      out.push_str(&format_line(
+        entry.commit,
+        parsed.scope.as_deref(),
+        &parsed.description,
+      ));
+    }
+  }
+
+  let other: Vec<&Entry> = entries
+    .iter()
+    .filter(|e| match &e.parsed {
+      Some(parsed) => !SECTIONS.iter().any(|(kind, _)| *kind == parsed.kind),
+      None => true,
+    })
+    .collect();
+  if !other.is_empty() {
+    out.push_str("\n### Other Changes\n\n");
+    for entry in other {
+      let (scope, description) = match &entry.parsed {
+        Some(parsed) => (parsed.scope.as_deref(), parsed.description.as_str()),
+        None => (
+          None,
+          entry.commit.message.lines().next().unwrap_or_default(),
+        ),
+      };
+      out.push_str(&format_line(entry.commit, scope, description));
+    }
+  }
+
+  out
+}
+
+fn format_line(commit: &BasicCommit, scope: Option<&str>, description: &str) -> String {
+  let short_id = &commit.id[..commit.id.len().min(7)];
+  let link = match &commit.web_url {
+    Some(url) => format!("[{}]({})", short_id, url),
+    None => short_id.to_string(),
+  };
+  let description = description.lines().next().unwrap_or_default();
+  match scope {
+    Some(scope) => format!("- **{}:** {} ({})\n", scope, description, link),
+    None => format!("- {} ({})\n", description, link),
+  }
+}
+
+// Inserts the new section below the top-level heading of an existing changelog
+fn prepend_section(existing: &str, section: &str) -> String {
+  if existing.trim().is_empty() {
+    return format!("# Changelog\n\n{}", section);
+  }
+
+  if existing.starts_with("# ") {
+    let (heading, rest) = existing.split_once('\n').unwrap_or((existing, ""));
+    format!(
+      "{}\n\n{}\n{}",
+      heading,
+      section,
+      rest.trim_start_matches('\n')
+    )
+  } else {
+    format!("{}\n{}", section, existing)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn commit(id: &str, message: &str) -> BasicCommit {
+    BasicCommit {
+      id: id.to_string(),
+      message: message.to_string(),
+      author: "Sam".to_string(),
+      date: 0,
+      remote_url: None,
+      web_url: None,
+      issues: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn renders_sections_in_order() {
+    let mut feature = commit("aaaaaaa1111", "feat(ui): add dark mode");
+    feature.web_url = Some("https://example.com/commit/aaaaaaa1111".to_string());
+    let commits = vec![
+      feature,
+      commit(
+        "bbbbbbb2222",
+        "fix!: drop legacy config\n\nBREAKING CHANGE: config.json is no longer read",
+      ),
+      commit("ccccccc3333", "Update readme\n\nMore details"),
+      commit("ddddddd4444", "chore(deps): bump serde"),
+    ];
+
+    assert_eq!(
+      render("v1.0.0", &commits),
+      "## v1.0.0 (1970-01-01)\n\
+       \n### BREAKING CHANGES\n\n\
+       - config.json is no longer read (bbbbbbb)\n\
+       \n### Features\n\n\
+       - **ui:** add dark mode ([aaaaaaa](https://example.com/commit/aaaaaaa1111))\n\
+       \n### Bug Fixes\n\n\
+       - drop legacy config (bbbbbbb)\n\
+       \n### Other Changes\n\n\
+       - Update readme (ccccccc)\n\
+       - **deps:** bump serde (ddddddd)\n"
+    );
+  }
+
+  #[test]
+  fn breaking_commits_without_a_note_use_the_description() {
+    let commits = vec![commit("eeeeeee5555", "feat(api)!: remove v1 routes")];
+    let markdown = render("HEAD", &commits);
+    assert!(markdown.contains("### BREAKING CHANGES\n\n- **api:** remove v1 routes (eeeeeee)\n"));
+    assert!(markdown.contains("### Features\n\n- **api:** remove v1 routes (eeeeeee)\n"));
+  }
+
+  #[test]
+  fn prepends_below_the_changelog_heading() {
+    let section = "## v2.0.0 (2024-02-01)\n\n- new\n";
+    assert_eq!(
+      prepend_section("", section),
+      "# Changelog\n\n## v2.0.0 (2024-02-01)\n\n- new\n"
+    );
+    assert_eq!(
+      prepend_section("# Changelog\n\n## v1.0.0 (2024-01-01)\n\n- old\n", section),
+      "# Changelog\n\n## v2.0.0 (2024-02-01)\n\n- new\n\n## v1.0.0 (2024-01-01)\n\n- old\n"
+    );
+    assert_eq!(
+      prepend_section("## v1.0.0 (2024-01-01)\n\n- old\n", section),
+      "## v2.0.0 (2024-02-01)\n\n- new\n\n## v1.0.0 (2024-01-01)\n\n- old\n"
+    );
+  }
+}
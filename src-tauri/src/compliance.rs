@@ -0,0 +1,371 @@
+use crate::config::{self, LicenseConfig};
+use crate::licenses;
+use crate::sbom::{self, Component};
+use crate::workspace::{self, Package, Workspace};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LicenseStatus {
+  Allowed,
+  // Listed in `deny`
+  Denied,
+  // Not in a non-empty `allow` list
+  Unlisted,
+  // No license found, or only a `LicenseRef-` the policy does not mention
+  Unknown,
+  // Listed in `exceptions`
+  Exempt,
+}
+
+impl LicenseStatus {
+  // Higher is better: an `OR` takes the best of its sides, an `AND` the worst
+  fn rank(self) -> u8 {
+    match self {
+      LicenseStatus::Denied => 0,
+      LicenseStatus::Unlisted => 1,
+      LicenseStatus::Unknown => 2,
+      LicenseStatus::Allowed | LicenseStatus::Exempt => 3,
+    }
+  }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LicenseEntry {
+  pub name: String,
+  pub version: String,
+  pub license: Option<String>,
+  pub status: LicenseStatus,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ComplianceReport {
+  pub package: String,
+  pub dependencies: usize,
+  // Dependencies per license expression
+  pub licenses: BTreeMap<String, usize>,
+  pub violations: Vec<LicenseEntry>,
+  pub unknown: Vec<LicenseEntry>,
+}
+
+enum Expression {
+  License(String),
+  And(Box<Expression>, Box<Expression>),
+  Or(Box<Expression>, Box<Expression>),
+}
+
+// Licenses of everything a package depends on, checked against the `licenses` policy of the
+// repository config. Covers every workspace package unless `package` names one, or only the
+// deployable apps with `deployable_only`
+#[tauri::command]
+pub fn check_license_compliance(
+  path: String,
+  package: Option<String>,
+  deployable_only: Option<bool>,
+) -> Result<Vec<ComplianceReport>, String> {
+  let workspace = workspace::load_workspace(&path)?;
+  let config = config::load_config(&path)?;
+  let policy = normalized_policy(&config.licenses);
+
+  let apps: Vec<&Package> = match package.as_deref() {
+    Some(name) => vec![workspace
+      .find(name)
+      .ok_or_else(|| format!("Unknown package: {}", name))?],
+    None => workspace
+      .packages
+      .iter()
+      .filter(|p| !deployable_only.unwrap_or(false) || sbom::is_deployable(p))
+      .collect(),
+  };
+  if apps.is_empty() {
+    return Err("No packages found".to_string());
+  }
+
+  apps
+    .into_iter()
+    .map(|app| {
+      let graph = sbom::dependency_graph(&workspace, app)?;
+      let mut report = ComplianceReport {
+        package: app.name.clone(),
+        dependencies: 0,
+        licenses: BTreeMap::new(),
+        violations: Vec::new(),
+        unknown: Vec::new(),
+      };
+
+      // Workspace packages are our own code, only third-party dependencies are checked
+      for component in graph
+        .components
+        .values()
+        .filter(|c| !is_workspace_component(&workspace, c))
+      {
+        report.dependencies += 1;
+        let label = component
+          .license
+          .clone()
+          .unwrap_or_else(|| "NOASSERTION".to_string());
+        *report.licenses.entry(label).or_default() += 1;
+
+        let exempt = policy.exceptions.iter().any(|exception| {
+          *exception == component.name
+            || *exception == format!("{}@{}", component.name, component.version)
+        });
+        let status = match &component.license {
+          _ if exempt => LicenseStatus::Exempt,
+          Some(license) => evaluate(license, &policy),
+          None => LicenseStatus::Unknown,
+        };
+        let entry = LicenseEntry {
+          name: component.name.clone(),
+          version: component.version.clone(),
+          license: component.license.clone(),
+          status,
+        };
+        match status {
+          LicenseStatus::Denied | LicenseStatus::Unlisted => report.violations.push(entry),
+          LicenseStatus::Unknown => report.unknown.push(entry),
+          LicenseStatus::Allowed | LicenseStatus::Exempt => {}
+        }
+      }
+      Ok(report)
+    })
+    .collect()
+}
+
+// A registry package may share its name with a workspace package, so the version has to
+// match as well. Npm importers without a version are listed as `0.0.0`
+fn is_workspace_component(workspace: &Workspace, component: &Component) -> bool {
+  workspace.packages.iter().any(|p| {
+    p.manifest == component.manifest
+      && p.name == component.name
+      && p.version.as_deref().unwrap_or("0.0.0") == component.version
+  })
+}
+
+// Policy entries go through the same normalization as dependency licenses, so `Apache 2.0`
+// matches `Apache-2.0`
+fn normalized_policy(policy: &LicenseConfig) -> LicenseConfig {
+  let normalize = |ids: &[String]| -> Vec<String> {
+    ids
+      .iter()
+      .filter_map(|id| licenses::normalize(id))
+      .collect()
+  };
+  LicenseConfig {
+    allow: normalize(&policy.allow),
+    deny: normalize(&policy.deny),
+    exceptions: policy.exceptions.clone(),
+  }
+}
+
+pub fn evaluate(license: &str, policy: &LicenseConfig) -> LicenseStatus {
+  let tokens: Vec<String> = license
+    .replace('(', " ( ")
+    .replace(')', " ) ")
+    .split_whitespace()
+    .map(String::from)
+    .collect();
+  let mut position = 0;
+  match parse_or(&tokens, &mut position) {
+    Some(expression) if position == tokens.len() => evaluate_expression(&expression, policy),
+    _ => LicenseStatus::Unknown,
+  }
+}
+
+fn evaluate_expression(expression: &Expression, policy: &LicenseConfig) -> LicenseStatus {
+  match expression {
+    Expression::License(id) => license_status(id, policy),
+    Expression::And(left, right) => {
+      let (left, right) = (
+        evaluate_expression(left, policy),
+        evaluate_expression(right, policy),
+      );
+      if left.rank() <= right.rank() {
+        left
+      } else {
+        right
+      }
+    }
+    Expression::Or(left, right) => {
+      let (left, right) = (
+        evaluate_expression(left, policy),
+        evaluate_expression(right, policy),
+      );
+      if left.rank() >= right.rank() {
+        left
+      } else {
+        right
+      }
+    }
+  }
+}
+
+// `id` may carry an exception (`GPL-2.0-only WITH Classpath-exception-2.0`); the policy can list
+// the combination or the license alone
+fn license_status(id: &str, policy: &LicenseConfig) -> LicenseStatus {
+  let base = id.split(" WITH ").next().unwrap_or(id);
+  let listed = |ids: &[String]| {
+    ids
+      .iter()
+      .any(|listed| listed.eq_ignore_ascii_case(id) || listed.eq_ignore_ascii_case(base))
+  };
+  if listed(&policy.deny) {
+    LicenseStatus::Denied
+  } else if listed(&policy.allow) {
+    LicenseStatus::Allowed
+  } else if base.starts_with("LicenseRef-") {
+    LicenseStatus::Unknown
+  } else if policy.allow.is_empty() {
+    LicenseStatus::Allowed
+  } else {
+    LicenseStatus::Unlisted
+  }
+}
+
+// `OR` binds loosest, then `AND`, then `WITH`
+fn parse_or(tokens: &[String], position: &mut usize) -> Option<Expression> {
+  let mut left = parse_and(tokens, position)?;
+  while tokens.get(*position).is_some_and(|t| t == "OR") {
+    *position += 1;
+    left = Expression::Or(Box::new(left), Box::new(parse_and(tokens, position)?));
+  }
+  Some(left)
+}
+
+fn parse_and(tokens: &[String], position: &mut usize) -> Option<Expression> {
+  let mut left = parse_term(tokens, position)?;
+  while tokens.get(*position).is_some_and(|t| t == "AND") {
+    *position += 1;
+    left = Expression::And(Box::new(left), Box::new(parse_term(tokens, position)?));
+  }
+  Some(left)
+}
+
+fn parse_term(tokens: &[String], position: &mut usize) -> Option<Expression> {
+  let token = tokens.get(*position)?;
+  *position += 1;
+  if token == "(" {
+    let inner = parse_or(tokens, position)?;
+    if tokens.get(*position)? != ")" {
+      return None;
+    }
+    *position += 1;
+    return Some(inner);
+  }
+  if matches!(token.as_str(), ")" | "AND" | "OR" | "WITH") {
+    return None;
+  }
+  if tokens.get(*position).is_some_and(|t| t == "WITH") {
+    let exception = tokens.get(*position + 1)?;
+    *position += 2;
+    return Some(Expression::License(format!("{} WITH {}", token, exception)));
+  }
+  Some(Expression::License(token.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::workspace::ManifestKind;
+
+  fn policy(allow: &[&str], deny: &[&str]) -> LicenseConfig {
+    normalized_policy(&LicenseConfig {
+      allow: allow.iter().map(|id| id.to_string()).collect(),
+      deny: deny.iter().map(|id| id.to_string()).collect(),
+      exceptions: Vec::new(),
+    })
+  }
+
+  #[test]
+  fn evaluates_single_licenses_against_the_policy() {
+    let policy = policy(&["MIT", "Apache 2.0"], &["GPL-3.0-only"]);
+    assert_eq!(evaluate("MIT", &policy), LicenseStatus::Allowed);
+    assert_eq!(evaluate("Apache-2.0", &policy), LicenseStatus::Allowed);
+    assert_eq!(evaluate("GPL-3.0-only", &policy), LicenseStatus::Denied);
+    assert_eq!(evaluate("ISC", &policy), LicenseStatus::Unlisted);
+    assert_eq!(evaluate("LicenseRef-BSD", &policy), LicenseStatus::Unknown);
+  }
+
+  #[test]
+  fn takes_the_best_side_of_or_and_the_worst_of_and() {
+    let policy = policy(&["MIT"], &["GPL-3.0-only"]);
+    assert_eq!(
+      evaluate("(MIT OR GPL-3.0-only)", &policy),
+      LicenseStatus::Allowed
+    );
+    assert_eq!(
+      evaluate("MIT AND GPL-3.0-only", &policy),
+      LicenseStatus::Denied
+    );
+    assert_eq!(evaluate("MIT AND ISC", &policy), LicenseStatus::Unlisted);
+    assert_eq!(
+      evaluate("ISC OR (MIT AND LicenseRef-x)", &policy),
+      LicenseStatus::Unknown
+    );
+  }
+
+  #[test]
+  fn matches_exceptions_by_combination_or_base_license() {
+    let policy = policy(
+      &["GPL-2.0-only WITH Classpath-exception-2.0"],
+      &["GPL-3.0-only"],
+    );
+    assert_eq!(
+      evaluate("GPL-2.0-only WITH Classpath-exception-2.0", &policy),
+      LicenseStatus::Allowed
+    );
+    assert_eq!(
+      evaluate("GPL-3.0-only WITH Classpath-exception-2.0", &policy),
+      LicenseStatus::Denied
+    );
+    assert_eq!(evaluate("GPL-2.0-only", &policy), LicenseStatus::Unlisted);
+  }
+
+  #[test]
+  fn allows_anything_not_denied_without_an_allow_list() {
+    let policy = policy(&[], &["AGPL-3.0-only"]);
+    assert_eq!(evaluate("ISC", &policy), LicenseStatus::Allowed);
+    assert_eq!(evaluate("AGPL-3.0-only", &policy), LicenseStatus::Denied);
+    assert_eq!(evaluate("LicenseRef-x", &policy), LicenseStatus::Unknown);
+  }
+
+  #[test]
+  fn treats_malformed_expressions_as_unknown() {
+    let policy = policy(&["MIT"], &[]);
+    assert_eq!(evaluate("MIT OR", &policy), LicenseStatus::Unknown);
+    assert_eq!(evaluate("(MIT", &policy), LicenseStatus::Unknown);
+    assert_eq!(evaluate("MIT ISC", &policy), LicenseStatus::Unknown);
+  }
+
+  #[test]
+  fn exempts_workspace_packages_by_name_and_version() {
+    let package = |name: &str, version: Option<&str>| Package {
+      name: name.to_string(),
+      version: version.map(String::from),
+      path: format!("/repo/packages/{}", name),
+      relative_path: format!("packages/{}", name),
+      manifest: ManifestKind::Npm,
+      private: false,
+      dependencies: Vec::new(),
+    };
+    let component = |name: &str, version: &str| Component {
+      name: name.to_string(),
+      version: version.to_string(),
+      manifest: ManifestKind::Npm,
+      license: None,
+      integrity: None,
+      dependencies: Vec::new(),
+    };
+    let workspace = Workspace {
+      root: "/repo".to_string(),
+      packages: vec![package("utils", Some("1.0.0")), package("app", None)],
+    };
+
+    let is_member = |name, version| is_workspace_component(&workspace, &component(name, version));
+    assert!(is_member("utils", "1.0.0"));
+    assert!(is_member("app", "0.0.0"));
+    // The registry `utils` is third-party code despite the shared name
+    assert!(!is_member("utils", "2.3.1"));
+  }
+}
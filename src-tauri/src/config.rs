@@ -0,0 +1,169 @@
+use crate::git;
+use crate::remote::Provider;
+use moka::sync::Cache;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Per-repository settings, read from `.mr-manager.json` at the repository root
+pub const CONFIG_FILE: &str = ".mr-manager.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct RepoConfig {
+  pub tags: TagConfig,
+  pub docs: DocsConfig,
+  pub remotes: RemoteConfig,
+  pub issues: IssueConfig,
+  pub tasks: TaskConfig,
+  pub licenses: LicenseConfig,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct TagConfig {
+  // `{name}` is the package name (scoped, unscoped or folder name), `{version}` a semver version
+  pub patterns: Vec<String>,
+}
+
+impl Default for TagConfig {
+  fn default() -> Self {
+    Self {
+      patterns: vec![
+        "{name}@{version}".to_string(),
+        "{name}-v{version}".to_string(),
+        "{name}/v{version}".to_string(),
+      ],
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PathScheme {
+  // `<root>/<package folder>`; packages sharing a folder name collide
+  Name,
+  // `<root>/<repository folder>/<path from the repository root>`, with the docs themselves in a
+  // `_docs` folder below it so a package's docs never contain those of packages nested in it
+  #[default]
+  Relative,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct DocsConfig {
+  // Defaults to `~/mr-manager`; a leading `~` expands to the home directory
+  pub output_root: Option<String>,
+  pub path_scheme: PathScheme,
+  // Builds kept per package, the current one included; 0 keeps all of them
+  pub keep: usize,
+}
+
+impl Default for DocsConfig {
+  fn default() -> Self {
+    Self {
+      output_root: None,
+      path_scheme: PathScheme::Relative,
+      keep: 5,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct RemoteConfig {
+  // Keyed by the host (or SSH alias) found in remote URLs, for self-hosted instances
+  pub hosts: HashMap<String, HostMapping>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HostMapping {
+  pub provider: Provider,
+  // Host serving the web UI when it differs from the remote's
+  #[serde(default)]
+  pub web_host: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct IssueConfig {
+  // Link for `#123` and `GH-123`, defaulting to the remote's issue tracker
+  pub url: Option<String>,
+  // Link templates by key prefix, e.g. `PROJ` for `PROJ-123`, or `*` for any prefix.
+  // `{key}`, `{prefix}` and `{number}` are substituted.
+  pub trackers: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct TaskConfig {
+  // Scripts whose results are cached and replayed; any other script always runs
+  pub cache: Vec<String>,
+  // Environment variables whose values are part of every task hash
+  pub env: Vec<String>,
+  // Globs, relative to each package, of the files tasks produce; restored on cache hits
+  pub outputs: Vec<String>,
+  // Defaults to the user cache directory; a leading `~` expands to the home directory
+  pub cache_dir: Option<String>,
+  // Least recently used results are evicted once the cache grows past this many MiB
+  pub max_cache_mb: u64,
+}
+
+impl Default for TaskConfig {
+  fn default() -> Self {
+    Self {
+      cache: Vec::new(),
+      env: Vec::new(),
+      outputs: vec!["dist/**".to_string(), "build/**".to_string()],
+      cache_dir: None,
+      max_cache_mb: 1024,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct LicenseConfig {
+  // SPDX identifiers dependencies may be used under; empty allows anything not denied
+  pub allow: Vec<String>,
+  pub deny: Vec<String>,
+  // Dependencies exempt from the policy, as `name` or `name@version`
+  pub exceptions: Vec<String>,
+}
+
+static CONFIG_CACHE: Lazy<Cache<String, Arc<RepoConfig>>> = Lazy::new(|| {
+  Cache::builder()
+    .time_to_live(Duration::from_secs(60))
+    .initial_capacity(10)
+    .build()
+});
+
+pub fn clear_cache() {
+  CONFIG_CACHE.invalidate_all();
+}
+
+#[tauri::command]
+pub fn get_repo_config(path: String) -> Result<RepoConfig, String> {
+  Ok(load_config(&path)?.as_ref().clone())
+}
+
+pub fn load_config(path: &str) -> Result<Arc<RepoConfig>, String> {
+  let git_root = git::find_git_root(Path::new(path)).ok_or("Could not find Git repository")?;
+  if let Some(cached) = CONFIG_CACHE.get(&git_root) {
+    return Ok(cached);
+  }
+
+  let config_path = Path::new(&git_root).join(CONFIG_FILE);
+  let config = match std::fs::read_to_string(&config_path) {
+    Ok(contents) => serde_json::from_str(&contents)
+      .map_err(|e| format!("Invalid {}: {}", config_path.display(), e))?,
+    Err(_) => RepoConfig::default(),
+  };
+
+  let config = Arc::new(config);
+  CONFIG_CACHE.insert(git_root, config.clone());
+  Ok(config)
+}
@@ -0,0 +1,524 @@
+use crate::workspace::{self, DependencyKind, ManifestKind, Package, Workspace};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Libraries that must resolve to a single copy at runtime, so packages using them declare peers
+const SINGLETON_LIBRARIES: &[&str] = &[
+  "react",
+  "react-dom",
+  "react-native",
+  "vue",
+  "svelte",
+  "preact",
+  "solid-js",
+  "@angular/core",
+];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DependencyUsage {
+  pub package: String,
+  pub kind: DependencyKind,
+  pub range: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RangeMismatch {
+  pub name: String,
+  pub manifest: ManifestKind,
+  pub usages: Vec<DependencyUsage>,
+  // Range with the highest minimum version, when every range could be read
+  pub suggested: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct InternalMismatch {
+  pub name: String,
+  pub version: String,
+  pub package: String,
+  pub kind: DependencyKind,
+  pub range: String,
+  pub suggested: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PeerCandidate {
+  pub name: String,
+  pub package: String,
+  pub range: String,
+  pub reason: String,
+}
+
+#[derive(Serialize)]
+pub struct ConsistencyReport {
+  pub mismatched: Vec<RangeMismatch>,
+  pub internal: Vec<InternalMismatch>,
+  pub peers: Vec<PeerCandidate>,
+  pub fixed: bool,
+}
+
+// With `fix`, mismatched ranges are aligned on the suggestion and internal references moved to the
+// workspace version; peer candidates are only reported
+#[tauri::command]
+pub fn check_dependency_consistency(
+  path: String,
+  fix: Option<bool>,
+) -> Result<ConsistencyReport, String> {
+  let workspace = workspace::load_workspace(&path)?;
+
+  let mismatched = mismatched_ranges(&workspace);
+  let internal = internal_mismatches(&workspace);
+  let peers = peer_candidates(&workspace);
+
+  let fixed = fix.unwrap_or(false);
+  if fixed {
+    apply_fixes(&workspace, &mismatched, &internal)?;
+    workspace::clear_cache();
+  }
+
+  Ok(ConsistencyReport {
+    mismatched,
+    internal,
+    peers,
+    fixed,
+  })
+}
+
+// External dependencies declared with more than one range. Peer ranges are left out, they are
+// meant to be wider than what the workspace installs
+fn mismatched_ranges(workspace: &Workspace) -> Vec<RangeMismatch> {
+  let mut usages: BTreeMap<(String, ManifestKind), Vec<DependencyUsage>> = BTreeMap::new();
+  for package in &workspace.packages {
+    for dependency in &package.dependencies {
+      if dependency.kind == DependencyKind::Peer
+        || is_internal(workspace, package, &dependency.name)
+        || is_protocol(&dependency.range)
+      {
+        continue;
+      }
+      usages
+        .entry((dependency.name.clone(), package.manifest))
+        .or_default()
+        .push(DependencyUsage {
+          package: package.name.clone(),
+          kind: dependency.kind,
+          range: dependency.range.clone(),
+        });
+    }
+  }
+
+  usages
+    .into_iter()
+    .filter(|(_, usages)| usages.iter().any(|u| u.range != usages[0].range))
+    .map(|((name, manifest), usages)| RangeMismatch {
+      suggested: suggested_range(&usages),
+      name,
+      manifest,
+      usages,
+    })
+    .collect()
+}
+
+fn suggested_range(usages: &[DependencyUsage]) -> Option<String> {
+  let floors = usages
+    .iter()
+    .map(|usage| range_floor(&usage.range).map(|floor| (floor, &usage.range)))
+    .collect::<Option<Vec<_>>>()?;
+
+  // Highest floor wins; on a tie, the range most packages already use
+  floors
+    .iter()
+    .max_by_key(|(floor, range)| {
+      let count = usages.iter().filter(|u| &u.range == *range).count();
+      (floor.clone(), count)
+    })
+    .map(|(_, range)| range.to_string())
+}
+
+// Internal packages referenced at a version other than the one in their manifest
+fn internal_mismatches(workspace: &Workspace) -> Vec<InternalMismatch> {
+  let mut mismatches = Vec::new();
+  for package in &workspace.packages {
+    for dependency in &package.dependencies {
+      if dependency.kind == DependencyKind::Peer || is_protocol(&dependency.range) {
+        continue;
+      }
+      let Some(target) = workspace
+        .packages
+        .iter()
+        .find(|p| p.name == dependency.name && p.manifest == package.manifest)
+      else {
+        continue;
+      };
+      let Some(version) = &target.version else {
+        continue;
+      };
+      let Ok(current) = semver::Version::parse(version) else {
+        continue;
+      };
+      let Some(floor) = range_floor(&dependency.range) else {
+        continue;
+      };
+      if floor == current {
+        continue;
+      }
+
+      let prefix = ["^", "~", "="]
+        .into_iter()
+        .find(|prefix| dependency.range.starts_with(prefix))
+        .unwrap_or_default();
+      mismatches.push(InternalMismatch {
+        name: target.name.clone(),
+        version: version.clone(),
+        package: package.name.clone(),
+        kind: dependency.kind,
+        range: dependency.range.clone(),
+        suggested: format!("{}{}", prefix, version),
+      });
+    }
+  }
+  mismatches
+}
+
+// Dev dependencies of published npm packages that other packages take as peers, or that are
+// singleton libraries, would be installed twice by consumers unless declared as peers
+fn peer_candidates(workspace: &Workspace) -> Vec<PeerCandidate> {
+  let mut candidates = Vec::new();
+  for package in &workspace.packages {
+    if package.manifest != ManifestKind::Npm || package.private {
+      continue;
+    }
+    for dependency in &package.dependencies {
+      if dependency.kind != DependencyKind::Dev
+        || is_internal(workspace, package, &dependency.name)
+        || package
+          .dependencies
+          .iter()
+          .any(|d| d.name == dependency.name && d.kind != DependencyKind::Dev)
+      {
+        continue;
+      }
+
+      let peer_of: Vec<&str> = workspace
+        .packages
+        .iter()
+        .filter(|other| other.name != package.name)
+        .filter(|other| {
+          other
+            .dependencies
+            .iter()
+            .any(|d| d.name == dependency.name && d.kind == DependencyKind::Peer)
+        })
+        .map(|other| other.name.as_str())
+        .collect();
+
+      let reason = if !peer_of.is_empty() {
+        format!("Peer dependency of {}", peer_of.join(", "))
+      } else if SINGLETON_LIBRARIES.contains(&dependency.name.as_str()) {
+        "Must resolve to a single copy in consumers".to_string()
+      } else {
+        continue;
+      };
+      candidates.push(PeerCandidate {
+        name: dependency.name.clone(),
+        package: package.name.clone(),
+        range: dependency.range.clone(),
+        reason,
+      });
+    }
+  }
+  candidates
+}
+
+fn apply_fixes(
+  workspace: &Workspace,
+  mismatched: &[RangeMismatch],
+  internal: &[InternalMismatch],
+) -> Result<(), String> {
+  let mut updates: Vec<(&str, &str, DependencyKind, &str)> = Vec::new();
+  for mismatch in mismatched {
+    let Some(suggested) = &mismatch.suggested else {
+      continue;
+    };
+    for usage in mismatch.usages.iter().filter(|u| &u.range != suggested) {
+      updates.push((&usage.package, &mismatch.name, usage.kind, suggested));
+    }
+  }
+  for mismatch in internal {
+    updates.push((
+      &mismatch.package,
+      &mismatch.name,
+      mismatch.kind,
+      &mismatch.suggested,
+    ));
+  }
+
+  for (package_name, dependency_name, kind, range) in updates {
+    let Some(package) = workspace.find(package_name) else {
+      continue;
+    };
+    let Some(dependency) = package
+      .dependencies
+      .iter()
+      .find(|d| d.name == dependency_name && d.kind == kind)
+    else {
+      continue;
+    };
+    workspace::set_dependency_range(package, dependency, range)?;
+  }
+  Ok(())
+}
+
+fn is_internal(workspace: &Workspace, package: &Package, name: &str) -> bool {
+  workspace
+    .packages
+    .iter()
+    .any(|p| p.name == name && p.manifest == package.manifest)
+}
+
+// `workspace:`, path, git and tag references have no version to compare
+fn is_protocol(range: &str) -> bool {
+  range.is_empty()
+    || range == "path"
+    || range == "workspace"
+    || range.contains(':')
+    || range.contains('/')
+    || range.starts_with(|c: char| c.is_ascii_alphabetic() && c != 'v')
+}
+
+// Lowest version a simple range accepts: `^1.2`, `~1.2.3`, `>=1`, `1.2.3`. Unions, hyphen and
+// compound ranges are not compared
+fn range_floor(range: &str) -> Option<semver::Version> {
+  let range = range.trim();
+  if range.contains("||") || range.contains(' ') || range.contains(',') {
+    return None;
+  }
+  let version = range
+    .trim_start_matches(['^', '~', '=', '>'])
+    .trim_start_matches('v');
+
+  let (core, pre) = match version.split_once('-') {
+    Some((core, pre)) => (core, Some(pre)),
+    None => (version, None),
+  };
+  let mut parts = core
+    .split('.')
+    .map(|part| part.parse::<u64>().ok())
+    .collect::<Option<Vec<_>>>()?;
+  if parts.is_empty() || parts.len() > 3 {
+    return None;
+  }
+  parts.resize(3, 0);
+
+  let mut floor = semver::Version::new(parts[0], parts[1], parts[2]);
+  if let Some(pre) = pre {
+    floor.pre = semver::Prerelease::new(pre).ok()?;
+  }
+  Some(floor)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::workspace::Dependency;
+
+  fn package(
+    name: &str,
+    version: &str,
+    private: bool,
+    dependencies: &[(&str, &str, DependencyKind)],
+  ) -> Package {
+    Package {
+      name: name.to_string(),
+      version: Some(version.to_string()),
+      path: format!("/repo/packages/{}", name),
+      relative_path: format!("packages/{}", name),
+      manifest: ManifestKind::Npm,
+      private,
+      dependencies: dependencies
+        .iter()
+        .map(|(name, range, kind)| Dependency {
+          name: name.to_string(),
+          key: name.to_string(),
+          range: range.to_string(),
+          kind: *kind,
+        })
+        .collect(),
+    }
+  }
+
+  fn workspace(packages: Vec<Package>) -> Workspace {
+    Workspace {
+      root: "/repo".to_string(),
+      packages,
+    }
+  }
+
+  fn usage(range: &str) -> DependencyUsage {
+    DependencyUsage {
+      package: "app".to_string(),
+      kind: DependencyKind::Normal,
+      range: range.to_string(),
+    }
+  }
+
+  #[test]
+  fn reads_range_floors() {
+    let cases = [
+      ("^1.2", Some("1.2.0")),
+      ("~1.2.3", Some("1.2.3")),
+      (">=1", Some("1.0.0")),
+      ("=v2.0.0", Some("2.0.0")),
+      ("1.0.0-beta.1", Some("1.0.0-beta.1")),
+      ("^1 || ^2", None),
+      (">=1 <2", None),
+      ("*", None),
+      ("1.2.3.4", None),
+    ];
+    for (range, expected) in cases {
+      let expected = expected.map(|v| semver::Version::parse(v).unwrap());
+      assert_eq!(range_floor(range), expected, "{}", range);
+    }
+  }
+
+  #[test]
+  fn recognizes_protocol_ranges() {
+    for range in [
+      "",
+      "path",
+      "workspace",
+      "workspace:^",
+      "file:../lib",
+      "github:o/r",
+      "latest",
+    ] {
+      assert!(is_protocol(range), "{}", range);
+    }
+    for range in ["^1.0.0", "v1.0.0", "*", ">=2"] {
+      assert!(!is_protocol(range), "{}", range);
+    }
+  }
+
+  #[test]
+  fn suggests_the_highest_floor_then_the_most_used_range() {
+    let usages = [usage("^1.2.0"), usage("^1.4.0"), usage("~1.3.0")];
+    assert_eq!(suggested_range(&usages).as_deref(), Some("^1.4.0"));
+
+    let usages = [usage("^1.2.0"), usage("^1.2"), usage("^1.2")];
+    assert_eq!(suggested_range(&usages).as_deref(), Some("^1.2"));
+
+    let usages = [usage("^1.2.0"), usage("1 - 2")];
+    assert_eq!(suggested_range(&usages), None);
+  }
+
+  #[test]
+  fn finds_mismatched_external_ranges() {
+    let workspace = workspace(vec![
+      package(
+        "app",
+        "1.0.0",
+        true,
+        &[
+          ("react", "^18.2.0", DependencyKind::Normal),
+          ("lodash", "^4.17.21", DependencyKind::Normal),
+          ("ui", "workspace:*", DependencyKind::Normal),
+        ],
+      ),
+      package(
+        "ui",
+        "1.0.0",
+        false,
+        &[
+          ("react", "^18.0.0", DependencyKind::Dev),
+          ("react-dom", "^17.0.0", DependencyKind::Peer),
+          ("lodash", "^4.17.21", DependencyKind::Normal),
+        ],
+      ),
+      package(
+        "docs",
+        "1.0.0",
+        true,
+        &[("react-dom", "^18.2.0", DependencyKind::Normal)],
+      ),
+    ]);
+
+    let mismatched = mismatched_ranges(&workspace);
+    assert_eq!(mismatched.len(), 1);
+    assert_eq!(mismatched[0].name, "react");
+    assert_eq!(mismatched[0].usages.len(), 2);
+    assert_eq!(mismatched[0].suggested.as_deref(), Some("^18.2.0"));
+  }
+
+  #[test]
+  fn finds_stale_internal_references() {
+    let workspace = workspace(vec![
+      package("lib", "1.2.0", false, &[]),
+      package(
+        "app",
+        "1.0.0",
+        true,
+        &[("lib", "^1.1.0", DependencyKind::Normal)],
+      ),
+      package(
+        "cli",
+        "1.0.0",
+        true,
+        &[("lib", "1.2.0", DependencyKind::Normal)],
+      ),
+      package(
+        "web",
+        "1.0.0",
+        true,
+        &[("lib", "workspace:^", DependencyKind::Normal)],
+      ),
+    ]);
+
+    let internal = internal_mismatches(&workspace);
+    assert_eq!(internal.len(), 1);
+    assert_eq!(internal[0].package, "app");
+    assert_eq!(internal[0].range, "^1.1.0");
+    assert_eq!(internal[0].suggested, "^1.2.0");
+  }
+
+  #[test]
+  fn suggests_peers_for_published_packages() {
+    let workspace = workspace(vec![
+      package(
+        "ui",
+        "1.0.0",
+        false,
+        &[
+          ("react", "^18.2.0", DependencyKind::Dev),
+          ("styled", "^6.0.0", DependencyKind::Dev),
+          ("vitest", "^1.0.0", DependencyKind::Dev),
+          ("vue", "^3.0.0", DependencyKind::Dev),
+          ("vue", "^3.0.0", DependencyKind::Normal),
+        ],
+      ),
+      package(
+        "theme",
+        "1.0.0",
+        false,
+        &[("styled", "^6.0.0", DependencyKind::Peer)],
+      ),
+      package(
+        "app",
+        "1.0.0",
+        true,
+        &[("react", "^18.2.0", DependencyKind::Dev)],
+      ),
+    ]);
+
+    let candidates = peer_candidates(&workspace);
+    let peers: Vec<(&str, &str, &str)> = candidates
+      .iter()
+      .map(|p| (p.package.as_str(), p.name.as_str(), p.reason.as_str()))
+      .collect();
+    assert_eq!(
+      peers,
+      vec![
+        ("ui", "react", "Must resolve to a single copy in consumers"),
+        ("ui", "styled", "Peer dependency of theme"),
+      ]
+    );
+  }
+}
@@ -0,0 +1,126 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use std::path::Path;
+
+// Same heuristic git uses: a NUL byte early in the content marks it as binary
+const BINARY_SNIFF_LEN: usize = 8000;
+
+pub fn is_binary(bytes: &[u8]) -> bool {
+  // UTF-16 text is full of NUL bytes but announces itself with a BOM
+  if Encoding::for_bom(bytes).is_some() {
+    return false;
+  }
+  bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0)
+}
+
+// Encoding of text from its BOM, UTF-8 when valid, or the most likely legacy encoding.
+// Also returns the length of the BOM, if any.
+pub fn detect_encoding(bytes: &[u8]) -> (&'static Encoding, usize) {
+  if let Some(found) = Encoding::for_bom(bytes) {
+    return found;
+  }
+
+  // A sample cut mid-character is still UTF-8
+  let valid = match std::str::from_utf8(bytes) {
+    Ok(_) => true,
+    Err(e) => e.error_len().is_none(),
+  };
+  if valid {
+    return (UTF_8, 0);
+  }
+
+  let mut detector = EncodingDetector::new();
+  detector.feed(bytes, true);
+  (detector.guess(None, true), 0)
+}
+
+// Decodes text, returning it together with the name of the encoding it was read as
+pub fn decode_text(bytes: &[u8]) -> (String, &'static str) {
+  let (encoding, bom_len) = detect_encoding(bytes);
+  (decode_with(encoding, &bytes[bom_len..]), encoding.name())
+}
+
+pub fn decode_with(encoding: &'static Encoding, bytes: &[u8]) -> String {
+  let (text, _) = encoding.decode_without_bom_handling(bytes);
+  text.into_owned()
+}
+
+// Bytes per code unit; offsets into text and line breaks are aligned to it
+pub fn code_unit_len(encoding: &'static Encoding) -> usize {
+  if encoding == UTF_16LE || encoding == UTF_16BE {
+    2
+  } else {
+    1
+  }
+}
+
+// Line feed as `encoding` stores it. Every other supported encoding is ASCII compatible and
+// never uses the byte inside a multi-byte character.
+pub fn newline(encoding: &'static Encoding) -> &'static [u8] {
+  if encoding == UTF_16LE {
+    &[b'\n', 0]
+  } else if encoding == UTF_16BE {
+    &[0, b'\n']
+  } else {
+    b"\n"
+  }
+}
+
+// Length of a chunk without a trailing, incomplete character
+pub fn char_boundary(encoding: &'static Encoding, bytes: &[u8]) -> usize {
+  if encoding == UTF_16LE || encoding == UTF_16BE {
+    return bytes.len() - bytes.len() % 2;
+  }
+  if encoding != UTF_8 {
+    return bytes.len();
+  }
+
+  let start = bytes.len().saturating_sub(3);
+  for i in (start..bytes.len()).rev() {
+    let width = match bytes[i] {
+      // Continuation byte, keep looking for the start of the character
+      0x80..=0xBF => continue,
+      0xC0..=0xDF => 2,
+      0xE0..=0xEF => 3,
+      0xF0..=0xFF => 4,
+      _ => 1,
+    };
+    return if bytes.len() - i < width {
+      i
+    } else {
+      bytes.len()
+    };
+  }
+  bytes.len()
+}
+
+// Content type from magic bytes, falling back to the file extension
+pub fn sniff_mime(path: &Path, bytes: &[u8]) -> &'static str {
+  infer::get(bytes)
+    .map(|kind| kind.mime_type())
+    .or_else(|| mime_from_extension(path))
+    .unwrap_or(if is_binary(bytes) {
+      "application/octet-stream"
+    } else {
+      "text/plain"
+    })
+}
+
+fn mime_from_extension(path: &Path) -> Option<&'static str> {
+  let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+  let mime = match extension.as_str() {
+    "json" | "map" => "application/json",
+    "js" | "mjs" | "cjs" | "jsx" => "text/javascript",
+    "ts" | "mts" | "cts" | "tsx" => "text/typescript",
+    "html" | "htm" => "text/html",
+    "css" => "text/css",
+    "md" | "markdown" => "text/markdown",
+    "svg" => "image/svg+xml",
+    "yaml" | "yml" => "application/yaml",
+    "toml" => "application/toml",
+    "xml" => "application/xml",
+    "csv" => "text/csv",
+    _ => return None,
+  };
+  Some(mime)
+}
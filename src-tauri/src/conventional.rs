@@ -0,0 +1,226 @@
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ConventionalCommit {
+  #[serde(rename = "type")]
+  pub kind: String,
+  pub scope: Option<String>,
+  pub breaking: bool,
+  pub description: String,
+  pub body: Option<String>,
+  pub footers: Vec<Footer>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Footer {
+  pub token: String,
+  pub value: String,
+}
+
+impl ConventionalCommit {
+  pub fn breaking_note(&self) -> Option<&str> {
+    self
+      .footers
+      .iter()
+      .find(|footer| is_breaking_token(&footer.token))
+      .map(|footer| footer.value.as_str())
+  }
+}
+
+// Parses a commit message following https://www.conventionalcommits.org/en/v1.0.0/
+// Returns None when the header is not in `type(scope)!: description` form.
+pub fn parse(message: &str) -> Option<ConventionalCommit> {
+  let message = message.trim();
+  let (header, rest) = match message.split_once('\n') {
+    Some((header, rest)) => (header.trim_end(), rest),
+    None => (message, ""),
+  };
+
+  let (prefix, description) = header.split_once(':')?;
+  let description = description.trim();
+  if description.is_empty() {
+    return None;
+  }
+
+  let (prefix, bang) = match prefix.strip_suffix('!') {
+    Some(prefix) => (prefix, true),
+    None => (prefix, false),
+  };
+
+  let (kind, scope) = match prefix.split_once('(') {
+    Some((kind, scope)) => {
+      let scope = scope.strip_suffix(')')?.trim();
+      if scope.is_empty() {
+        return None;
+      }
+      (kind, Some(scope.to_string()))
+    }
+    None => (prefix, None),
+  };
+
+  if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphanumeric()) {
+    return None;
+  }
+
+  let (body, footers) = parse_body(rest);
+  let breaking = bang
+    || footers
+      .iter()
+      .any(|footer| is_breaking_token(&footer.token));
+
+  Some(ConventionalCommit {
+    kind: kind.to_ascii_lowercase(),
+    scope,
+    breaking,
+    description: description.to_string(),
+    body,
+    footers,
+  })
+}
+
+fn is_breaking_token(token: &str) -> bool {
+  token == "BREAKING CHANGE" || token == "BREAKING-CHANGE"
+}
+
+// Splits a footer line into token and value, accepting both `Token: value` and `Token #value`.
+fn split_footer(line: &str) -> Option<(&str, &str)> {
+  for token in ["BREAKING CHANGE", "BREAKING-CHANGE"] {
+    if let Some(value) = line.strip_prefix(token).and_then(|v| v.strip_prefix(':')) {
+      return Some((token, value.trim()));
+    }
+  }
+
+  let (token, value) = if let Some((token, value)) = line.split_once(": ") {
+    (token, value)
+  } else {
+    let (token, _) = line.split_once(" #")?;
+    // Keep the `#` as part of the value, e.g. `Refs #123`
+    (token, &line[token.len() + 1..])
+  };
+
+  let valid_token =
+    !token.is_empty() && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+  if valid_token {
+    Some((token, value.trim()))
+  } else {
+    None
+  }
+}
+
+fn parse_body(rest: &str) -> (Option<String>, Vec<Footer>) {
+  let paragraphs: Vec<&str> = rest
+    .split("\n\n")
+    .map(|p| p.trim_matches('\n'))
+    .filter(|p| !p.trim().is_empty())
+    .collect();
+
+  let mut footers = Vec::new();
+  let mut body_paragraphs = paragraphs.as_slice();
+
+  if let Some((last, others)) = paragraphs.split_last() {
+    let first_line = last.lines().next().unwrap_or_default();
+    if split_footer(first_line).is_some() {
+      for line in last.lines() {
+        match split_footer(line) {
+          Some((token, value)) => footers.push(Footer {
+            token: token.to_string(),
+            value: value.to_string(),
+          }),
+          // Continuation of the previous footer value
+          None => {
+            if let Some(footer) = footers.last_mut() {
+              footer.value.push('\n');
+              footer.value.push_str(line.trim());
+            }
+          }
+        }
+      }
+      body_paragraphs = others;
+    }
+  }
+
+  let body = if body_paragraphs.is_empty() {
+    None
+  } else {
+    Some(body_paragraphs.join("\n\n"))
+  };
+
+  (body, footers)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_type_scope_and_description() {
+    let commit = parse("feat(ui): add dark mode").unwrap();
+    assert_eq!(commit.kind, "feat");
+    assert_eq!(commit.scope.as_deref(), Some("ui"));
+    assert_eq!(commit.description, "add dark mode");
+    assert!(!commit.breaking);
+    assert_eq!(commit.body, None);
+    assert!(commit.footers.is_empty());
+
+    let commit = parse("Fix: handle a: b").unwrap();
+    assert_eq!(commit.kind, "fix");
+    assert_eq!(commit.scope, None);
+    assert_eq!(commit.description, "handle a: b");
+  }
+
+  #[test]
+  fn marks_breaking_changes_from_bang_or_footer() {
+    let commit = parse("refactor(api)!: drop v1 routes").unwrap();
+    assert!(commit.breaking);
+    assert_eq!(commit.scope.as_deref(), Some("api"));
+    assert_eq!(commit.breaking_note(), None);
+
+    for token in ["BREAKING CHANGE", "BREAKING-CHANGE"] {
+      let message = format!("fix: read config\n\n{}: config.json is ignored", token);
+      let commit = parse(&message).unwrap();
+      assert!(commit.breaking, "{}", token);
+      assert_eq!(commit.breaking_note(), Some("config.json is ignored"));
+    }
+  }
+
+  #[test]
+  fn splits_body_and_footers() {
+    let commit = parse(
+      "feat!: new storage\n\nFirst paragraph.\n\nSecond paragraph.\n\n\
+       BREAKING CHANGE: data moves\n  to a new folder\nRefs #12\nReviewed-by: Sam",
+    )
+    .unwrap();
+    assert_eq!(
+      commit.body.as_deref(),
+      Some("First paragraph.\n\nSecond paragraph.")
+    );
+    let footers: Vec<(&str, &str)> = commit
+      .footers
+      .iter()
+      .map(|f| (f.token.as_str(), f.value.as_str()))
+      .collect();
+    assert_eq!(
+      footers,
+      vec![
+        ("BREAKING CHANGE", "data moves\nto a new folder"),
+        ("Refs", "#12"),
+        ("Reviewed-by", "Sam"),
+      ]
+    );
+  }
+
+  #[test]
+  fn rejects_non_conforming_subjects() {
+    for message in [
+      "Update readme",
+      "feat: ",
+      "feat(): empty scope",
+      "feat(ui: unclosed scope",
+      "fix bug: spaces in type",
+      ": no type",
+      "Merge branch 'main' into feature",
+    ] {
+      assert!(parse(message).is_none(), "{}", message);
+    }
+  }
+}
@@ -0,0 +1,358 @@
+use crate::config;
+use crate::docs;
+use git2::{DiffOptions, Oid, Repository, StatusOptions};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// Written next to generated docs to record what they were built from
+pub const BUILD_MANIFEST: &str = ".mr-manager-build.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BuildManifest {
+  pub generator: String,
+  pub commit: String,
+  pub tree: String,
+  pub built_at: i64,
+  // Built from uncommitted changes on top of `commit`, so the docs match no committed tree
+  #[serde(default)]
+  pub dirty: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SourceState {
+  pub commit: String,
+  pub tree: String,
+  pub dirty: bool,
+  pub source_path: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DocBuild {
+  pub path: String,
+  pub current: bool,
+  #[serde(flatten)]
+  pub manifest: BuildManifest,
+}
+
+#[derive(Serialize)]
+pub struct DocsStatus {
+  pub built: bool,
+  pub stale: bool,
+  pub built_from: Option<BuildManifest>,
+  pub current: Option<SourceState>,
+  pub changed_files: Vec<String>,
+}
+
+pub fn read_manifest(out_dir: &Path) -> Option<BuildManifest> {
+  let contents = std::fs::read_to_string(out_dir.join(BUILD_MANIFEST)).ok()?;
+  serde_json::from_str(&contents).ok()
+}
+
+pub fn write_manifest(out_dir: &Path, generator: &str, state: &SourceState) -> Result<(), String> {
+  let manifest = BuildManifest {
+    generator: generator.to_string(),
+    commit: state.commit.clone(),
+    tree: state.tree.clone(),
+    built_at: chrono::Utc::now().timestamp(),
+    dirty: state.dirty,
+  };
+  let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+  std::fs::write(out_dir.join(BUILD_MANIFEST), json)
+    .map_err(|e| format!("Failed to write build manifest: {}", e))
+}
+
+// Copies the build in `out_dir` into the history, named after the commit it was built from:
+// its pages (`pages_dir`), entry page and manifest, at the same paths relative to `out_dir`.
+// Builds from a dirty worktree are kept apart from the clean build of the same commit.
+// Builds without a manifest cannot be versioned and are simply replaced.
+pub fn archive_build(
+  out_dir: &Path,
+  pages_dir: &Path,
+  history_dir: &Path,
+) -> Result<Option<PathBuf>, String> {
+  let Some(manifest) = read_manifest(out_dir) else {
+    return Ok(None);
+  };
+
+  std::fs::create_dir_all(history_dir)
+    .map_err(|e| format!("Failed to create docs history: {}", e))?;
+  let mut name = manifest.commit[..manifest.commit.len().min(12)].to_string();
+  if manifest.dirty {
+    name.push_str("-dirty");
+  }
+  let target = history_dir.join(name);
+  if target.exists() {
+    std::fs::remove_dir_all(&target).map_err(|e| format!("Failed to replace old build: {}", e))?;
+  }
+  let archived = copy_build(out_dir, pages_dir, &target);
+  if let Err(error) = archived {
+    let _ = std::fs::remove_dir_all(&target);
+    return Err(format!("Failed to archive docs: {}", error));
+  }
+  Ok(Some(target))
+}
+
+// Puts an archived build back in place of a failed one
+pub fn restore_build(archived: &Path, out_dir: &Path, pages_dir: &Path) -> Result<(), String> {
+  if pages_dir.exists() {
+    std::fs::remove_dir_all(pages_dir).map_err(|e| e.to_string())?;
+  }
+  copy_dir(archived, out_dir).map_err(|e| e.to_string())
+}
+
+fn copy_build(out_dir: &Path, pages_dir: &Path, target: &Path) -> std::io::Result<()> {
+  std::fs::create_dir_all(target)?;
+  if pages_dir.is_dir() {
+    let pages = pages_dir.strip_prefix(out_dir).unwrap_or(Path::new(""));
+    copy_dir(pages_dir, &target.join(pages))?;
+  }
+  for file in ["index.html", BUILD_MANIFEST] {
+    if out_dir.join(file).is_file() {
+      std::fs::copy(out_dir.join(file), target.join(file))?;
+    }
+  }
+  Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+  std::fs::create_dir_all(to)?;
+  for entry in std::fs::read_dir(from)? {
+    let entry = entry?;
+    let target = to.join(entry.file_name());
+    if entry.file_type()?.is_dir() {
+      copy_dir(&entry.path(), &target)?;
+    } else {
+      std::fs::copy(entry.path(), target)?;
+    }
+  }
+  Ok(())
+}
+
+// Archived builds, newest first
+fn archived_builds(history_dir: &Path) -> Vec<(PathBuf, BuildManifest)> {
+  let Ok(entries) = std::fs::read_dir(history_dir) else {
+    return Vec::new();
+  };
+  let mut builds: Vec<(PathBuf, BuildManifest)> = entries
+    .filter_map(|e| e.ok())
+    .map(|e| e.path())
+    .filter_map(|path| read_manifest(&path).map(|manifest| (path, manifest)))
+    .collect();
+  builds.sort_by_key(|(_, manifest)| std::cmp::Reverse(manifest.built_at));
+  builds
+}
+
+// Removes archived builds beyond `keep`, which counts the current build; 0 keeps everything
+pub fn prune_builds(history_dir: &Path, keep: usize) -> Result<Vec<PathBuf>, String> {
+  if keep == 0 {
+    return Ok(Vec::new());
+  }
+
+  let mut removed = Vec::new();
+  for (path, _) in archived_builds(history_dir).into_iter().skip(keep - 1) {
+    std::fs::remove_dir_all(&path)
+      .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    removed.push(path);
+  }
+  Ok(removed)
+}
+
+#[tauri::command]
+pub fn list_doc_builds(path: String) -> Result<Vec<DocBuild>, String> {
+  let out_dir = docs::docs_output_dir(&path)?;
+  let history_dir = docs::docs_history_dir(&path)?;
+
+  let mut builds: Vec<DocBuild> = read_manifest(&out_dir)
+    .map(|manifest| DocBuild {
+      path: out_dir.to_string_lossy().into_owned(),
+      current: true,
+      manifest,
+    })
+    .into_iter()
+    .collect();
+  builds.extend(
+    archived_builds(&history_dir)
+      .into_iter()
+      .map(|(path, manifest)| DocBuild {
+        path: path.to_string_lossy().into_owned(),
+        current: false,
+        manifest,
+      }),
+  );
+  Ok(builds)
+}
+
+#[tauri::command]
+pub fn prune_doc_builds(path: String, keep: Option<usize>) -> Result<Vec<String>, String> {
+  let keep = match keep {
+    Some(keep) => keep,
+    None => config::load_config(&path)?.docs.keep,
+  };
+  let removed = prune_builds(&docs::docs_history_dir(&path)?, keep)?;
+  Ok(
+    removed
+      .iter()
+      .map(|path| path.to_string_lossy().into_owned())
+      .collect(),
+  )
+}
+
+// Documented sources of a package: its `src` folder when present, the package itself otherwise
+fn source_dir(package_dir: &Path) -> PathBuf {
+  let src = package_dir.join("src");
+  if src.is_dir() {
+    src
+  } else {
+    package_dir.to_path_buf()
+  }
+}
+
+fn open_repo(package_dir: &Path) -> Result<(Repository, String), String> {
+  let repo =
+    Repository::discover(package_dir).map_err(|e| format!("Failed to open repository: {}", e))?;
+  let workdir = repo
+    .workdir()
+    .ok_or("Repository has no working directory")?
+    .canonicalize()
+    .map_err(|e| e.to_string())?;
+  let source = source_dir(package_dir)
+    .canonicalize()
+    .map_err(|e| e.to_string())?;
+
+  let relative = source
+    .strip_prefix(&workdir)
+    .map_err(|_| "Failed to get relative path".to_string())?
+    .to_string_lossy()
+    .replace('\\', "/");
+  Ok((repo, relative))
+}
+
+// Commit and tree hash the package sources currently resolve to
+pub fn source_state(package_dir: &Path) -> Result<SourceState, String> {
+  let (repo, relative) = open_repo(package_dir)?;
+  let head = repo
+    .head()
+    .and_then(|h| h.peel_to_commit())
+    .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+  let tree = head.tree().map_err(|e| e.to_string())?;
+
+  let tree_id = if relative.is_empty() {
+    tree.id()
+  } else {
+    tree
+      .get_path(Path::new(&relative))
+      .map(|entry| entry.id())
+      .unwrap_or_else(|_| Oid::zero())
+  };
+
+  let mut options = StatusOptions::new();
+  options.include_untracked(true).include_ignored(false);
+  if !relative.is_empty() {
+    options.pathspec(&relative);
+  }
+  let dirty = repo
+    .statuses(Some(&mut options))
+    .map(|statuses| !statuses.is_empty())
+    .unwrap_or(true);
+
+  Ok(SourceState {
+    commit: head.id().to_string(),
+    tree: tree_id.to_string(),
+    dirty,
+    source_path: relative,
+  })
+}
+
+// Whether existing docs in `out_dir` already match the package sources
+pub fn is_up_to_date(out_dir: &Path, generator: &str, state: &SourceState) -> bool {
+  !state.dirty
+    && out_dir.join("index.html").exists()
+    && read_manifest(out_dir).is_some_and(|manifest| {
+      !manifest.dirty && manifest.generator == generator && manifest.tree == state.tree
+    })
+}
+
+#[tauri::command]
+pub fn get_docs_status(path: String) -> Result<DocsStatus, String> {
+  let out_dir = docs::docs_output_dir(&path)?;
+  let manifest = read_manifest(&out_dir);
+  let built = out_dir.join("index.html").exists();
+  let current = source_state(Path::new(&path)).ok();
+
+  let (stale, changed_files) = match (&manifest, &current) {
+    (Some(manifest), Some(current)) => {
+      let mut changed =
+        changed_files(Path::new(&path), &manifest.commit, current).unwrap_or_default();
+      if current.dirty {
+        changed.extend(uncommitted_files(Path::new(&path), current).unwrap_or_default());
+        changed.sort();
+        changed.dedup();
+      }
+      (
+        manifest.tree != current.tree || manifest.dirty || current.dirty,
+        changed,
+      )
+    }
+    _ => (true, Vec::new()),
+  };
+
+  Ok(DocsStatus {
+    built,
+    stale: !built || stale,
+    built_from: manifest,
+    current,
+    changed_files,
+  })
+}
+
+fn changed_files(
+  package_dir: &Path,
+  built_commit: &str,
+  current: &SourceState,
+) -> Result<Vec<String>, String> {
+  let (repo, _) = open_repo(package_dir)?;
+  let old_commit = Oid::from_str(built_commit)
+    .and_then(|oid| repo.find_commit(oid))
+    .map_err(|e| e.to_string())?;
+  let new_commit = Oid::from_str(&current.commit)
+    .and_then(|oid| repo.find_commit(oid))
+    .map_err(|e| e.to_string())?;
+
+  let old_tree = old_commit.tree().map_err(|e| e.to_string())?;
+  let new_tree = new_commit.tree().map_err(|e| e.to_string())?;
+
+  let mut options = DiffOptions::new();
+  if !current.source_path.is_empty() {
+    options.pathspec(&current.source_path);
+  }
+  let diff = repo
+    .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut options))
+    .map_err(|e| e.to_string())?;
+
+  Ok(
+    diff
+      .deltas()
+      .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+      .map(|path| path.to_string_lossy().into_owned())
+      .collect(),
+  )
+}
+
+fn uncommitted_files(package_dir: &Path, current: &SourceState) -> Result<Vec<String>, String> {
+  let (repo, _) = open_repo(package_dir)?;
+  let mut options = StatusOptions::new();
+  options.include_untracked(true).include_ignored(false);
+  if !current.source_path.is_empty() {
+    options.pathspec(&current.source_path);
+  }
+  let statuses = repo
+    .statuses(Some(&mut options))
+    .map_err(|e| e.to_string())?;
+
+  Ok(
+    statuses
+      .iter()
+      .filter_map(|entry| entry.path().map(String::from))
+      .collect(),
+  )
+}
@@ -0,0 +1,561 @@
+use crate::config::{self, PathScheme};
+use crate::doc_builds;
+use crate::git;
+use crate::jobs;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use tauri::AppHandle;
+
+// A documentation tool the pipeline can run for a package
+pub trait DocGenerator: Sync {
+  fn id(&self) -> &'static str;
+
+  fn detect(&self, package_dir: &Path) -> bool;
+
+  fn command(&self, package_dir: &Path, out_dir: &Path) -> Command;
+
+  // Generated entry page; a redirecting `index.html` is written when it is not `out_dir/index.html`
+  fn entry_point(&self, _package_dir: &Path, out_dir: &Path) -> PathBuf {
+    out_dir.join("index.html")
+  }
+
+  // Generated pages, copied into the history with each build; anything else in `out_dir`,
+  // such as a build cache, stays in place
+  fn pages_dir(&self, _package_dir: &Path, out_dir: &Path) -> PathBuf {
+    out_dir.to_path_buf()
+  }
+
+  // Post-processing for tools that print their output instead of writing files
+  fn finish(&self, _package_dir: &Path, _out_dir: &Path, _output: &Output) -> Result<(), String> {
+    Ok(())
+  }
+}
+
+// Checked in order: the first generator detecting a package is its default
+static GENERATORS: &[&dyn DocGenerator] = &[&Typedoc, &Jsdoc, &CargoDoc, &Sphinx, &Pdoc, &GoDoc];
+
+#[derive(Serialize)]
+pub struct DocsBuild {
+  pub path: String,
+  pub generator: &'static str,
+  pub skipped: bool,
+}
+
+#[derive(Serialize)]
+pub struct GeneratorInfo {
+  pub id: &'static str,
+  pub detected: bool,
+}
+
+// Previous builds live apart from the current docs, which generators may wipe
+const HISTORY_DIR: &str = ".history";
+const SBOM_DIR: &str = ".sbom";
+// Combined monorepo index pages, kept out of every package's docs folder
+const INDEX_DIR: &str = ".index";
+// Leaf holding a package's docs under the `relative` scheme
+const PACKAGE_DOCS_DIR: &str = "_docs";
+
+pub fn docs_output_dir(path: &str) -> Result<PathBuf, String> {
+  let (root, key) = output_location(path)?;
+  let scheme = config::load_config(path)
+    .map(|c| c.docs.path_scheme)
+    .unwrap_or_default();
+  Ok(match scheme {
+    PathScheme::Name => root.join(key),
+    PathScheme::Relative => root.join(key).join(PACKAGE_DOCS_DIR),
+  })
+}
+
+pub fn docs_output_root(path: &str) -> Result<PathBuf, String> {
+  output_location(path).map(|(root, _)| root)
+}
+
+pub fn docs_history_dir(path: &str) -> Result<PathBuf, String> {
+  let (root, key) = output_location(path)?;
+  Ok(root.join(HISTORY_DIR).join(key))
+}
+
+pub fn docs_index_dir(path: &str) -> Result<PathBuf, String> {
+  let (root, key) = output_location(path)?;
+  Ok(root.join(INDEX_DIR).join(key))
+}
+
+pub fn sbom_output_dir(path: &str) -> Result<PathBuf, String> {
+  let (root, key) = output_location(path)?;
+  Ok(root.join(SBOM_DIR).join(key))
+}
+
+// Output root and the package's path below it, following the repository's docs config
+fn output_location(path: &str) -> Result<(PathBuf, PathBuf), String> {
+  let folder_name = Path::new(path)
+    .file_name()
+    .ok_or_else(|| "Invalid path".to_string())?
+    .to_str()
+    .ok_or_else(|| "Invalid project name".to_string())?;
+
+  // Folders outside a repository have no config and use the defaults
+  let git_root = git::find_git_root(Path::new(path)).map(PathBuf::from);
+  let docs_config = config::load_config(path)
+    .map(|c| c.docs.clone())
+    .unwrap_or_default();
+
+  let root = match docs_config.output_root.as_deref() {
+    Some(root) => resolve_root(root, git_root.as_deref())?,
+    None => default_output_root()?,
+  };
+
+  let key = match docs_config.path_scheme {
+    PathScheme::Name => None,
+    PathScheme::Relative => git_root.and_then(|root| relative_key(&root, Path::new(path))),
+  };
+  Ok((root, key.unwrap_or_else(|| PathBuf::from(folder_name))))
+}
+
+pub fn default_output_root() -> Result<PathBuf, String> {
+  Ok(
+    dirs::home_dir()
+      .ok_or_else(|| "Could not find home directory".to_string())?
+      .join("mr-manager"),
+  )
+}
+
+// `~` expands to the home directory; relative roots are resolved against the repository
+pub fn resolve_root(root: &str, git_root: Option<&Path>) -> Result<PathBuf, String> {
+  if let Some(rest) = root.strip_prefix('~') {
+    let home = dirs::home_dir().ok_or_else(|| "Could not find home directory".to_string())?;
+    return Ok(home.join(rest.trim_start_matches(['/', '\\'])));
+  }
+
+  let root = PathBuf::from(root);
+  match git_root {
+    Some(git_root) if root.is_relative() => Ok(git_root.join(root)),
+    _ => Ok(root),
+  }
+}
+
+fn relative_key(git_root: &Path, package_dir: &Path) -> Option<PathBuf> {
+  let git_root = git_root.canonicalize().ok()?;
+  let package_dir = package_dir.canonicalize().ok()?;
+  let relative = package_dir.strip_prefix(&git_root).ok()?;
+  Some(Path::new(git_root.file_name()?).join(relative))
+}
+
+#[tauri::command]
+pub fn get_docs_output_dir(path: String) -> Result<String, String> {
+  docs_output_dir(&path).map(|dir| dir.to_string_lossy().into_owned())
+}
+
+pub fn find_generator(
+  package_dir: &Path,
+  id: Option<&str>,
+) -> Result<&'static dyn DocGenerator, String> {
+  match id {
+    Some(id) => GENERATORS
+      .iter()
+      .find(|g| g.id() == id)
+      .copied()
+      .ok_or_else(|| format!("Unknown doc generator: {}", id)),
+    None => GENERATORS
+      .iter()
+      .find(|g| g.detect(package_dir))
+      .copied()
+      .ok_or_else(|| "No documentation generator detected for this package".to_string()),
+  }
+}
+
+#[tauri::command]
+pub fn list_doc_generators(path: String) -> Vec<GeneratorInfo> {
+  let package_dir = Path::new(&path);
+  GENERATORS
+    .iter()
+    .map(|g| GeneratorInfo {
+      id: g.id(),
+      detected: g.detect(package_dir),
+    })
+    .collect()
+}
+
+#[tauri::command]
+pub async fn generate_docs(
+  path: String,
+  generator: Option<String>,
+  force: Option<bool>,
+) -> Result<String, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let force = force.unwrap_or(false);
+    build_docs(&path, generator.as_deref(), force, |mut command| {
+      command.output().map_err(|e| e.to_string())
+    })
+    .map(|build| build.path)
+  })
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+// Runs documentation generation as a background job streaming its output
+#[tauri::command]
+pub fn start_docs_job(
+  app: AppHandle,
+  path: String,
+  generator: Option<String>,
+  force: Option<bool>,
+) -> Result<String, String> {
+  find_generator(Path::new(&path), generator.as_deref())?;
+  let force = force.unwrap_or(false);
+
+  let label = path.clone();
+  Ok(jobs::spawn(&app, "docs", &label, move |app, job_id| {
+    build_docs(&path, generator.as_deref(), force, |command| {
+      jobs::run_command(app, job_id, command)
+    })
+    .map(|build| Some(build.path))
+  }))
+}
+
+// Generates docs for a package, with `run` deciding how the generator process is executed.
+// Unless forced, the build is skipped when the sources match the last recorded build.
+pub fn build_docs<F>(
+  path: &str,
+  generator: Option<&str>,
+  force: bool,
+  run: F,
+) -> Result<DocsBuild, String>
+where
+  F: FnOnce(Command) -> Result<Output, String>,
+{
+  let package_dir = Path::new(path);
+  let generator = find_generator(package_dir, generator)?;
+
+  let docs_path = docs_output_dir(path)?;
+  let docs_path_str = docs_path
+    .to_str()
+    .map(String::from)
+    .ok_or_else(|| "Invalid path".to_string())?;
+
+  let state = doc_builds::source_state(package_dir).ok();
+  if let Some(state) = &state {
+    if !force && doc_builds::is_up_to_date(&docs_path, generator.id(), state) {
+      return Ok(DocsBuild {
+        path: docs_path_str,
+        generator: generator.id(),
+        skipped: true,
+      });
+    }
+  }
+
+  // The previous build is copied into the history and comes back if this one fails
+  let history_path = docs_history_dir(path)?;
+  let previous = doc_builds::read_manifest(&docs_path)
+    .and_then(|manifest| find_generator(package_dir, Some(&manifest.generator)).ok())
+    .unwrap_or(generator);
+  let previous_pages = previous.pages_dir(package_dir, &docs_path);
+  let archived = doc_builds::archive_build(&docs_path, &previous_pages, &history_path)?;
+  let built = run_generator(generator, package_dir, &docs_path, run);
+  if let Err(error) = built {
+    if let Some(archived) = archived {
+      let _ = doc_builds::restore_build(&archived, &docs_path, &previous_pages);
+    }
+    return Err(error);
+  }
+
+  if let Some(state) = &state {
+    doc_builds::write_manifest(&docs_path, generator.id(), state)?;
+  }
+  let keep = config::load_config(path)
+    .map(|c| c.docs.keep)
+    .unwrap_or_else(|_| config::DocsConfig::default().keep);
+  doc_builds::prune_builds(&history_path, keep)?;
+
+  Ok(DocsBuild {
+    path: docs_path_str,
+    generator: generator.id(),
+    skipped: false,
+  })
+}
+
+fn run_generator<F>(
+  generator: &dyn DocGenerator,
+  package_dir: &Path,
+  docs_path: &Path,
+  run: F,
+) -> Result<(), String>
+where
+  F: FnOnce(Command) -> Result<Output, String>,
+{
+  std::fs::create_dir_all(docs_path)
+    .map_err(|e| format!("Failed to create docs directory: {}", e))?;
+
+  let mut command = generator.command(package_dir, docs_path);
+  command.current_dir(package_dir);
+  let output = run(command)?;
+
+  if !output.status.success() {
+    let error = String::from_utf8_lossy(&output.stderr);
+    return Err(error.into_owned());
+  }
+
+  finish_build(generator, package_dir, docs_path, &output)
+}
+
+pub fn finish_build(
+  generator: &dyn DocGenerator,
+  package_dir: &Path,
+  out_dir: &Path,
+  output: &Output,
+) -> Result<(), String> {
+  generator.finish(package_dir, out_dir, output)?;
+
+  let entry_point = generator.entry_point(package_dir, out_dir);
+  let index = out_dir.join("index.html");
+  if entry_point != index {
+    let target = entry_point.strip_prefix(out_dir).unwrap_or(&entry_point);
+    let target = target.to_string_lossy().replace('\\', "/");
+    let redirect = format!(
+      "<!DOCTYPE html><meta http-equiv=\"refresh\" content=\"0; url={0}\"><a href=\"{0}\">{0}</a>\n",
+      target
+    );
+    std::fs::write(&index, redirect).map_err(|e| format!("Failed to write index: {}", e))?;
+  }
+  Ok(())
+}
+
+pub fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+fn first_existing(dir: &Path, candidates: &[&str]) -> Option<PathBuf> {
+  candidates
+    .iter()
+    .map(|c| dir.join(c))
+    .find(|path| path.exists())
+}
+
+fn npx(args: &[&str], out_dir: &Path) -> Command {
+  let mut command = Command::new("npx");
+  command.args(args).arg(out_dir);
+  command
+}
+
+struct Typedoc;
+
+impl DocGenerator for Typedoc {
+  fn id(&self) -> &'static str {
+    "typedoc"
+  }
+
+  fn detect(&self, package_dir: &Path) -> bool {
+    package_dir.join("tsconfig.json").exists()
+  }
+
+  fn command(&self, package_dir: &Path, out_dir: &Path) -> Command {
+    let config = first_existing(
+      package_dir,
+      &[
+        "typedoc.json",
+        "typedoc.jsonc",
+        "typedoc.config.js",
+        "typedoc.config.cjs",
+        "typedoc.config.mjs",
+      ],
+    );
+
+    match config {
+      Some(config) => {
+        let config = config.to_string_lossy().into_owned();
+        npx(&["typedoc", "--options", &config, "--out"], out_dir)
+      }
+      None => npx(
+        &["typedoc", "--entryPointStrategy", "Expand", "src", "--out"],
+        out_dir,
+      ),
+    }
+  }
+}
+
+struct Jsdoc;
+
+impl DocGenerator for Jsdoc {
+  fn id(&self) -> &'static str {
+    "jsdoc"
+  }
+
+  fn detect(&self, package_dir: &Path) -> bool {
+    package_dir.join("package.json").exists()
+  }
+
+  fn command(&self, package_dir: &Path, out_dir: &Path) -> Command {
+    let config = first_existing(
+      package_dir,
+      &["jsdoc.json", ".jsdoc.json", "jsdoc.conf.json", "conf.json"],
+    );
+
+    match config {
+      Some(config) => {
+        let config = config.to_string_lossy().into_owned();
+        npx(&["jsdoc", "-c", &config, "-r", "src", "-d"], out_dir)
+      }
+      None => npx(&["jsdoc", "-r", "src", "-d"], out_dir),
+    }
+  }
+}
+
+struct CargoDoc;
+
+impl CargoDoc {
+  fn crate_name(package_dir: &Path) -> String {
+    crate::workspace::read_toml(&package_dir.join("Cargo.toml"))
+      .and_then(|doc| {
+        let package = doc.get("package")?;
+        // Library target names can differ from the package name
+        let name = doc
+          .get("lib")
+          .and_then(|lib| lib.get("name"))
+          .or_else(|| package.get("name"))?;
+        name.as_str().map(String::from)
+      })
+      .unwrap_or_default()
+      .replace('-', "_")
+  }
+}
+
+impl DocGenerator for CargoDoc {
+  fn id(&self) -> &'static str {
+    "cargo-doc"
+  }
+
+  fn detect(&self, package_dir: &Path) -> bool {
+    package_dir.join("Cargo.toml").exists()
+  }
+
+  fn command(&self, _package_dir: &Path, out_dir: &Path) -> Command {
+    let mut command = Command::new("cargo");
+    command
+      .args(["doc", "--no-deps", "--target-dir"])
+      .arg(out_dir.join("target"));
+    command
+  }
+
+  fn entry_point(&self, package_dir: &Path, out_dir: &Path) -> PathBuf {
+    out_dir
+      .join("target")
+      .join("doc")
+      .join(Self::crate_name(package_dir))
+      .join("index.html")
+  }
+
+  // The rest of `target` is cargo's incremental state
+  fn pages_dir(&self, _package_dir: &Path, out_dir: &Path) -> PathBuf {
+    out_dir.join("target").join("doc")
+  }
+}
+
+struct Sphinx;
+
+impl Sphinx {
+  fn source_dir(package_dir: &Path) -> Option<PathBuf> {
+    ["docs", "doc", "docs/source", "doc/source", "."]
+      .iter()
+      .map(|dir| package_dir.join(dir))
+      .find(|dir| dir.join("conf.py").exists())
+  }
+}
+
+impl DocGenerator for Sphinx {
+  fn id(&self) -> &'static str {
+    "sphinx"
+  }
+
+  fn detect(&self, package_dir: &Path) -> bool {
+    Self::source_dir(package_dir).is_some()
+  }
+
+  fn command(&self, package_dir: &Path, out_dir: &Path) -> Command {
+    let source = Self::source_dir(package_dir).unwrap_or_else(|| package_dir.join("docs"));
+    let mut command = Command::new("sphinx-build");
+    command.args(["-b", "html"]).arg(source).arg(out_dir);
+    command
+  }
+}
+
+struct Pdoc;
+
+impl Pdoc {
+  // Importable module of the package, supporting both `src/` and flat layouts
+  fn module(package_dir: &Path) -> String {
+    for base in ["src", "."] {
+      let Ok(entries) = std::fs::read_dir(package_dir.join(base)) else {
+        continue;
+      };
+      let module = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|p| p.join("__init__.py").exists());
+      if let Some(module) = module {
+        let relative = module.strip_prefix(package_dir).unwrap_or(&module);
+        return relative.to_string_lossy().into_owned();
+      }
+    }
+
+    package_dir
+      .file_name()
+      .map(|n| n.to_string_lossy().replace('-', "_"))
+      .unwrap_or_default()
+  }
+}
+
+impl DocGenerator for Pdoc {
+  fn id(&self) -> &'static str {
+    "pdoc"
+  }
+
+  fn detect(&self, package_dir: &Path) -> bool {
+    first_existing(package_dir, &["pyproject.toml", "setup.py", "setup.cfg"]).is_some()
+  }
+
+  fn command(&self, package_dir: &Path, out_dir: &Path) -> Command {
+    let mut command = Command::new("pdoc");
+    command
+      .arg("-o")
+      .arg(out_dir)
+      .arg(Self::module(package_dir));
+    command
+  }
+}
+
+struct GoDoc;
+
+impl DocGenerator for GoDoc {
+  fn id(&self) -> &'static str {
+    "godoc"
+  }
+
+  fn detect(&self, package_dir: &Path) -> bool {
+    package_dir.join("go.mod").exists()
+  }
+
+  fn command(&self, _package_dir: &Path, _out_dir: &Path) -> Command {
+    let mut command = Command::new("go");
+    command.args(["doc", "-all", "."]);
+    command
+  }
+
+  // `go doc` prints plain text; wrap it in a page the preview can load
+  fn finish(&self, package_dir: &Path, out_dir: &Path, output: &Output) -> Result<(), String> {
+    let title = package_dir
+      .file_name()
+      .map(|n| n.to_string_lossy().into_owned())
+      .unwrap_or_default();
+    let text = escape_html(&String::from_utf8_lossy(&output.stdout));
+    let html = format!(
+      "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title></head>\n<body><pre>{}</pre></body></html>\n",
+      title, text
+    );
+    std::fs::write(out_dir.join("index.html"), html)
+      .map_err(|e| format!("Failed to write docs: {}", e))
+  }
+}
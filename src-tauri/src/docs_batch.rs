@@ -0,0 +1,263 @@
+use crate::doc_builds;
+use crate::docs::{self, escape_html};
+use crate::workspace::{self, Package, Workspace};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+pub const DEFAULT_CONCURRENCY: usize = 2;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildOutcome {
+  Built,
+  Skipped,
+  Failed,
+  Unsupported,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PackageDocsResult {
+  pub name: String,
+  pub path: String,
+  pub docs_path: Option<String>,
+  pub generator: Option<&'static str>,
+  pub outcome: BuildOutcome,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BatchDocsResult {
+  pub index: String,
+  pub packages: Vec<PackageDocsResult>,
+}
+
+// Builds docs for all (or the selected) workspace packages in parallel and refreshes the landing page
+#[tauri::command]
+pub async fn generate_all_docs(
+  path: String,
+  packages: Option<Vec<String>>,
+  concurrency: Option<usize>,
+  force: Option<bool>,
+) -> Result<BatchDocsResult, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    build_all(
+      &path,
+      packages.as_deref(),
+      concurrency.unwrap_or(DEFAULT_CONCURRENCY),
+      force.unwrap_or(false),
+    )
+  })
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+// Folder of the combined landing page `generate_all_docs` writes for the workspace of `path`
+#[tauri::command]
+pub fn get_docs_index_dir(path: String) -> Result<String, String> {
+  let workspace = workspace::load_workspace(&path)?;
+  docs::docs_index_dir(&workspace.root).map(|dir| dir.to_string_lossy().into_owned())
+}
+
+pub fn build_all(
+  path: &str,
+  selection: Option<&[String]>,
+  concurrency: usize,
+  force: bool,
+) -> Result<BatchDocsResult, String> {
+  let workspace = workspace::load_workspace(path)?;
+  let selected: Vec<&Package> = match selection {
+    Some(names) => names
+      .iter()
+      .map(|name| {
+        workspace
+          .packages
+          .iter()
+          .find(|p| &p.name == name || &p.relative_path == name || &p.path == name)
+          .ok_or_else(|| format!("Unknown package: {}", name))
+      })
+      .collect::<Result<_, _>>()?,
+    None => workspace.packages.iter().collect(),
+  };
+
+  // Packages sharing an output folder (possible with the `name` scheme) would overwrite each
+  // other's docs, so only the first of them is built
+  let mut owners: HashMap<PathBuf, &str> = HashMap::new();
+  let mut collisions = Vec::new();
+  let mut buildable = Vec::new();
+  for package in selected {
+    let Ok(docs_dir) = docs::docs_output_dir(&package.path) else {
+      buildable.push(package);
+      continue;
+    };
+    match owners.get(&docs_dir) {
+      Some(owner) => collisions.push(PackageDocsResult {
+        outcome: BuildOutcome::Failed,
+        error: Some(format!(
+          "Docs output {} is already used by {}; use the relative path scheme",
+          docs_dir.display(),
+          owner
+        )),
+        ..unbuilt(package)
+      }),
+      None => {
+        owners.insert(docs_dir, &package.name);
+        buildable.push(package);
+      }
+    }
+  }
+
+  let pool = rayon::ThreadPoolBuilder::new()
+    .num_threads(concurrency.max(1))
+    .build()
+    .map_err(|e| e.to_string())?;
+  let mut packages: Vec<PackageDocsResult> = pool.install(|| {
+    buildable
+      .par_iter()
+      .map(|p| build_package(p, force))
+      .collect()
+  });
+  packages.extend(collisions);
+
+  let index = write_index(&workspace)?;
+  Ok(BatchDocsResult {
+    index: index.to_string_lossy().into_owned(),
+    packages,
+  })
+}
+
+fn unbuilt(package: &Package) -> PackageDocsResult {
+  PackageDocsResult {
+    name: package.name.clone(),
+    path: package.path.clone(),
+    docs_path: None,
+    generator: None,
+    outcome: BuildOutcome::Unsupported,
+    error: None,
+  }
+}
+
+fn build_package(package: &Package, force: bool) -> PackageDocsResult {
+  let mut result = unbuilt(package);
+
+  if docs::find_generator(Path::new(&package.path), None).is_err() {
+    return result;
+  }
+
+  let build = docs::build_docs(&package.path, None, force, |mut command| {
+    command.output().map_err(|e| e.to_string())
+  });
+  match build {
+    Ok(build) => {
+      result.outcome = if build.skipped {
+        BuildOutcome::Skipped
+      } else {
+        BuildOutcome::Built
+      };
+      result.generator = Some(build.generator);
+      result.docs_path = Some(build.path);
+    }
+    Err(error) => {
+      result.outcome = BuildOutcome::Failed;
+      result.error = Some(error);
+    }
+  }
+  result
+}
+
+// Landing page linking every package that has generated docs. It lives in its own folder,
+// since the workspace root may be a package whose docs folder it would otherwise overwrite.
+fn write_index(workspace: &Workspace) -> Result<PathBuf, String> {
+  let index_dir = docs::docs_index_dir(&workspace.root)?;
+  std::fs::create_dir_all(&index_dir)
+    .map_err(|e| format!("Failed to create docs directory: {}", e))?;
+
+  let project = Path::new(&workspace.root)
+    .file_name()
+    .map(|n| n.to_string_lossy().into_owned())
+    .unwrap_or_default();
+
+  let mut rows = String::new();
+  let mut listed = HashSet::new();
+  for package in &workspace.packages {
+    let Ok(docs_dir) = docs::docs_output_dir(&package.path) else {
+      continue;
+    };
+    // A shared output folder holds the docs of whichever package built first
+    if !docs_dir.join("index.html").exists() || !listed.insert(docs_dir.clone()) {
+      continue;
+    }
+
+    let link = relative_path(&index_dir, &docs_dir.join("index.html"));
+    let manifest = doc_builds::read_manifest(&docs_dir);
+    let built = manifest
+      .as_ref()
+      .and_then(|m| chrono::DateTime::from_timestamp(m.built_at, 0))
+      .map(|date| date.format("%Y-%m-%d %H:%M UTC").to_string())
+      .unwrap_or_else(|| "unknown".to_string());
+    let commit = manifest
+      .as_ref()
+      .map(|m| m.commit[..m.commit.len().min(7)].to_string())
+      .unwrap_or_default();
+
+    rows.push_str(&format!(
+      "<tr><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td><td><code>{}</code></td><td>{}</td></tr>\n",
+      escape_html(&link.to_string_lossy().replace('\\', "/")),
+      escape_html(&package.name),
+      escape_html(package.version.as_deref().unwrap_or("-")),
+      built,
+      commit,
+      escape_html(manifest.as_ref().map_or("-", |m| m.generator.as_str())),
+    ));
+  }
+
+  let html = format!(
+    r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{0} documentation</title>
+<style>
+body {{ font-family: system-ui, sans-serif; margin: 2rem; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ text-align: left; padding: .5rem; border-bottom: 1px solid #ddd; }}
+</style>
+</head>
+<body>
+<h1>{0}</h1>
+<table>
+<thead><tr><th>Package</th><th>Version</th><th>Last built</th><th>Commit</th><th>Generator</th></tr></thead>
+<tbody>
+{1}</tbody>
+</table>
+</body>
+</html>
+"#,
+    escape_html(&project),
+    rows
+  );
+
+  let index = index_dir.join("index.html");
+  std::fs::write(&index, html).map_err(|e| format!("Failed to write index: {}", e))?;
+  Ok(index)
+}
+
+fn relative_path(from_dir: &Path, to: &Path) -> PathBuf {
+  let from: Vec<Component> = from_dir.components().collect();
+  let to_components: Vec<Component> = to.components().collect();
+  let common = from
+    .iter()
+    .zip(&to_components)
+    .take_while(|(a, b)| a == b)
+    .count();
+
+  let mut relative = PathBuf::new();
+  for _ in common..from.len() {
+    relative.push("..");
+  }
+  for component in &to_components[common..] {
+    relative.push(component.as_os_str());
+  }
+  relative
+}
@@ -0,0 +1,248 @@
+use crate::git;
+use crate::sandbox;
+use git2::Repository;
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+// Manifests marking a directory as a package, checked in order
+const MANIFESTS: &[(&str, &str)] = &[
+  ("package.json", "JavaScript"),
+  ("Cargo.toml", "Rust"),
+  ("pyproject.toml", "Python"),
+  ("setup.py", "Python"),
+  ("go.mod", "Go"),
+  ("pom.xml", "Java"),
+  ("build.gradle", "Java"),
+  ("build.gradle.kts", "Kotlin"),
+  ("composer.json", "PHP"),
+  ("Gemfile", "Ruby"),
+];
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryType {
+  File,
+  Directory,
+  Symlink,
+  Other,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FolderEntry {
+  pub name: String,
+  pub path: String,
+  pub relative_path: String,
+  pub depth: usize,
+  pub entry_type: Option<EntryType>,
+  pub size: Option<u64>,
+  pub modified: Option<i64>,
+  pub ignored: bool,
+  pub manifest: Option<&'static str>,
+  pub language: Option<&'static str>,
+  // Set for entries that could not be read, instead of dropping them
+  pub error: Option<String>,
+}
+
+// Lists the folders below `path` like ripgrep walks them, and files too with `include_files`.
+// Git-ignored entries are listed and flagged unless `include_ignored` is false, `include`
+// globs whitelist entries and `exclude` globs skip them.
+#[tauri::command]
+pub fn list_folders(
+  path: String,
+  depth: Option<usize>,
+  include: Option<Vec<String>>,
+  exclude: Option<Vec<String>>,
+  include_ignored: Option<bool>,
+  include_files: Option<bool>,
+) -> Result<Vec<FolderEntry>, String> {
+  sandbox::resolve(&path).map_err(|e| e.to_string())?;
+  let root = Path::new(&path);
+  if !root.exists() {
+    return Err("Path does not exist".to_string());
+  }
+  let include_ignored = include_ignored.unwrap_or(true);
+  let include_files = include_files.unwrap_or(false);
+
+  let mut overrides = OverrideBuilder::new(root);
+  for glob in include.iter().flatten() {
+    overrides
+      .add(glob)
+      .map_err(|e| format!("Invalid include pattern {}: {}", glob, e))?;
+  }
+  for glob in exclude.iter().flatten() {
+    overrides
+      .add(&format!("!{}", glob))
+      .map_err(|e| format!("Invalid exclude pattern {}: {}", glob, e))?;
+  }
+  let overrides = overrides.build().map_err(|e| e.to_string())?;
+
+  let walker = WalkBuilder::new(root)
+    .max_depth(Some(depth.unwrap_or(1).max(1)))
+    .hidden(false)
+    .git_ignore(!include_ignored)
+    .git_exclude(!include_ignored)
+    .git_global(!include_ignored)
+    .overrides(overrides)
+    .filter_entry(|entry| entry.file_name() != ".git")
+    .sort_by_file_name(|a, b| a.cmp(b))
+    .build();
+
+  // Only needed to flag ignored entries, without them everything listed passed the ignore rules
+  let repo = include_ignored
+    .then(|| git::find_git_root(root))
+    .flatten()
+    .and_then(|git_root| Repository::open(git_root).ok());
+  let workdir = repo
+    .as_ref()
+    .and_then(|r| r.workdir())
+    .and_then(|w| w.canonicalize().ok());
+
+  let mut entries = Vec::new();
+  for result in walker {
+    let entry = match result {
+      Ok(entry) if entry.depth() == 0 => continue,
+      Ok(entry) => entry,
+      Err(error) => {
+        let Some((entry_path, depth)) = error_location(&error) else {
+          continue;
+        };
+        entries.push(FolderEntry {
+          error: Some(error.to_string()),
+          ..new_entry(root, entry_path, depth)
+        });
+        continue;
+      }
+    };
+
+    // Symlinks count as folders when they point at one
+    if !include_files && !entry.path().is_dir() {
+      continue;
+    }
+
+    let mut folder_entry = new_entry(root, entry.path(), entry.depth());
+    folder_entry.entry_type = entry.file_type().map(|file_type| {
+      if file_type.is_symlink() {
+        EntryType::Symlink
+      } else if file_type.is_dir() {
+        EntryType::Directory
+      } else if file_type.is_file() {
+        EntryType::File
+      } else {
+        EntryType::Other
+      }
+    });
+
+    match entry.metadata() {
+      Ok(metadata) => {
+        if metadata.is_file() {
+          folder_entry.size = Some(metadata.len());
+        }
+        folder_entry.modified = metadata
+          .modified()
+          .ok()
+          .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+          .map(|duration| duration.as_secs() as i64);
+      }
+      Err(error) => folder_entry.error = Some(error.to_string()),
+    }
+
+    if folder_entry.entry_type == Some(EntryType::Directory) {
+      folder_entry.manifest = MANIFESTS
+        .iter()
+        .find(|(manifest, _)| entry.path().join(manifest).is_file())
+        .map(|(manifest, _)| *manifest);
+      folder_entry.language = directory_language(entry.path());
+    } else {
+      folder_entry.language = file_language(entry.path());
+    }
+
+    if let (Some(repo), Some(workdir)) = (&repo, &workdir) {
+      folder_entry.ignored = entry
+        .path()
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.strip_prefix(workdir).ok().map(Path::to_path_buf))
+        .is_some_and(|relative| repo.status_should_ignore(&relative).unwrap_or(false));
+    }
+
+    entries.push(folder_entry);
+  }
+
+  Ok(entries)
+}
+
+fn new_entry(root: &Path, path: &Path, depth: usize) -> FolderEntry {
+  FolderEntry {
+    name: path
+      .file_name()
+      .map(|n| n.to_string_lossy().into_owned())
+      .unwrap_or_default(),
+    path: path.to_string_lossy().into_owned(),
+    relative_path: path
+      .strip_prefix(root)
+      .unwrap_or(path)
+      .to_string_lossy()
+      .replace('\\', "/"),
+    depth,
+    entry_type: None,
+    size: None,
+    modified: None,
+    ignored: false,
+    manifest: None,
+    language: None,
+    error: None,
+  }
+}
+
+fn error_location(error: &ignore::Error) -> Option<(&Path, usize)> {
+  match error {
+    ignore::Error::WithDepth { depth, err } => error_location(err).map(|(path, _)| (path, *depth)),
+    ignore::Error::WithPath { path, .. } => Some((path, 0)),
+    ignore::Error::WithLineNumber { err, .. } => error_location(err),
+    _ => None,
+  }
+}
+
+fn directory_language(dir: &Path) -> Option<&'static str> {
+  let (manifest, language) = MANIFESTS
+    .iter()
+    .find(|(manifest, _)| dir.join(manifest).is_file())?;
+  if *manifest == "package.json" && dir.join("tsconfig.json").is_file() {
+    return Some("TypeScript");
+  }
+  Some(language)
+}
+
+fn file_language(path: &Path) -> Option<&'static str> {
+  let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+  let language = match extension.as_str() {
+    "ts" | "tsx" | "mts" | "cts" => "TypeScript",
+    "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+    "rs" => "Rust",
+    "py" => "Python",
+    "go" => "Go",
+    "java" => "Java",
+    "kt" | "kts" => "Kotlin",
+    "rb" => "Ruby",
+    "php" => "PHP",
+    "swift" => "Swift",
+    "c" | "h" => "C",
+    "cc" | "cpp" | "cxx" | "hpp" => "C++",
+    "cs" => "C#",
+    "vue" => "Vue",
+    "svelte" => "Svelte",
+    "html" | "htm" => "HTML",
+    "css" => "CSS",
+    "scss" | "sass" => "SCSS",
+    "md" | "mdx" => "Markdown",
+    "json" => "JSON",
+    "yaml" | "yml" => "YAML",
+    "toml" => "TOML",
+    "sh" | "bash" | "zsh" => "Shell",
+    _ => return None,
+  };
+  Some(language)
+}
@@ -1,3 +1,4 @@
+use crate::issues::IssueRef;
 use moka::sync::Cache;
 use once_cell::sync::Lazy;
 use serde::Serialize;
@@ -13,6 +14,10 @@ pub struct BasicCommit {
   pub message: String,
   pub author: String,
   pub date: i64,
+  // Web URL of the repository and of the commit on its host, when known
+  pub remote_url: Option<String>,
+  pub web_url: Option<String>,
+  pub issues: Vec<IssueRef>,
 }
 
 const CACHE_TTL: Duration = Duration::from_secs(600);
@@ -32,7 +37,7 @@ pub static LAST_FETCH_CACHE: Lazy<Cache<String, i64>> = Lazy::new(|| {
 });
 
 // Function to perform git fetch for a specific remote
-fn fetch_from_remote(git_root: &str, remote: &str) -> Result<(), String> {
+pub fn fetch_from_remote(git_root: &str, remote: &str) -> Result<(), String> {
   let cache_key = format!("{}:{}", git_root, remote);
   let now = std::time::SystemTime::now()
     .duration_since(std::time::UNIX_EPOCH)
@@ -112,7 +117,7 @@ pub fn list_folder_commits(
       .arg("--");
 
     if let Some(branch_name) = branch {
-      let ref_name = remote.map_or(Cow::Borrowed(&branch_name), |r| {
+      let ref_name = remote.as_deref().map_or(Cow::Borrowed(&branch_name), |r| {
         Cow::Owned(format!("{}/{}", r, branch_name))
       });
       cmd.arg(&*ref_name);
@@ -129,7 +134,8 @@ pub fn list_folder_commits(
     }
 
     let output_str = String::from_utf8(output.stdout).map_err(|e| e.to_string())?;
-    let all_commits = parse_git_log_output(&output_str);
+    let mut all_commits = parse_git_log_output(&output_str);
+    enrich(&git_root, remote.as_deref(), &mut all_commits);
     let commits_arc: Arc<[BasicCommit]> = Arc::from(all_commits.into_boxed_slice());
     COMMIT_CACHE.insert(cache_key, commits_arc.clone());
     commits_arc
@@ -164,6 +170,12 @@ pub fn clear_git_cache(path: Option<String>) -> Result<(), String> {
       GIT_ROOT_CACHE.invalidate_all();
       COMMIT_CACHE.invalidate_all();
       LAST_FETCH_CACHE.invalidate_all();
+      crate::workspace::clear_cache();
+      crate::config::clear_cache();
+      crate::remote::clear_cache();
+      crate::task_cache::clear_cache();
+      crate::lockfile::clear_cache();
+      crate::audit::clear_cache();
       Ok(())
     }
   }
@@ -201,7 +213,7 @@ pub fn find_git_root(start_path: &Path) -> Option<String> {
   result
 }
 
-fn parse_git_log_output(output: &str) -> Vec<BasicCommit> {
+pub(crate) fn parse_git_log_output(output: &str) -> Vec<BasicCommit> {
   let mut commits = Vec::with_capacity(32);
   for commit_block in output.split("\0<COMMIT>") {
     let mut fields = commit_block.splitn(4, '\0');
@@ -220,12 +232,97 @@ fn parse_git_log_output(output: &str) -> Vec<BasicCommit> {
         author: author.to_string(),
         date,
         message: message.to_string(),
+        remote_url: None,
+        web_url: None,
+        issues: Vec::new(),
       });
     }
   }
   commits
 }
 
+// Web links and issue references, filled in after parsing
+pub fn enrich(git_root: &str, remote: Option<&str>, commits: &mut [BasicCommit]) {
+  crate::remote::annotate(git_root, remote, commits);
+  crate::issues::annotate(git_root, remote, commits);
+}
+
+// Paths a commit changed relative to its first parent, or every path of a root commit
+pub fn changed_paths(
+  repo: &git2::Repository,
+  commit: &git2::Commit,
+) -> Result<Vec<String>, String> {
+  let tree = commit.tree().map_err(|e| e.to_string())?;
+  let parent_tree = match commit.parent(0) {
+    Ok(parent) => Some(parent.tree().map_err(|e| e.to_string())?),
+    Err(_) => None,
+  };
+  let diff = repo
+    .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+    .map_err(|e| e.to_string())?;
+
+  Ok(
+    diff
+      .deltas()
+      .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+      .map(|path| path.to_string_lossy().replace('\\', "/"))
+      .collect(),
+  )
+}
+
+// Lists commits touching `relative_path` that are reachable from `to` but not from `from`
+pub fn list_commits_in_range(
+  git_root: &str,
+  from: Option<&str>,
+  to: &str,
+  relative_path: &str,
+) -> Result<Vec<BasicCommit>, String> {
+  // Revisions are resolved to commit ids first, so nothing the caller passes reaches git as
+  // an option
+  let repo =
+    git2::Repository::open(git_root).map_err(|e| format!("Failed to open repository: {}", e))?;
+  let resolve = |revision: &str| -> Result<String, String> {
+    if revision.starts_with('-') {
+      return Err(format!("Invalid revision: {}", revision));
+    }
+    repo
+      .revparse_single(revision)
+      .and_then(|object| object.peel_to_commit())
+      .map(|commit| commit.id().to_string())
+      .map_err(|e| format!("Unknown revision {}: {}", revision, e))
+  };
+  let range = match from {
+    Some(from) => format!("{}..{}", resolve(from)?, resolve(to)?),
+    None => resolve(to)?,
+  };
+
+  let mut cmd = Command::new("git");
+  cmd
+    .arg("-C")
+    .arg(git_root)
+    .arg("log")
+    .arg("--format=%H%x00%an%x00%at%x00%B%x00<COMMIT>")
+    .arg(&range)
+    .arg("--");
+
+  if !relative_path.is_empty() {
+    cmd.arg(relative_path);
+  }
+
+  let output = cmd
+    .output()
+    .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+  if !output.status.success() {
+    return Err(String::from_utf8_lossy(&output.stderr).to_string());
+  }
+
+  let output_str = String::from_utf8(output.stdout).map_err(|e| e.to_string())?;
+  let mut commits = parse_git_log_output(&output_str);
+  enrich(git_root, None, &mut commits);
+  Ok(commits)
+}
+
 pub fn get_git_references(path: &str) -> Result<GitReferences, String> {
   let remotes = Command::new("git")
     .arg("-C")
@@ -241,13 +338,30 @@ pub fn get_git_references(path: &str) -> Result<GitReferences, String> {
 
   let branches = vec![String::from("master")];
 
-  Ok(GitReferences { remotes, branches })
+  let tags = Command::new("git")
+    .arg("-C")
+    .arg(path)
+    .args(["tag", "--list", "--sort=-creatordate"])
+    .output()
+    .map_err(|e| format!("Failed to get tags: {}", e))
+    .and_then(|output| {
+      String::from_utf8(output.stdout)
+        .map(|s| s.lines().map(String::from).collect())
+        .map_err(|e| e.to_string())
+    })?;
+
+  Ok(GitReferences {
+    remotes,
+    branches,
+    tags,
+  })
 }
 
 #[derive(Serialize)]
 pub struct GitReferences {
   pub remotes: Vec<String>,
   pub branches: Vec<String>,
+  pub tags: Vec<String>,
 }
 
 // #[tauri::command]
@@ -339,5 +453,7 @@ pub fn get_new_commits(
   }
 
   let output_str = String::from_utf8(output.stdout).map_err(|e| e.to_string())?;
-  Ok(parse_git_log_output(&output_str))
+  let mut commits = parse_git_log_output(&output_str);
+  enrich(&git_root, Some(&remote), &mut commits);
+  Ok(commits)
 }
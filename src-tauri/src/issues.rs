@@ -0,0 +1,304 @@
+use crate::config::{self, IssueConfig};
+use crate::git::{self, BasicCommit};
+use crate::remote::{self, RemoteRepo};
+use crate::workspace;
+use git2::{Oid, Repository};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+
+// `#123`, `GH-123` and `PROJ-123`, optionally led by a closing keyword
+static REFERENCE_RE: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(
+    r"(?:\b(?i:(close[sd]?|fix(?:e[sd])?|resolve[sd]?))\s*:?\s+)?(#\d+|\bGH-\d+|\b[A-Z][A-Z0-9]{1,9}-\d+)\b",
+  )
+  .unwrap()
+});
+
+// Prefixes that look like tracker keys but are not, e.g. `UTF-8` or `SHA-256`
+const IGNORED_PREFIXES: &[&str] = &["UTF", "ISO", "SHA", "RFC", "CVE", "CWE", "MD"];
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct IssueRef {
+  // `#123` for the repository's own issues (also written `GH-123`), `PROJ-123` for tracker keys
+  pub key: String,
+  pub tracker: Option<String>,
+  pub number: u64,
+  // Referenced with a closing keyword such as `Fixes` or `Closes`
+  pub closes: bool,
+  pub url: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct IssueCommit {
+  #[serde(flatten)]
+  pub commit: BasicCommit,
+  // Workspace packages touched by the commit
+  pub packages: Vec<String>,
+}
+
+// Every commit on HEAD referencing `key`, whichever packages it touched
+#[tauri::command]
+pub fn find_commits_by_issue(path: String, key: String) -> Result<Vec<IssueCommit>, String> {
+  let key = normalize_key(&key).ok_or_else(|| format!("Invalid issue key: {}", key))?;
+  let git_root = git::find_git_root(Path::new(&path)).ok_or("Could not find Git repository")?;
+
+  let commits = git::list_commits_in_range(&git_root, None, "HEAD", "")?;
+  let matching: Vec<BasicCommit> = commits
+    .into_iter()
+    .filter(|commit| commit.issues.iter().any(|issue| issue.key == key))
+    .collect();
+  if matching.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  let repo = Repository::open(&git_root).map_err(|e| e.to_string())?;
+  let workspace = workspace::load_workspace(&git_root).ok();
+
+  matching
+    .into_iter()
+    .map(|commit| {
+      let oid = Oid::from_str(&commit.id).map_err(|e| e.to_string())?;
+      let git_commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+      let mut packages: Vec<String> = match &workspace {
+        Some(workspace) => git::changed_paths(&repo, &git_commit)?
+          .iter()
+          .filter_map(|file| workspace.package_for_path(file))
+          .map(|package| package.name.clone())
+          .collect(),
+        None => Vec::new(),
+      };
+      packages.sort();
+      packages.dedup();
+      Ok(IssueCommit { commit, packages })
+    })
+    .collect()
+}
+
+pub fn annotate(git_root: &str, remote: Option<&str>, commits: &mut [BasicCommit]) {
+  let config = config::load_config(git_root).unwrap_or_default();
+  let remote = remote::resolve(git_root, remote);
+  for commit in commits {
+    commit.issues = extract(&commit.message, &config.issues, remote.as_deref());
+  }
+}
+
+// References in a commit message, in order of first mention
+pub fn extract(message: &str, config: &IssueConfig, remote: Option<&RemoteRepo>) -> Vec<IssueRef> {
+  let mut issues: Vec<IssueRef> = Vec::new();
+  // End of the last reference introduced by a closing keyword, so `Fixes #1, #2` closes both
+  let mut closing_end = None;
+
+  for captures in REFERENCE_RE.captures_iter(message) {
+    let reference = captures.get(2).unwrap();
+    // Skip `abc#1`, `owner/repo#1` and HTML entities like `&#123;`
+    let preceding = message[..reference.start()].chars().next_back();
+    if reference.as_str().starts_with('#')
+      && preceding.is_some_and(|c| c.is_alphanumeric() || matches!(c, '&' | '/' | '_'))
+    {
+      continue;
+    }
+
+    let Some(mut issue) = parse_reference(reference.as_str(), config) else {
+      continue;
+    };
+    issue.closes = captures.get(1).is_some()
+      || closing_end.is_some_and(|end| {
+        let between = message[end..reference.start()].trim();
+        matches!(between, "," | "and" | ", and" | "&")
+      });
+    closing_end = issue.closes.then_some(reference.end());
+    issue.url = issue_url(&issue, config, remote);
+
+    match issues.iter_mut().find(|existing| existing.key == issue.key) {
+      Some(existing) => existing.closes |= issue.closes,
+      None => issues.push(issue),
+    }
+  }
+  issues
+}
+
+fn parse_reference(reference: &str, config: &IssueConfig) -> Option<IssueRef> {
+  let (prefix, number) = match reference.strip_prefix('#') {
+    Some(number) => (None, number),
+    None => {
+      let (prefix, number) = reference.split_once('-')?;
+      (Some(prefix), number)
+    }
+  };
+  let number = number.parse().ok()?;
+
+  let tracker = match prefix {
+    None | Some("GH") => None,
+    Some(prefix) if IGNORED_PREFIXES.contains(&prefix) => return None,
+    // Once trackers are configured, only their keys count
+    Some(prefix)
+      if !config.trackers.is_empty()
+        && !config.trackers.contains_key(prefix)
+        && !config.trackers.contains_key("*") =>
+    {
+      return None
+    }
+    Some(prefix) => Some(prefix.to_string()),
+  };
+
+  Some(IssueRef {
+    key: match &tracker {
+      Some(tracker) => format!("{}-{}", tracker, number),
+      None => format!("#{}", number),
+    },
+    tracker,
+    number,
+    closes: false,
+    url: None,
+  })
+}
+
+fn issue_url(
+  issue: &IssueRef,
+  config: &IssueConfig,
+  remote: Option<&RemoteRepo>,
+) -> Option<String> {
+  let template = match &issue.tracker {
+    Some(tracker) => config
+      .trackers
+      .get(tracker)
+      .or_else(|| config.trackers.get("*"))?,
+    None => match &config.url {
+      Some(template) => template,
+      None => return remote.map(|remote| remote.issue_url(issue.number)),
+    },
+  };
+  Some(
+    template
+      .replace("{key}", &issue.key)
+      .replace("{prefix}", issue.tracker.as_deref().unwrap_or_default())
+      .replace("{number}", &issue.number.to_string()),
+  )
+}
+
+// Accepts `123`, `#123`, `gh-123` and `proj-123`, returning the key `IssueRef` uses
+fn normalize_key(key: &str) -> Option<String> {
+  let key = key.trim().trim_start_matches('#').to_uppercase();
+  if !key.is_empty() && key.chars().all(|c| c.is_ascii_digit()) {
+    return Some(format!("#{}", key.parse::<u64>().ok()?));
+  }
+  let (prefix, number) = key.split_once('-')?;
+  let number: u64 = number.parse().ok()?;
+  if prefix == "GH" {
+    return Some(format!("#{}", number));
+  }
+  Some(format!("{}-{}", prefix, number))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::remote::Provider;
+  use std::collections::HashMap;
+
+  fn keys(message: &str, config: &IssueConfig) -> Vec<(String, bool)> {
+    extract(message, config, None)
+      .into_iter()
+      .map(|issue| (issue.key, issue.closes))
+      .collect()
+  }
+
+  #[test]
+  fn extracts_references_and_closing_keywords() {
+    let cases: &[(&str, &[(&str, bool)])] = &[
+      ("fixes #12", &[("#12", true)]),
+      ("Closes GH-3", &[("#3", true)]),
+      ("Resolved: #7", &[("#7", true)]),
+      ("Refs JIRA-12", &[("JIRA-12", false)]),
+      ("PROJ-0012 follow-up", &[("PROJ-12", false)]),
+      (
+        "Fixes #1, #2 and #3",
+        &[("#1", true), ("#2", true), ("#3", true)],
+      ),
+      ("See #4, fixes #5", &[("#4", false), ("#5", true)]),
+      ("#6 first, then fixes #6", &[("#6", true)]),
+      ("prefixes #8", &[("#8", false)]),
+      ("Use UTF-8 and SHA-256", &[]),
+      ("owner/repo#9 abc#1 &#123; snake_#2", &[]),
+      ("A-1 is too short", &[]),
+    ];
+    let config = IssueConfig::default();
+    for (message, expected) in cases {
+      let expected: Vec<(String, bool)> = expected
+        .iter()
+        .map(|(key, closes)| (key.to_string(), *closes))
+        .collect();
+      assert_eq!(keys(message, &config), expected, "{}", message);
+    }
+  }
+
+  #[test]
+  fn only_counts_configured_trackers() {
+    let config = IssueConfig {
+      url: Some("https://tracker.example.com/{number}".to_string()),
+      trackers: HashMap::from([(
+        "PROJ".to_string(),
+        "https://jira.example.com/browse/{key}".to_string(),
+      )]),
+    };
+    let issues = extract("PROJ-7, JIRA-12 and #3", &config, None);
+    let urls: Vec<(&str, Option<&str>)> = issues
+      .iter()
+      .map(|issue| (issue.key.as_str(), issue.url.as_deref()))
+      .collect();
+    assert_eq!(
+      urls,
+      vec![
+        ("PROJ-7", Some("https://jira.example.com/browse/PROJ-7")),
+        ("#3", Some("https://tracker.example.com/3")),
+      ]
+    );
+    assert_eq!(issues[0].tracker.as_deref(), Some("PROJ"));
+  }
+
+  #[test]
+  fn links_wildcard_trackers_and_remote_issues() {
+    let config = IssueConfig {
+      url: None,
+      trackers: HashMap::from([(
+        "*".to_string(),
+        "https://t.example.com/{prefix}/{number}".to_string(),
+      )]),
+    };
+    let remote = RemoteRepo {
+      remote: "origin".to_string(),
+      fetch_url: "https://github.com/owner/repo.git".to_string(),
+      provider: Provider::Github,
+      host: "github.com".to_string(),
+      repo_path: "owner/repo".to_string(),
+      base_url: "https://github.com/owner/repo".to_string(),
+    };
+    let issues = extract("JIRA-12 fixes #3", &config, Some(&remote));
+    assert_eq!(
+      issues[0].url.as_deref(),
+      Some("https://t.example.com/JIRA/12")
+    );
+    assert_eq!(
+      issues[1].url.as_deref(),
+      Some("https://github.com/owner/repo/issues/3")
+    );
+    assert_eq!(extract("fixes #3", &config, None)[0].url, None);
+  }
+
+  #[test]
+  fn normalizes_searched_keys() {
+    let cases = [
+      ("123", Some("#123")),
+      ("#0123", Some("#123")),
+      ("gh-5", Some("#5")),
+      ("proj-7", Some("PROJ-7")),
+      ("proj", None),
+      ("", None),
+    ];
+    for (key, expected) in cases {
+      assert_eq!(normalize_key(key).as_deref(), expected, "{}", key);
+    }
+  }
+}
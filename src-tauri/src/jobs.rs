@@ -0,0 +1,385 @@
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use parking_lot::{Condvar, Mutex};
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+pub const JOB_LOG_EVENT: &str = "job-log";
+pub const JOB_STATUS_EVENT: &str = "job-status";
+
+// Jobs beyond this limit wait in the queue
+const MAX_RUNNING_JOBS: usize = 4;
+
+// Finished jobs are kept for `get_job` and `list_jobs` until they age out or are among the oldest
+const MAX_FINISHED_JOBS: usize = 100;
+const FINISHED_JOB_RETENTION_SECS: i64 = 60 * 60;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+  Queued,
+  Running,
+  Succeeded,
+  Failed,
+  Cancelled,
+}
+
+impl JobStatus {
+  pub fn is_finished(self) -> bool {
+    matches!(
+      self,
+      JobStatus::Succeeded | JobStatus::Failed | JobStatus::Cancelled
+    )
+  }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct JobInfo {
+  pub id: String,
+  pub kind: String,
+  pub label: String,
+  pub status: JobStatus,
+  pub created_at: i64,
+  pub started_at: Option<i64>,
+  pub finished_at: Option<i64>,
+  pub exit_code: Option<i32>,
+  pub result: Option<String>,
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct JobLog {
+  pub job_id: String,
+  pub stream: &'static str,
+  pub line: String,
+}
+
+struct Job {
+  info: Mutex<JobInfo>,
+  child: Mutex<Option<Child>>,
+  cancelled: AtomicBool,
+}
+
+static JOBS: Lazy<DashMap<String, Arc<Job>>> = Lazy::new(DashMap::new);
+static JOB_COUNTER: AtomicU64 = AtomicU64::new(1);
+static RUNNING: Lazy<(Mutex<usize>, Condvar)> = Lazy::new(|| (Mutex::new(0), Condvar::new()));
+
+fn now() -> i64 {
+  chrono::Utc::now().timestamp()
+}
+
+fn emit_status(app: &AppHandle, job: &Job) {
+  let info = job.info.lock().clone();
+  let _ = app.emit(JOB_STATUS_EVENT, info);
+}
+
+// Registers a queued job and runs `work` on a background thread once a slot is free
+pub fn spawn<F>(app: &AppHandle, kind: &str, label: &str, work: F) -> String
+where
+  F: FnOnce(&AppHandle, &str) -> Result<Option<String>, String> + Send + 'static,
+{
+  start(app, kind, label, true, work)
+}
+
+// Runs `work` right away, outside the shared slots, for callers that limit their own concurrency
+pub fn spawn_unqueued<F>(app: &AppHandle, kind: &str, label: &str, work: F) -> String
+where
+  F: FnOnce(&AppHandle, &str) -> Result<Option<String>, String> + Send + 'static,
+{
+  start(app, kind, label, false, work)
+}
+
+fn start<F>(app: &AppHandle, kind: &str, label: &str, gated: bool, work: F) -> String
+where
+  F: FnOnce(&AppHandle, &str) -> Result<Option<String>, String> + Send + 'static,
+{
+  let id = format!("{}-{}", kind, JOB_COUNTER.fetch_add(1, Ordering::Relaxed));
+  let job = Arc::new(Job {
+    info: Mutex::new(JobInfo {
+      id: id.clone(),
+      kind: kind.to_string(),
+      label: label.to_string(),
+      status: JobStatus::Queued,
+      created_at: now(),
+      started_at: None,
+      finished_at: None,
+      exit_code: None,
+      result: None,
+      error: None,
+    }),
+    child: Mutex::new(None),
+    cancelled: AtomicBool::new(false),
+  });
+  prune_finished();
+  JOBS.insert(id.clone(), job.clone());
+  emit_status(app, &job);
+
+  let app = app.clone();
+  let job_id = id.clone();
+  std::thread::spawn(move || {
+    let (running, slot_freed) = &*RUNNING;
+    let acquired = {
+      let mut running = running.lock();
+      // Cancelling wakes the queue, so a cancelled job leaves without taking a slot
+      while gated && *running >= MAX_RUNNING_JOBS && !job.cancelled.load(Ordering::SeqCst) {
+        slot_freed.wait(&mut running);
+      }
+      let acquired = !job.cancelled.load(Ordering::SeqCst);
+      if acquired && gated {
+        *running += 1;
+      }
+      acquired
+    };
+
+    // A queued job cancelled meanwhile has already been marked as such by `cancel_job`
+    let started = acquired && {
+      let mut info = job.info.lock();
+      let queued = info.status == JobStatus::Queued;
+      if queued {
+        info.status = JobStatus::Running;
+        info.started_at = Some(now());
+      }
+      queued
+    };
+
+    if started {
+      emit_status(&app, &job);
+
+      // A panicking job fails like any other instead of holding its slot forever
+      let result = panic::catch_unwind(AssertUnwindSafe(|| work(&app, &job_id)))
+        .unwrap_or_else(|_| Err("Job panicked".to_string()));
+
+      {
+        let mut info = job.info.lock();
+        info.finished_at = Some(now());
+        match result {
+          _ if job.cancelled.load(Ordering::SeqCst) => info.status = JobStatus::Cancelled,
+          Ok(result) => {
+            info.status = JobStatus::Succeeded;
+            info.result = result;
+          }
+          Err(error) => {
+            info.status = JobStatus::Failed;
+            info.error = Some(error);
+          }
+        }
+      }
+      emit_status(&app, &job);
+    }
+
+    if acquired && gated {
+      *running.lock() -= 1;
+      // Waiters include cancelled jobs that leave without the slot
+      slot_freed.notify_all();
+    }
+  });
+
+  id
+}
+
+// Forgets finished jobs past their retention, then the oldest beyond the count limit
+fn prune_finished() {
+  let cutoff = now() - FINISHED_JOB_RETENTION_SECS;
+  let mut finished: Vec<(String, i64)> = JOBS
+    .iter()
+    .filter_map(|job| {
+      let info = job.info.lock();
+      let finished_at = info.finished_at.filter(|_| info.status.is_finished())?;
+      Some((job.key().clone(), finished_at))
+    })
+    .collect();
+  finished.sort_by_key(|(_, finished_at)| std::cmp::Reverse(*finished_at));
+
+  for (index, (id, finished_at)) in finished.into_iter().enumerate() {
+    if index >= MAX_FINISHED_JOBS || finished_at < cutoff {
+      JOBS.remove(&id);
+    }
+  }
+}
+
+// Runs a child process for a job, streaming each output line as a `job-log` event
+pub fn run_command(app: &AppHandle, job_id: &str, mut command: Command) -> Result<Output, String> {
+  let job = JOBS
+    .get(job_id)
+    .map(|j| j.clone())
+    .ok_or_else(|| format!("Unknown job: {}", job_id))?;
+
+  if job.cancelled.load(Ordering::SeqCst) {
+    return Err("Job was cancelled".to_string());
+  }
+
+  command
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+  #[cfg(unix)]
+  {
+    // Own process group, so cancelling also reaches grandchildren such as `npx` → `node`
+    use std::os::unix::process::CommandExt;
+    command.process_group(0);
+  }
+
+  let mut child = command
+    .spawn()
+    .map_err(|e| format!("Failed to start process: {}", e))?;
+  let stdout = child
+    .stdout
+    .take()
+    .map(|s| stream_lines(app, job_id, "stdout", s));
+  let stderr = child
+    .stderr
+    .take()
+    .map(|s| stream_lines(app, job_id, "stderr", s));
+  {
+    let mut guard = job.child.lock();
+    // A cancel landing between the spawn and here found no process to stop
+    if job.cancelled.load(Ordering::SeqCst) {
+      terminate_tree(child.id(), false);
+    }
+    *guard = Some(child);
+  }
+
+  let status = loop {
+    let mut guard = job.child.lock();
+    let Some(child) = guard.as_mut() else {
+      return Err("Process handle lost".to_string());
+    };
+    match child.try_wait() {
+      Ok(Some(status)) => {
+        guard.take();
+        break status;
+      }
+      Ok(None) => {}
+      Err(e) => return Err(e.to_string()),
+    }
+    drop(guard);
+    std::thread::sleep(Duration::from_millis(100));
+  };
+
+  let stdout = stdout.and_then(|h| h.join().ok()).unwrap_or_default();
+  let stderr = stderr.and_then(|h| h.join().ok()).unwrap_or_default();
+  job.info.lock().exit_code = status.code();
+
+  Ok(Output {
+    status,
+    stdout,
+    stderr,
+  })
+}
+
+fn stream_lines<R: Read + Send + 'static>(
+  app: &AppHandle,
+  job_id: &str,
+  stream: &'static str,
+  reader: R,
+) -> std::thread::JoinHandle<Vec<u8>> {
+  let app = app.clone();
+  let job_id = job_id.to_string();
+  std::thread::spawn(move || {
+    let mut captured = Vec::new();
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    while let Ok(read) = reader.read_until(b'\n', &mut line) {
+      if read == 0 {
+        break;
+      }
+      captured.extend_from_slice(&line);
+      emit_log(
+        &app,
+        &job_id,
+        stream,
+        String::from_utf8_lossy(&line).trim_end(),
+      );
+      line.clear();
+    }
+    captured
+  })
+}
+
+// Sends a line of job output, also used to replay output the job did not produce itself
+pub fn emit_log(app: &AppHandle, job_id: &str, stream: &'static str, line: &str) {
+  let _ = app.emit(
+    JOB_LOG_EVENT,
+    JobLog {
+      job_id: job_id.to_string(),
+      stream,
+      line: line.to_string(),
+    },
+  );
+}
+
+#[tauri::command]
+pub fn get_job(id: String) -> Result<JobInfo, String> {
+  JOBS
+    .get(&id)
+    .map(|job| job.info.lock().clone())
+    .ok_or_else(|| format!("Unknown job: {}", id))
+}
+
+#[tauri::command]
+pub fn list_jobs() -> Vec<JobInfo> {
+  let mut jobs: Vec<JobInfo> = JOBS.iter().map(|job| job.info.lock().clone()).collect();
+  jobs.sort_by_key(|job| std::cmp::Reverse(job.created_at));
+  jobs
+}
+
+// Cancelling asks the process tree to terminate; `force` kills it outright.
+// Queued jobs are cancelled on the spot and never start.
+#[tauri::command]
+pub fn cancel_job(app: AppHandle, id: String, force: Option<bool>) -> Result<(), String> {
+  let job = JOBS
+    .get(&id)
+    .map(|j| j.clone())
+    .ok_or_else(|| format!("Unknown job: {}", id))?;
+  job.cancelled.store(true, Ordering::SeqCst);
+
+  let was_queued = {
+    let mut info = job.info.lock();
+    let queued = info.status == JobStatus::Queued;
+    if queued {
+      info.status = JobStatus::Cancelled;
+      info.finished_at = Some(now());
+    }
+    queued
+  };
+  if was_queued {
+    emit_status(&app, &job);
+    let (running, slot_freed) = &*RUNNING;
+    let _running = running.lock();
+    slot_freed.notify_all();
+    return Ok(());
+  }
+
+  let mut guard = job.child.lock();
+  let Some(child) = guard.as_mut() else {
+    return Ok(());
+  };
+  let force = force.unwrap_or(false);
+  terminate_tree(child.id(), force);
+  if force {
+    let _ = child.kill();
+  }
+  Ok(())
+}
+
+#[cfg(unix)]
+fn terminate_tree(pid: u32, force: bool) {
+  let signal = if force { "-KILL" } else { "-TERM" };
+  let _ = Command::new("kill")
+    .args([signal, "--", &format!("-{}", pid)])
+    .status();
+}
+
+// Without `/F`, taskkill only asks windows to close, which console processes never have
+#[cfg(windows)]
+fn terminate_tree(pid: u32, _force: bool) {
+  let _ = Command::new("taskkill")
+    .args(["/T", "/F", "/PID", &pid.to_string()])
+    .status();
+}
@@ -1,4 +1,32 @@
-mod git;
+mod audit;
+mod changelog;
+mod compliance;
+mod config;
+mod consistency;
+mod content;
+mod conventional;
+mod doc_builds;
+pub mod docs;
+pub mod docs_batch;
+mod folders;
+pub mod git;
+mod issues;
+mod jobs;
+mod licenses;
+mod lockfile;
+mod preview;
+mod pulls;
+mod release;
+mod remote;
+mod revision;
+mod sandbox;
+mod sbom;
+mod scripts;
+mod semantic_diff;
+mod tags;
+mod task_cache;
+mod tasks;
+pub mod workspace;
 use git::GitReferences;
 use git2::{DiffOptions, Oid, Repository};
 use serde::Serialize;
@@ -7,106 +35,55 @@ use std::{
   process::Command,
 };
 
-#[tauri::command]
-async fn generate_docs(path: String) -> Result<String, String> {
-  let project_name = std::path::Path::new(&path)
-    .file_name()
-    .ok_or_else(|| "Invalid path".to_string())?
-    .to_str()
-    .ok_or_else(|| "Invalid project name".to_string())?;
+#[derive(Serialize)]
+pub struct DetailedCommit {
+  pub id: String,
+  pub message: String,
+  pub author: String,
+  pub date: i64,
+  pub changes: Vec<GitChange>,
+  pub remote_url: Option<String>,
+  pub web_url: Option<String>,
+  pub issues: Vec<issues::IssueRef>,
+}
 
-  let file_type = if std::path::Path::new(&path).join("tsconfig.json").exists() {
-    "ts"
-  } else {
-    "js"
-  };
+#[derive(Serialize)]
+pub struct GitChange {
+  pub status: String,
+  pub file: String,
+  pub web_url: Option<String>,
+  pub semantic: Option<semantic_diff::SemanticDiff>,
+}
 
-  let home_dir = dirs::home_dir().ok_or_else(|| "Could not find home directory".to_string())?;
-  let docs_path = home_dir
-    .join("mr-manager")
-    .join(project_name)
-    .to_str()
-    .ok_or_else(|| "Invalid path".to_string())?
-    .to_string();
+// Issue references, plus web links for the commit and its files when the repository has a known host.
+// Links point at `remote`, the one the commit was read from, defaulting to `origin`.
+fn link_commit(git_root: &Path, remote: Option<&str>, commit: &mut DetailedCommit) {
+  let git_root = git_root.to_string_lossy();
+  let remote = remote::resolve(&git_root, remote);
+  let config = config::load_config(&git_root).unwrap_or_default();
+  commit.issues = issues::extract(&commit.message, &config.issues, remote.as_deref());
 
-  std::fs::create_dir_all(&docs_path)
-    .map_err(|e| format!("Failed to create docs directory: {}", e))?;
-
-  let doc_command = match file_type {
-    "ts" => vec![
-      "typedoc",
-      "--entryPointStrategy",
-      "Expand",
-      "src",
-      "--out",
-      &docs_path,
-    ],
-    "js" => vec!["jsdoc", "-r", "src", "-d", &docs_path],
-    _ => return Err(format!("Unsupported file type: {}", file_type)),
+  let Some(remote) = remote else {
+    return;
   };
-
-  let output = std::process::Command::new("npx")
-    .args(&doc_command)
-    .current_dir(&path)
-    .output()
-    .map_err(|e| e.to_string())?;
-
-  if !output.status.success() {
-    let error = String::from_utf8_lossy(&output.stderr);
-    return Err(error.into_owned());
-  }
-
-  Ok(docs_path)
-}
-#[tauri::command]
-fn read_file(path: String) -> Result<String, String> {
-  match std::fs::read_to_string(&path) {
-    Ok(contents) => Ok(contents),
-    Err(e) => Err(e.to_string()),
+  commit.remote_url = Some(remote.base_url.clone());
+  commit.web_url = Some(remote.commit_url(&commit.id));
+  for change in &mut commit.changes {
+    if change.status != "Deleted" {
+      change.web_url = Some(remote.file_url(&change.file, &commit.id, None, None));
+    }
   }
 }
 
-#[tauri::command]
-fn file_exists(path: String) -> bool {
-  std::path::Path::new(&path).exists()
-}
-
-#[tauri::command]
-fn list_folders(path: String) -> Result<Vec<String>, String> {
-  let path = Path::new(&path);
-  if !path.exists() {
-    return Err("Path does not exist".to_string());
+// Dependency, script and version changes for the package.json files and lockfiles a commit touches
+fn describe_changes(repo: &Repository, commit: &git2::Commit, changes: &mut [GitChange]) {
+  let Ok(tree) = commit.tree() else {
+    return;
+  };
+  let parent_tree = commit.parent(0).and_then(|parent| parent.tree()).ok();
+  for change in changes {
+    change.semantic = semantic_diff::diff_file(repo, parent_tree.as_ref(), &tree, &change.file);
   }
-
-  let entries = std::fs::read_dir(path)
-    .map_err(|e| e.to_string())?
-    .filter_map(|entry| {
-      let entry = entry.ok()?;
-      let path = entry.path();
-      if path.is_dir() {
-        path.file_name()?.to_str().map(String::from)
-      } else {
-        None
-      }
-    })
-    .collect();
-
-  Ok(entries)
-}
-
-#[derive(Serialize)]
-struct DetailedCommit {
-  id: String,
-  message: String,
-  author: String,
-  date: i64,
-  changes: Vec<GitChange>,
-}
-
-#[derive(Serialize)]
-struct GitChange {
-  status: String,
-  file: String,
 }
 
 fn find_git_root(start_path: &Path) -> Option<PathBuf> {
@@ -193,7 +170,7 @@ async fn get_commit_diff(
 }
 
 #[tauri::command]
-fn get_commit_details(repo_path: String, commit_id: String) -> Result<DetailedCommit, String> {
+pub fn get_commit_details(repo_path: String, commit_id: String) -> Result<DetailedCommit, String> {
   let path = Path::new(&repo_path);
   let git_root = find_git_root(path).ok_or_else(|| "Could not find Git repository".to_string())?;
 
@@ -250,6 +227,8 @@ fn get_commit_details(repo_path: String, commit_id: String) -> Result<DetailedCo
                   git2::Delta::Conflicted => "Conflicted".to_string(),
                 },
                 file: file_path_str.to_string(),
+                web_url: None,
+                semantic: None,
               };
               changes.push(change);
             }
@@ -262,21 +241,30 @@ fn get_commit_details(repo_path: String, commit_id: String) -> Result<DetailedCo
       None,
     )
     .map_err(|e| e.to_string())?;
+  describe_changes(&repo, &commit, &mut changes);
 
   // Create the detailed commit outside the borrow scope
-  let detailed_commit = DetailedCommit {
+  let mut detailed_commit = DetailedCommit {
     id: commit.id().to_string(),
     message: commit.message().unwrap_or("").to_string(),
     author: commit.author().name().unwrap_or("").to_string(),
     date: commit.time().seconds(),
     changes,
+    remote_url: None,
+    web_url: None,
+    issues: Vec::new(),
   };
+  link_commit(&git_root, None, &mut detailed_commit);
 
   Ok(detailed_commit)
 }
 
 #[tauri::command]
-fn get_new_commits_details(repo_path: String, commit_id: String) -> Result<DetailedCommit, String> {
+pub fn get_new_commits_details(
+  repo_path: String,
+  commit_id: String,
+  remote: Option<String>,
+) -> Result<DetailedCommit, String> {
   let path = Path::new(&repo_path);
   let git_root = find_git_root(path).ok_or_else(|| "Could not find Git repository".to_string())?;
 
@@ -342,34 +330,92 @@ fn get_new_commits_details(repo_path: String, commit_id: String) -> Result<Detai
       changes.push(GitChange {
         status: status.to_string(),
         file: file.to_string(),
+        web_url: None,
+        semantic: None,
       });
     }
   }
 
-  Ok(DetailedCommit {
+  if let Ok(repo) = Repository::open(&git_root) {
+    if let Ok(commit) = Oid::from_str(&id).and_then(|oid| repo.find_commit(oid)) {
+      describe_changes(&repo, &commit, &mut changes);
+    }
+  }
+
+  let mut detailed_commit = DetailedCommit {
     id,
     message,
     author,
     date,
     changes,
-  })
+    remote_url: None,
+    web_url: None,
+    issues: Vec::new(),
+  };
+  link_commit(&git_root, remote.as_deref(), &mut detailed_commit);
+
+  Ok(detailed_commit)
 }
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
     .plugin(tauri_plugin_notification::init())
+    .setup(|app| {
+      sandbox::restore_repository(app.handle());
+      Ok(())
+    })
     .invoke_handler(tauri::generate_handler![
-      generate_docs,
-      read_file,
-      file_exists,
-      list_folders,
+      docs::generate_docs,
+      docs::list_doc_generators,
+      docs::get_docs_output_dir,
+      docs::start_docs_job,
+      doc_builds::get_docs_status,
+      doc_builds::list_doc_builds,
+      doc_builds::prune_doc_builds,
+      docs_batch::generate_all_docs,
+      docs_batch::get_docs_index_dir,
+      jobs::get_job,
+      jobs::list_jobs,
+      jobs::cancel_job,
+      scripts::list_scripts,
+      scripts::run_script,
+      tasks::run_task,
+      tasks::cancel_task_run,
+      task_cache::clear_task_cache,
+      sandbox::choose_repository,
+      sandbox::get_repository,
+      sandbox::read_file,
+      sandbox::file_exists,
+      preview::preview_file,
+      preview::read_file_chunk,
+      revision::read_file_at_revision,
+      revision::list_tree_at_revision,
+      folders::list_folders,
       list_folder_commits,
       get_commit_details,
       get_commit_diff,
       get_git_references,
       get_new_commits_details,
       git::get_new_commits,
-      git::clear_git_cache
+      git::clear_git_cache,
+      remote::get_remote,
+      remote::get_web_url,
+      issues::find_commits_by_issue,
+      pulls::list_pull_requests,
+      pulls::get_new_pull_requests,
+      changelog::generate_changelog,
+      workspace::list_packages,
+      release::plan_release,
+      consistency::check_dependency_consistency,
+      lockfile::get_resolved_dependencies,
+      lockfile::explain_dependency,
+      sbom::generate_sbom,
+      audit::import_advisories,
+      audit::audit_dependencies,
+      compliance::check_license_compliance,
+      config::get_repo_config,
+      tags::list_tags,
+      tags::get_package_timeline
     ]) // Combined into single handler
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_fs::init())
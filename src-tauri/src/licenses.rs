@@ -0,0 +1,445 @@
+use crate::workspace;
+use std::path::{Path, PathBuf};
+
+// Finds licenses of installed dependencies without network access: npm packages from their
+// package.json in `node_modules`, crates from the cargo registry sources
+pub struct LicenseIndex {
+  node_modules: Vec<PathBuf>,
+  // Entries of `node_modules/.pnpm`, named `name@version` with `/` in scopes replaced by `+`
+  pnpm_store: Vec<(String, PathBuf)>,
+  cargo_sources: Vec<PathBuf>,
+}
+
+impl LicenseIndex {
+  // `dirs` are the lockfile directory and the importers, whose `node_modules` are searched in order
+  pub fn new(dirs: &[PathBuf]) -> Self {
+    let node_modules: Vec<PathBuf> = dirs
+      .iter()
+      .map(|dir| dir.join("node_modules"))
+      .filter(|dir| dir.is_dir())
+      .collect();
+
+    let pnpm_store = node_modules
+      .iter()
+      .filter_map(|dir| std::fs::read_dir(dir.join(".pnpm")).ok())
+      .flatten()
+      .filter_map(|entry| {
+        let entry = entry.ok()?;
+        Some((
+          entry.file_name().to_string_lossy().into_owned(),
+          entry.path(),
+        ))
+      })
+      .collect();
+
+    let cargo_home = std::env::var_os("CARGO_HOME")
+      .map(PathBuf::from)
+      .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")));
+    let cargo_sources = cargo_home
+      .and_then(|home| std::fs::read_dir(home.join("registry").join("src")).ok())
+      .into_iter()
+      .flatten()
+      .filter_map(|entry| Some(entry.ok()?.path()))
+      .collect();
+
+    LicenseIndex {
+      node_modules,
+      pnpm_store,
+      cargo_sources,
+    }
+  }
+
+  pub fn npm(&self, name: &str, version: &str) -> Option<String> {
+    // pnpm suffixes store entries with their peers: `react-dom@18.2.0_react@18.2.0` or `(react@18.2.0)`
+    let store_name = format!("{}@{}", name.replace('/', "+"), version);
+    let from_store = self
+      .pnpm_store
+      .iter()
+      .filter(|(entry, _)| {
+        entry
+          .strip_prefix(&store_name)
+          .is_some_and(|rest| rest.is_empty() || rest.starts_with(['_', '(']))
+      })
+      .map(|(_, dir)| dir.join("node_modules").join(name));
+
+    let hoisted = self.node_modules.iter().map(|dir| dir.join(name));
+    from_store.chain(hoisted).find_map(|dir| {
+      let json = workspace::read_json(&dir.join("package.json"))?;
+      if json.get("version").and_then(|v| v.as_str()) != Some(version) {
+        return None;
+      }
+      resolve(&dir, manifest_license(&json))
+    })
+  }
+
+  pub fn cargo(&self, name: &str, version: &str) -> Option<String> {
+    let dir_name = format!("{}-{}", name, version);
+    self.cargo_sources.iter().find_map(|registry| {
+      let dir = registry.join(&dir_name);
+      let manifest = workspace::read_toml(&dir.join("Cargo.toml"))?;
+      resolve(&dir, cargo_license(&manifest))
+    })
+  }
+}
+
+// `license` as a string or the legacy `{ "type": ... }` object, or a legacy `licenses` array
+pub fn manifest_license(json: &serde_json::Value) -> Option<String> {
+  let license_type = |value: &serde_json::Value| -> Option<String> {
+    value
+      .as_str()
+      .or_else(|| value.get("type")?.as_str())
+      .map(String::from)
+  };
+
+  if let Some(license) = json.get("license").and_then(license_type) {
+    return Some(license);
+  }
+  let licenses: Vec<String> = json
+    .get("licenses")?
+    .as_array()?
+    .iter()
+    .filter_map(license_type)
+    .collect();
+  match licenses.len() {
+    0 => None,
+    1 => licenses.into_iter().next(),
+    _ => Some(format!("({})", licenses.join(" OR "))),
+  }
+}
+
+// `license`, or a reference to the `license-file` the crate ships instead
+pub fn cargo_license(manifest: &toml_edit::DocumentMut) -> Option<String> {
+  let package = manifest.get("package")?;
+  if let Some(license) = package.get("license").and_then(|l| l.as_str()) {
+    return Some(license.to_string());
+  }
+  package
+    .get("license-file")
+    .and_then(|l| l.as_str())
+    .map(|_| LICENSE_FILE_REF.to_string())
+}
+
+// License of a workspace package, from its own manifest
+pub fn package_license(dir: &Path, manifest: workspace::ManifestKind) -> Option<String> {
+  let declared = match manifest {
+    workspace::ManifestKind::Npm => {
+      manifest_license(&workspace::read_json(&dir.join("package.json"))?)
+    }
+    workspace::ManifestKind::Cargo => {
+      cargo_license(&workspace::read_toml(&dir.join("Cargo.toml"))?)
+    }
+  };
+  resolve(dir, declared)
+}
+
+// The declared license as an SPDX expression; the package's license file is identified when the
+// manifest only points to it, or declares nothing
+fn resolve(dir: &Path, declared: Option<String>) -> Option<String> {
+  let points_to_file = declared.as_deref().is_none_or(|license| {
+    license == LICENSE_FILE_REF || license.to_ascii_uppercase().starts_with("SEE LICENSE IN")
+  });
+  if points_to_file {
+    if let Some(detected) = detect_license_file(dir) {
+      return Some(detected.to_string());
+    }
+  }
+  normalize(&declared?)
+}
+
+const LICENSE_FILE_REF: &str = "LicenseRef-license-file";
+
+// Identifiers as the SPDX license list spells them
+const SPDX_IDS: &[&str] = &[
+  "0BSD",
+  "AGPL-3.0-only",
+  "AGPL-3.0-or-later",
+  "Apache-2.0",
+  "Artistic-2.0",
+  "BlueOak-1.0.0",
+  "BSD-2-Clause",
+  "BSD-3-Clause",
+  "BSL-1.0",
+  "CC-BY-3.0",
+  "CC-BY-4.0",
+  "CC0-1.0",
+  "CDDL-1.0",
+  "EPL-1.0",
+  "EPL-2.0",
+  "GPL-2.0-only",
+  "GPL-2.0-or-later",
+  "GPL-3.0-only",
+  "GPL-3.0-or-later",
+  "ISC",
+  "LGPL-2.1-only",
+  "LGPL-2.1-or-later",
+  "LGPL-3.0-only",
+  "LGPL-3.0-or-later",
+  "MIT",
+  "MIT-0",
+  "MPL-2.0",
+  "OFL-1.1",
+  "Python-2.0",
+  "Unicode-3.0",
+  "Unicode-DFS-2016",
+  "Unlicense",
+  "WTFPL",
+  "Zlib",
+];
+
+// Common spellings in manifests, keyed like `license_key`
+const ALIASES: &[(&str, &str)] = &[
+  ("apache2", "Apache-2.0"),
+  ("apache2.0", "Apache-2.0"),
+  ("asl2.0", "Apache-2.0"),
+  ("simplifiedbsd", "BSD-2-Clause"),
+  ("freebsd", "BSD-2-Clause"),
+  ("newbsd", "BSD-3-Clause"),
+  ("modifiedbsd", "BSD-3-Clause"),
+  ("boost", "BSL-1.0"),
+  ("cc0", "CC0-1.0"),
+  ("expat", "MIT"),
+  ("gpl2", "GPL-2.0-only"),
+  ("gpl2.0", "GPL-2.0-only"),
+  ("gplv2", "GPL-2.0-only"),
+  ("gpl3", "GPL-3.0-only"),
+  ("gpl3.0", "GPL-3.0-only"),
+  ("gplv3", "GPL-3.0-only"),
+  ("lgpl2.1", "LGPL-2.1-only"),
+  ("lgplv2.1", "LGPL-2.1-only"),
+  ("lgpl3", "LGPL-3.0-only"),
+  ("lgpl3.0", "LGPL-3.0-only"),
+  ("lgplv3", "LGPL-3.0-only"),
+  ("agpl3", "AGPL-3.0-only"),
+  ("agpl3.0", "AGPL-3.0-only"),
+  ("agplv3", "AGPL-3.0-only"),
+  ("mpl2", "MPL-2.0"),
+  ("mpl2.0", "MPL-2.0"),
+  ("mozillapublic2.0", "MPL-2.0"),
+  ("python2.0", "Python-2.0"),
+];
+
+// Lowercased alphanumerics (and dots), without filler words, so `Apache License, Version 2.0`
+// and `apache-2.0` compare equal
+fn license_key(name: &str) -> String {
+  name
+    .to_ascii_lowercase()
+    .split(|c: char| !c.is_ascii_alphanumeric() && c != '.')
+    .filter(|word| {
+      !matches!(
+        *word,
+        "" | "the" | "license" | "licence" | "version" | "clause"
+      )
+    })
+    .collect::<Vec<_>>()
+    .concat()
+}
+
+fn normalize_id(name: &str) -> String {
+  // `GPL-2.0+` is the deprecated spelling of `GPL-2.0-or-later`
+  if let Some(base) = name.strip_suffix('+') {
+    let id = normalize_id(base);
+    if let Some(family) = id.strip_suffix("-only") {
+      return format!("{}-or-later", family);
+    }
+    return id;
+  }
+  let key = license_key(name);
+  let known = SPDX_IDS.iter().find(|id| license_key(id) == key).copied();
+  let id = known.or_else(|| {
+    ALIASES
+      .iter()
+      .find(|(alias, _)| *alias == key)
+      .map(|(_, id)| *id)
+  });
+  match id {
+    Some(id) => id.to_string(),
+    None if name.starts_with("LicenseRef-") => name.to_string(),
+    None if name.eq_ignore_ascii_case("UNLICENSED") => "LicenseRef-proprietary".to_string(),
+    None => {
+      let reference: String = name
+        .chars()
+        .map(|c| {
+          if c.is_ascii_alphanumeric() || c == '.' {
+            c
+          } else {
+            '-'
+          }
+        })
+        .collect();
+      format!("LicenseRef-{}", reference.trim_matches('-'))
+    }
+  }
+}
+
+// Normalizes a declared license to an SPDX expression: identifiers are matched case-insensitively
+// and against common spellings, Cargo's legacy `MIT/Apache-2.0` becomes an `OR`, anything
+// unrecognized becomes a `LicenseRef-`
+pub fn normalize(declared: &str) -> Option<String> {
+  let declared = declared.trim();
+  if declared.is_empty() {
+    return None;
+  }
+  if declared.to_ascii_uppercase().starts_with("SEE LICENSE IN") {
+    return Some(LICENSE_FILE_REF.to_string());
+  }
+
+  let spaced = declared
+    .replace('/', " OR ")
+    .replace('(', " ( ")
+    .replace(')', " ) ");
+  let mut tokens: Vec<String> = Vec::new();
+  // Consecutive words are one license name, as in `Apache License 2.0`
+  let mut name: Vec<&str> = Vec::new();
+  let mut after_with = false;
+  let flush = |name: &mut Vec<&str>, tokens: &mut Vec<String>, after_with: bool| {
+    if name.is_empty() {
+      return;
+    }
+    let joined = name.join(" ");
+    // Exceptions after `WITH` are kept as written
+    tokens.push(if after_with {
+      joined
+    } else {
+      normalize_id(&joined)
+    });
+    name.clear();
+  };
+  for word in spaced.split_whitespace() {
+    let operator = match word.to_ascii_uppercase().as_str() {
+      "OR" => Some("OR"),
+      "AND" => Some("AND"),
+      "WITH" => Some("WITH"),
+      "(" => Some("("),
+      ")" => Some(")"),
+      _ => None,
+    };
+    match operator {
+      Some(operator) => {
+        flush(&mut name, &mut tokens, after_with);
+        after_with = operator == "WITH";
+        tokens.push(operator.to_string());
+      }
+      None => name.push(word),
+    }
+  }
+  flush(&mut name, &mut tokens, after_with);
+
+  let expression = tokens.join(" ").replace("( ", "(").replace(" )", ")");
+  // A single parenthesized license, as legacy `licenses` arrays of one entry produce
+  match expression
+    .strip_prefix('(')
+    .and_then(|e| e.strip_suffix(')'))
+  {
+    Some(inner) if !inner.contains(['(', ')', ' ']) => Some(inner.to_string()),
+    _ => Some(expression),
+  }
+}
+
+// Identifies a LICENSE, LICENCE or COPYING file by its wording
+pub fn detect_license_file(dir: &Path) -> Option<&'static str> {
+  let entries = std::fs::read_dir(dir).ok()?;
+  let mut files: Vec<PathBuf> = entries
+    .filter_map(|entry| entry.ok().map(|e| e.path()))
+    .filter(|path| {
+      let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_ascii_uppercase())
+        .unwrap_or_default();
+      path.is_file()
+        && ["LICENSE", "LICENCE", "COPYING"]
+          .iter()
+          .any(|prefix| name.starts_with(prefix))
+    })
+    .collect();
+  files.sort();
+
+  files.iter().find_map(|file| {
+    let text = std::fs::read_to_string(file).ok()?;
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let has = |phrase: &str| text.contains(phrase);
+    let license = if has("Permission is hereby granted, free of charge") {
+      "MIT"
+    } else if has("Apache License") && has("Version 2.0") {
+      "Apache-2.0"
+    } else if has("Permission to use, copy, modify, and/or distribute") {
+      "ISC"
+    } else if has("Redistribution and use in source and binary forms") {
+      if has("Neither the name") || has("endorse or promote") {
+        "BSD-3-Clause"
+      } else {
+        "BSD-2-Clause"
+      }
+    } else if has("GNU LESSER GENERAL PUBLIC LICENSE") && has("Version 3") {
+      "LGPL-3.0-only"
+    } else if has("GNU LESSER GENERAL PUBLIC LICENSE") && has("Version 2.1") {
+      "LGPL-2.1-only"
+    } else if has("GNU AFFERO GENERAL PUBLIC LICENSE") {
+      "AGPL-3.0-only"
+    } else if has("GNU GENERAL PUBLIC LICENSE") && has("Version 3") {
+      "GPL-3.0-only"
+    } else if has("GNU GENERAL PUBLIC LICENSE") && has("Version 2") {
+      "GPL-2.0-only"
+    } else if has("Mozilla Public License Version 2.0") {
+      "MPL-2.0"
+    } else if has("This is free and unencumbered software released into the public domain") {
+      "Unlicense"
+    } else if has("CC0 1.0 Universal") {
+      "CC0-1.0"
+    } else {
+      return None;
+    };
+    Some(license)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn normalizes_identifiers_and_common_spellings() {
+    assert_eq!(normalize("mit").as_deref(), Some("MIT"));
+    assert_eq!(
+      normalize("Apache License, Version 2.0").as_deref(),
+      Some("Apache-2.0")
+    );
+    assert_eq!(normalize("Expat").as_deref(), Some("MIT"));
+    assert_eq!(normalize("GPL-2.0+").as_deref(), Some("GPL-2.0-or-later"));
+    assert_eq!(normalize("  "), None);
+  }
+
+  #[test]
+  fn normalizes_expressions() {
+    assert_eq!(
+      normalize("MIT/Apache-2.0").as_deref(),
+      Some("MIT OR Apache-2.0")
+    );
+    assert_eq!(
+      normalize("(mit or apache 2.0)").as_deref(),
+      Some("(MIT OR Apache-2.0)")
+    );
+    assert_eq!(normalize("(ISC)").as_deref(), Some("ISC"));
+    assert_eq!(
+      normalize("GPL-2.0-only WITH Classpath-exception-2.0").as_deref(),
+      Some("GPL-2.0-only WITH Classpath-exception-2.0")
+    );
+  }
+
+  #[test]
+  fn references_unknown_licenses() {
+    assert_eq!(normalize("BSD").as_deref(), Some("LicenseRef-BSD"));
+    assert_eq!(
+      normalize("Custom License v1").as_deref(),
+      Some("LicenseRef-Custom-License-v1")
+    );
+    assert_eq!(
+      normalize("UNLICENSED").as_deref(),
+      Some("LicenseRef-proprietary")
+    );
+    assert_eq!(normalize("Unlicense").as_deref(), Some("Unlicense"));
+    assert_eq!(
+      normalize("SEE LICENSE IN LICENSE.md").as_deref(),
+      Some(LICENSE_FILE_REF)
+    );
+  }
+}
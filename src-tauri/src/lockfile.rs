@@ -0,0 +1,1392 @@
+use crate::git;
+use crate::workspace::{self, DependencyKind};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use moka::sync::Cache;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+// Looked up in this order in the workspace root, then in its parents up to the repository root
+pub const LOCKFILES: &[(&str, LockfileKind)] = &[
+  ("pnpm-lock.yaml", LockfileKind::Pnpm),
+  ("yarn.lock", LockfileKind::Yarn),
+  ("package-lock.json", LockfileKind::Npm),
+  ("npm-shrinkwrap.json", LockfileKind::Npm),
+];
+
+const IMPORTER_SECTIONS: &[(&str, DependencyKind)] = &[
+  ("dependencies", DependencyKind::Normal),
+  ("devDependencies", DependencyKind::Dev),
+  ("optionalDependencies", DependencyKind::Optional),
+];
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LockfileKind {
+  Pnpm,
+  Yarn,
+  Npm,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LockedPackage {
+  pub name: String,
+  pub version: String,
+  // Ids (`name@version`) of the resolved dependencies
+  pub dependencies: Vec<String>,
+  // Subresource integrity of the registry tarball, e.g. `sha512-...`
+  pub integrity: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ImporterDependency {
+  pub name: String,
+  pub kind: DependencyKind,
+  pub specifier: Option<String>,
+  // Resolved package id; unset for workspace links and unresolved entries
+  pub id: Option<String>,
+  // Path of the linked workspace importer
+  pub link: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Importer {
+  // Relative to the lockfile's directory, `.` for the root
+  pub path: String,
+  pub name: Option<String>,
+  pub dependencies: Vec<ImporterDependency>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Lockfile {
+  pub kind: LockfileKind,
+  pub path: String,
+  pub importers: Vec<Importer>,
+  pub packages: BTreeMap<String, LockedPackage>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DuplicatePackage {
+  pub name: String,
+  // Lowest first
+  pub versions: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ResolvedDependencies {
+  #[serde(flatten)]
+  pub lockfile: Lockfile,
+  pub duplicates: Vec<DuplicatePackage>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DependencyChain {
+  pub importer: String,
+  // Kind of the importer's dependency the chain starts with
+  pub kind: DependencyKind,
+  // Package ids from the importer's direct dependency down to the package asked about
+  pub chain: Vec<String>,
+}
+
+// Keyed by lockfile path, with the modification time it was parsed at
+static LOCKFILE_CACHE: Lazy<Cache<String, (SystemTime, Arc<Lockfile>)>> = Lazy::new(|| {
+  Cache::builder()
+    .time_to_live(Duration::from_secs(300))
+    .initial_capacity(10)
+    .build()
+});
+
+pub fn clear_cache() {
+  LOCKFILE_CACHE.invalidate_all();
+}
+
+#[tauri::command]
+pub fn get_resolved_dependencies(path: String) -> Result<ResolvedDependencies, String> {
+  let lockfile = load_lockfile(&path)?;
+  Ok(ResolvedDependencies {
+    duplicates: duplicates(&lockfile),
+    lockfile: lockfile.as_ref().clone(),
+  })
+}
+
+// Why a package is installed: the shortest chain from each importer to each of its versions
+#[tauri::command]
+pub fn explain_dependency(
+  path: String,
+  name: String,
+  version: Option<String>,
+) -> Result<Vec<DependencyChain>, String> {
+  let lockfile = load_lockfile(&path)?;
+  Ok(explain(&lockfile, &name, version.as_deref()))
+}
+
+pub fn load_lockfile(path: &str) -> Result<Arc<Lockfile>, String> {
+  let (file, kind) = find_lockfile(path)?;
+  let key = file.to_string_lossy().into_owned();
+  let modified = std::fs::metadata(&file)
+    .and_then(|m| m.modified())
+    .map_err(|e| e.to_string())?;
+  if let Some((parsed_at, cached)) = LOCKFILE_CACHE.get(&key) {
+    if parsed_at == modified {
+      return Ok(cached);
+    }
+  }
+
+  let contents = std::fs::read_to_string(&file).map_err(|e| e.to_string())?;
+  let root = file.parent().unwrap_or(Path::new("."));
+  let read_manifest =
+    |relative: &str| workspace::read_json(&root.join(relative).join("package.json"));
+  let expand = |pattern: &str| -> Vec<String> {
+    glob::glob(&root.join(pattern).to_string_lossy())
+      .into_iter()
+      .flatten()
+      .filter_map(Result::ok)
+      .filter(|dir| dir.join("package.json").is_file())
+      .filter_map(|dir| {
+        let relative = dir.strip_prefix(root).ok()?;
+        Some(relative.to_string_lossy().replace('\\', "/"))
+      })
+      .collect()
+  };
+  let mut lockfile = parse(kind, &contents, &read_manifest, &expand)?;
+  lockfile.path = key.clone();
+  let lockfile = Arc::new(lockfile);
+  LOCKFILE_CACHE.insert(key, (modified, lockfile.clone()));
+  Ok(lockfile)
+}
+
+fn find_lockfile(path: &str) -> Result<(PathBuf, LockfileKind), String> {
+  let workspace = workspace::load_workspace(path)?;
+  let git_root = git::find_git_root(Path::new(path)).map(PathBuf::from);
+  for dir in Path::new(&workspace.root).ancestors() {
+    if let Some((name, kind)) = LOCKFILES.iter().find(|(name, _)| dir.join(name).is_file()) {
+      return Ok((dir.join(name), *kind));
+    }
+    if git_root.as_deref() == Some(dir) {
+      break;
+    }
+  }
+  Err("No pnpm, yarn or npm lockfile found".to_string())
+}
+
+// Parses lockfile contents. Paths are relative to the lockfile's directory: `read_manifest` reads
+// the package.json of one, `expand` lists the package directories a workspace glob matches.
+// Both read from wherever the lockfile came from, the disk or a revision.
+pub fn parse(
+  kind: LockfileKind,
+  contents: &str,
+  read_manifest: &dyn Fn(&str) -> Option<serde_json::Value>,
+  expand: &dyn Fn(&str) -> Vec<String>,
+) -> Result<Lockfile, String> {
+  let (mut importers, packages) = match kind {
+    LockfileKind::Pnpm => parse_pnpm(contents)?,
+    LockfileKind::Yarn => parse_yarn(contents, read_manifest, expand)?,
+    LockfileKind::Npm => parse_npm(contents)?,
+  };
+
+  for importer in &mut importers {
+    if importer.name.is_none() {
+      importer.name = read_manifest(&importer.path)
+        .and_then(|json| Some(json.get("name")?.as_str()?.to_string()));
+    }
+  }
+  importers.sort_by(|a, b| a.path.cmp(&b.path));
+
+  Ok(Lockfile {
+    kind,
+    path: String::new(),
+    importers,
+    packages,
+  })
+}
+
+pub fn duplicates(lockfile: &Lockfile) -> Vec<DuplicatePackage> {
+  let mut versions: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+  for package in lockfile.packages.values() {
+    versions
+      .entry(&package.name)
+      .or_default()
+      .push(&package.version);
+  }
+
+  versions
+    .into_iter()
+    .filter(|(_, versions)| versions.len() > 1)
+    .map(|(name, mut versions)| {
+      versions.sort_by(
+        |a, b| match (semver::Version::parse(a), semver::Version::parse(b)) {
+          (Ok(a), Ok(b)) => a.cmp(&b),
+          _ => a.cmp(b),
+        },
+      );
+      DuplicatePackage {
+        name: name.to_string(),
+        versions: versions.into_iter().map(String::from).collect(),
+      }
+    })
+    .collect()
+}
+
+// Breadth-first from each importer, so the first chain reaching a version is a shortest one.
+// Workspace links are not followed; the linked importer reports its own chains
+pub fn explain(lockfile: &Lockfile, name: &str, version: Option<&str>) -> Vec<DependencyChain> {
+  let mut chains = Vec::new();
+  for importer in &lockfile.importers {
+    let mut parents: HashMap<&str, (Option<&str>, DependencyKind)> = HashMap::new();
+    let mut queue: VecDeque<&str> = VecDeque::new();
+    for dependency in &importer.dependencies {
+      let Some(id) = dependency.id.as_deref() else {
+        continue;
+      };
+      if !parents.contains_key(id) {
+        parents.insert(id, (None, dependency.kind));
+        queue.push_back(id);
+      }
+    }
+
+    while let Some(id) = queue.pop_front() {
+      let Some(package) = lockfile.packages.get(id) else {
+        continue;
+      };
+      if package.name == name && version.is_none_or(|v| v == package.version) {
+        let mut chain = vec![id.to_string()];
+        let mut current = id;
+        while let Some((Some(parent), _)) = parents.get(current) {
+          chain.push(parent.to_string());
+          current = parent;
+        }
+        chain.reverse();
+        chains.push(DependencyChain {
+          importer: importer.path.clone(),
+          kind: parents[current].1,
+          chain,
+        });
+      }
+
+      let kind = parents[id].1;
+      for dependency in &package.dependencies {
+        if !parents.contains_key(dependency.as_str()) {
+          parents.insert(dependency, (Some(id), kind));
+          queue.push_back(dependency);
+        }
+      }
+    }
+  }
+  chains
+}
+
+fn parse_pnpm(contents: &str) -> Result<(Vec<Importer>, BTreeMap<String, LockedPackage>), String> {
+  let yaml: serde_yaml::Value =
+    serde_yaml::from_str(contents).map_err(|e| format!("Invalid pnpm lockfile: {}", e))?;
+
+  // Before lockfile v6 package keys read `/name/version_peer`, later `/name@version(peer)`
+  let lockfile_version = match yaml.get("lockfileVersion") {
+    Some(serde_yaml::Value::Number(number)) => number.as_f64(),
+    Some(serde_yaml::Value::String(version)) => version.parse().ok(),
+    _ => None,
+  };
+  let legacy = lockfile_version.is_some_and(|version| version < 6.0);
+
+  let mut importers = Vec::new();
+  match yaml.get("importers").and_then(|i| i.as_mapping()) {
+    Some(entries) => {
+      for (path, importer) in entries {
+        let Some(path) = path.as_str() else {
+          continue;
+        };
+        importers.push(pnpm_importer(path, importer, legacy));
+      }
+    }
+    // Single-package repositories keep the root's dependencies at the top level
+    None => importers.push(pnpm_importer(".", &yaml, legacy)),
+  }
+
+  // v9 moved dependencies from `packages` to `snapshots`
+  let mut packages: BTreeMap<String, LockedPackage> = BTreeMap::new();
+  let entries = yaml
+    .get("snapshots")
+    .or_else(|| yaml.get("packages"))
+    .and_then(|p| p.as_mapping());
+  for (key, entry) in entries.into_iter().flatten() {
+    let Some((name, version)) = key.as_str().and_then(|key| pnpm_package_id(key, legacy)) else {
+      continue;
+    };
+    let mut dependencies = Vec::new();
+    for section in ["dependencies", "optionalDependencies"] {
+      let section = entry.get(section).and_then(|d| d.as_mapping());
+      for (dependency, reference) in section.into_iter().flatten() {
+        if let (Some(dependency), Some(reference)) = (dependency.as_str(), reference.as_str()) {
+          dependencies.extend(pnpm_reference(dependency, reference, legacy));
+        }
+      }
+    }
+    insert_package(&mut packages, name, version, dependencies, None);
+  }
+
+  // Integrity stays in `packages`, keyed without the peer suffix of `snapshots` keys
+  let resolutions = yaml.get("packages").and_then(|p| p.as_mapping());
+  for (key, entry) in resolutions.into_iter().flatten() {
+    let Some((name, version)) = key.as_str().and_then(|key| pnpm_package_id(key, legacy)) else {
+      continue;
+    };
+    let integrity = entry
+      .get("resolution")
+      .and_then(|r| r.get("integrity"))
+      .and_then(|i| i.as_str());
+    if let Some(package) = packages.get_mut(&package_id(&name, &version)) {
+      package.integrity = integrity.map(String::from);
+    }
+  }
+
+  Ok((importers, packages))
+}
+
+fn pnpm_importer(path: &str, importer: &serde_yaml::Value, legacy: bool) -> Importer {
+  let mut dependencies = Vec::new();
+  for (section, kind) in IMPORTER_SECTIONS {
+    let entries = importer.get(*section).and_then(|d| d.as_mapping());
+    for (name, entry) in entries.into_iter().flatten() {
+      let Some(name) = name.as_str() else {
+        continue;
+      };
+      // `{ specifier, version }` since v6, a bare version with a separate `specifiers` map before
+      let (specifier, reference) = match entry.as_str() {
+        Some(reference) => (
+          importer
+            .get("specifiers")
+            .and_then(|s| s.get(name))
+            .and_then(|s| s.as_str()),
+          Some(reference),
+        ),
+        None => (
+          entry.get("specifier").and_then(|s| s.as_str()),
+          entry.get("version").and_then(|v| v.as_str()),
+        ),
+      };
+      let Some(reference) = reference else {
+        continue;
+      };
+
+      let link = reference
+        .strip_prefix("link:")
+        .map(|target| join_relative(path, target));
+      dependencies.push(ImporterDependency {
+        name: name.to_string(),
+        kind: *kind,
+        specifier: specifier.map(String::from),
+        id: match link {
+          Some(_) => None,
+          None => pnpm_reference(name, reference, legacy),
+        },
+        link,
+      });
+    }
+  }
+
+  Importer {
+    path: path.to_string(),
+    name: None,
+    dependencies,
+  }
+}
+
+// A dependency's resolved version, or a full package key for aliased dependencies
+fn pnpm_reference(name: &str, reference: &str, legacy: bool) -> Option<String> {
+  if reference.starts_with("link:") || reference.starts_with("file:") {
+    return None;
+  }
+  let version = reference.split('(').next().unwrap_or(reference);
+  let aliased = !legacy && version.get(1..).is_some_and(|rest| rest.contains('@'));
+  if version.starts_with('/') || aliased {
+    if let Some((name, version)) = pnpm_package_id(reference, legacy) {
+      return Some(package_id(&name, &version));
+    }
+  }
+  let version = if legacy {
+    version.split('_').next().unwrap_or(version)
+  } else {
+    version
+  };
+  Some(package_id(name, version))
+}
+
+fn pnpm_package_id(key: &str, legacy: bool) -> Option<(String, String)> {
+  let key = key.trim_start_matches('/');
+  let key = key.split('(').next()?;
+  let (name, version) = if legacy {
+    let (name, version) = key.rsplit_once('/')?;
+    (name, version.split('_').next()?)
+  } else {
+    let at = key.get(1..)?.find('@')? + 1;
+    (&key[..at], &key[at + 1..])
+  };
+  if name.is_empty() || version.is_empty() {
+    return None;
+  }
+  Some((name.to_string(), version.to_string()))
+}
+
+struct YarnEntry {
+  name: String,
+  version: String,
+  integrity: Option<String>,
+  dependencies: Vec<(String, String)>,
+}
+
+struct YarnWorkspace {
+  // Relative to the lockfile's directory, `.` for the root
+  path: String,
+  name: Option<String>,
+  // As locked by Berry, used when the workspace has no manifest to read
+  dependencies: Vec<(String, String)>,
+}
+
+// Yarn resolves importers' `name@range` descriptors through the lockfile. Berry lists the
+// workspaces as `@workspace:` entries, Yarn 1 only through the root manifest's `workspaces`.
+fn parse_yarn(
+  contents: &str,
+  read_manifest: &dyn Fn(&str) -> Option<serde_json::Value>,
+  expand: &dyn Fn(&str) -> Vec<String>,
+) -> Result<(Vec<Importer>, BTreeMap<String, LockedPackage>), String> {
+  let (entries, descriptors, workspaces) = if contents.contains("__metadata:") {
+    parse_yarn_berry(contents)?
+  } else {
+    let (entries, descriptors) = parse_yarn_classic(contents);
+    (entries, descriptors, yarn_workspaces(read_manifest, expand))
+  };
+  let resolve = |name: &str, range: &str| -> Option<String> {
+    let index = descriptors
+      .get(&format!("{}@{}", name, range))
+      .or_else(|| descriptors.get(&format!("{}@npm:{}", name, range)))?;
+    let entry = entries
+      .get(*index)
+      .filter(|entry| !entry.version.is_empty())?;
+    Some(package_id(&entry.name, &entry.version))
+  };
+
+  let mut packages: BTreeMap<String, LockedPackage> = BTreeMap::new();
+  for entry in entries.iter().filter(|entry| !entry.version.is_empty()) {
+    let dependencies = entry
+      .dependencies
+      .iter()
+      .filter_map(|(name, range)| resolve(name, range))
+      .collect();
+    insert_package(
+      &mut packages,
+      entry.name.clone(),
+      entry.version.clone(),
+      dependencies,
+      entry.integrity.clone(),
+    );
+  }
+
+  let manifests: Vec<(&YarnWorkspace, Option<serde_json::Value>)> = workspaces
+    .iter()
+    .map(|workspace| (workspace, read_manifest(&workspace.path)))
+    .collect();
+  let name_of = |(workspace, manifest): &(&YarnWorkspace, Option<serde_json::Value>)| {
+    manifest
+      .as_ref()
+      .and_then(|json| json.get("name")?.as_str())
+      .or(workspace.name.as_deref())
+      .map(String::from)
+  };
+  let links: HashMap<String, &str> = manifests
+    .iter()
+    .filter_map(|side| Some((name_of(side)?, side.0.path.as_str())))
+    .collect();
+
+  let mut importers = Vec::new();
+  for side in &manifests {
+    let (workspace, manifest) = side;
+    let ranges: Vec<(&str, &str, DependencyKind)> = match manifest {
+      Some(json) => IMPORTER_SECTIONS
+        .iter()
+        .flat_map(|(section, kind)| {
+          let section = json.get(*section).and_then(|d| d.as_object());
+          section
+            .into_iter()
+            .flatten()
+            .map(|(name, range)| (name.as_str(), range.as_str().unwrap_or_default(), *kind))
+        })
+        .collect(),
+      // Yarn 1 workspaces are only known through their manifest
+      None if workspace.name.is_none() => continue,
+      None => workspace
+        .dependencies
+        .iter()
+        .map(|(name, range)| (name.as_str(), range.as_str(), DependencyKind::Normal))
+        .collect(),
+    };
+
+    let dependencies = ranges
+      .into_iter()
+      .map(|(name, range, kind)| {
+        let link = links.get(name).map(|path| path.to_string());
+        ImporterDependency {
+          name: name.to_string(),
+          kind,
+          specifier: Some(range.to_string()),
+          id: match link {
+            Some(_) => None,
+            None => resolve(name, range),
+          },
+          link,
+        }
+      })
+      .collect();
+    importers.push(Importer {
+      path: workspace.path.clone(),
+      name: name_of(side),
+      dependencies,
+    });
+  }
+
+  Ok((importers, packages))
+}
+
+// Yarn 1 workspaces: the root and the package directories its `workspaces` globs match
+fn yarn_workspaces(
+  read_manifest: &dyn Fn(&str) -> Option<serde_json::Value>,
+  expand: &dyn Fn(&str) -> Vec<String>,
+) -> Vec<YarnWorkspace> {
+  let root = read_manifest(".");
+  let patterns: Vec<&str> = root
+    .as_ref()
+    .and_then(|json| json.get("workspaces"))
+    .map(|w| w.get("packages").unwrap_or(w))
+    .and_then(|w| w.as_array())
+    .into_iter()
+    .flatten()
+    .filter_map(|p| p.as_str())
+    .collect();
+  let excluded: Vec<glob::Pattern> = patterns
+    .iter()
+    .filter_map(|p| p.strip_prefix('!'))
+    .filter_map(|p| glob::Pattern::new(p.trim_end_matches('/')).ok())
+    .collect();
+
+  let mut paths = vec![".".to_string()];
+  for pattern in patterns.iter().filter(|p| !p.starts_with('!')) {
+    for path in expand(pattern.trim_end_matches('/')) {
+      if path.is_empty()
+        || path.split('/').any(|part| part == "node_modules")
+        || excluded.iter().any(|p| p.matches(&path))
+        || paths.contains(&path)
+      {
+        continue;
+      }
+      paths.push(path);
+    }
+  }
+
+  paths
+    .into_iter()
+    .map(|path| YarnWorkspace {
+      path,
+      name: None,
+      dependencies: Vec::new(),
+    })
+    .collect()
+}
+
+type YarnEntries = (Vec<YarnEntry>, HashMap<String, usize>);
+
+// Yarn 2+ writes YAML keyed by comma separated descriptors like `"foo@npm:^1.0.0, foo@npm:^1.1.0"`
+fn parse_yarn_berry(
+  contents: &str,
+) -> Result<(Vec<YarnEntry>, HashMap<String, usize>, Vec<YarnWorkspace>), String> {
+  let yaml: serde_yaml::Mapping =
+    serde_yaml::from_str(contents).map_err(|e| format!("Invalid yarn lockfile: {}", e))?;
+
+  let mut entries = Vec::new();
+  let mut descriptors = HashMap::new();
+  let mut workspaces = Vec::new();
+  for (key, entry) in &yaml {
+    let Some(key) = key.as_str().filter(|key| *key != "__metadata") else {
+      continue;
+    };
+    let dependencies: Vec<(String, String)> = ["dependencies", "optionalDependencies"]
+      .iter()
+      .filter_map(|section| entry.get(*section).and_then(|d| d.as_mapping()))
+      .flatten()
+      .filter_map(|(name, range)| Some((name.as_str()?.to_string(), yaml_scalar(range)?)))
+      .collect();
+
+    // Workspaces are importers, not installed packages: `name@workspace:path`
+    let resolution = entry
+      .get("resolution")
+      .and_then(|r| r.as_str())
+      .unwrap_or_default();
+    if let Some((name, path)) = resolution.split_once("@workspace:") {
+      let path = path.trim_end_matches('/');
+      workspaces.push(YarnWorkspace {
+        path: if path.is_empty() { "." } else { path }.to_string(),
+        name: Some(name.to_string()).filter(|name| !name.is_empty()),
+        dependencies,
+      });
+      continue;
+    }
+
+    let (Some(name), Some(version)) = (
+      key.split(',').next().and_then(descriptor_name),
+      entry.get("version").and_then(yaml_scalar),
+    ) else {
+      continue;
+    };
+
+    for descriptor in key.split(',') {
+      descriptors.insert(descriptor.trim().to_string(), entries.len());
+    }
+    // Berry's `checksum` hashes its own archive format, not the registry tarball
+    entries.push(YarnEntry {
+      name: name.to_string(),
+      version,
+      integrity: None,
+      dependencies,
+    });
+  }
+  Ok((entries, descriptors, workspaces))
+}
+
+// Yarn 1 writes its own indentation based format:
+//   "foo@^1.0.0", foo@^1.1.0:
+//     version "1.2.0"
+//     dependencies:
+//       bar "^2.0.0"
+fn parse_yarn_classic(contents: &str) -> YarnEntries {
+  let mut entries: Vec<YarnEntry> = Vec::new();
+  let mut descriptors = HashMap::new();
+  let mut in_dependencies = false;
+
+  for line in contents.lines() {
+    if line.trim().is_empty() || line.trim_start().starts_with('#') {
+      continue;
+    }
+    let indent = line.len() - line.trim_start().len();
+    let line = line.trim();
+
+    if indent == 0 {
+      let keys: Vec<&str> = line
+        .trim_end_matches(':')
+        .split(", ")
+        .map(|key| key.trim_matches('"'))
+        .collect();
+      let Some(name) = keys.first().and_then(|key| descriptor_name(key)) else {
+        continue;
+      };
+      for key in keys.iter() {
+        descriptors.insert(key.to_string(), entries.len());
+      }
+      entries.push(YarnEntry {
+        name: name.to_string(),
+        version: String::new(),
+        integrity: None,
+        dependencies: Vec::new(),
+      });
+      in_dependencies = false;
+      continue;
+    }
+
+    let Some(entry) = entries.last_mut() else {
+      continue;
+    };
+    if indent <= 2 {
+      in_dependencies = line == "dependencies:" || line == "optionalDependencies:";
+      if let Some(version) = line.strip_prefix("version ") {
+        entry.version = version.trim_matches('"').to_string();
+      }
+      if let Some(integrity) = line.strip_prefix("integrity ") {
+        entry.integrity = Some(integrity.trim_matches('"').to_string());
+      }
+    } else if in_dependencies {
+      if let Some((name, range)) = split_classic_pair(line) {
+        entry.dependencies.push((name, range));
+      }
+    }
+  }
+
+  (entries, descriptors)
+}
+
+fn split_classic_pair(line: &str) -> Option<(String, String)> {
+  let (name, range) = if let Some(rest) = line.strip_prefix('"') {
+    let (name, range) = rest.split_once('"')?;
+    (name, range)
+  } else {
+    line.split_once(' ')?
+  };
+  Some((name.to_string(), range.trim().trim_matches('"').to_string()))
+}
+
+// `@scope/name@range` -> `@scope/name`
+fn descriptor_name(descriptor: &str) -> Option<&str> {
+  let descriptor = descriptor.trim().trim_matches('"');
+  let at = descriptor.get(1..)?.find('@')? + 1;
+  Some(&descriptor[..at])
+}
+
+fn yaml_scalar(value: &serde_yaml::Value) -> Option<String> {
+  match value {
+    serde_yaml::Value::String(value) => Some(value.clone()),
+    serde_yaml::Value::Number(value) => Some(value.to_string()),
+    _ => None,
+  }
+}
+
+// npm 7+ lockfiles list every installed location under `packages`; dependencies resolve like
+// node does, from the nearest `node_modules` upwards
+fn parse_npm(contents: &str) -> Result<(Vec<Importer>, BTreeMap<String, LockedPackage>), String> {
+  let json: serde_json::Value =
+    serde_json::from_str(contents).map_err(|e| format!("Invalid npm lockfile: {}", e))?;
+  let locations = json
+    .get("packages")
+    .and_then(|p| p.as_object())
+    .ok_or("npm lockfiles before version 2 are not supported")?;
+
+  // Installed location -> package id, or the importer path for workspace links
+  let mut resolved: HashMap<&str, Result<String, String>> = HashMap::new();
+  for (location, entry) in locations {
+    if !is_node_modules(location) {
+      continue;
+    }
+    if entry.get("link").and_then(|l| l.as_bool()) == Some(true) {
+      let target = entry
+        .get("resolved")
+        .and_then(|r| r.as_str())
+        .unwrap_or_default();
+      resolved.insert(location, Err(target.to_string()));
+      continue;
+    }
+    let name = entry
+      .get("name")
+      .and_then(|n| n.as_str())
+      .unwrap_or_else(|| location_name(location));
+    let version = entry
+      .get("version")
+      .and_then(|v| v.as_str())
+      .unwrap_or_default();
+    resolved.insert(location, Ok(package_id(name, version)));
+  }
+
+  let lookup = |from: &str, name: &str| -> Option<&Result<String, String>> {
+    let mut base = Some(from);
+    while let Some(current) = base {
+      let candidate = if current.is_empty() {
+        format!("node_modules/{}", name)
+      } else {
+        format!("{}/node_modules/{}", current, name)
+      };
+      if let Some(found) = resolved.get(candidate.as_str()) {
+        return Some(found);
+      }
+      base = parent_location(current);
+    }
+    None
+  };
+
+  let mut packages: BTreeMap<String, LockedPackage> = BTreeMap::new();
+  let mut importers = Vec::new();
+  for (location, entry) in locations {
+    if !is_node_modules(location) {
+      let mut dependencies = Vec::new();
+      for (section, kind) in IMPORTER_SECTIONS {
+        let section = entry.get(*section).and_then(|d| d.as_object());
+        for (name, range) in section.into_iter().flatten() {
+          let found = lookup(location, name);
+          dependencies.push(ImporterDependency {
+            name: name.clone(),
+            kind: *kind,
+            specifier: range.as_str().map(String::from),
+            id: found.and_then(|found| found.clone().ok()),
+            link: found.and_then(|found| found.clone().err()),
+          });
+        }
+      }
+      importers.push(Importer {
+        path: if location.is_empty() {
+          ".".to_string()
+        } else {
+          location.clone()
+        },
+        name: entry.get("name").and_then(|n| n.as_str()).map(String::from),
+        dependencies,
+      });
+      continue;
+    }
+
+    let Some(Ok(id)) = resolved.get(location.as_str()) else {
+      continue;
+    };
+    // Installed peers are part of the tree since npm 7
+    let dependencies = ["dependencies", "optionalDependencies", "peerDependencies"]
+      .iter()
+      .filter_map(|section| entry.get(*section).and_then(|d| d.as_object()))
+      .flat_map(|section| section.keys())
+      .filter_map(|name| lookup(location, name)?.clone().ok())
+      .collect();
+    let (name, version) = split_package_id(id);
+    insert_package(
+      &mut packages,
+      name.to_string(),
+      version.to_string(),
+      dependencies,
+      entry
+        .get("integrity")
+        .and_then(|i| i.as_str())
+        .map(String::from),
+    );
+  }
+
+  Ok((importers, packages))
+}
+
+// Packages of a Cargo.lock, with checksums turned into `sha256-` integrity strings. Dependencies
+// read `name`, or `name version` when several versions are locked
+pub fn parse_cargo_lock(contents: &str) -> Result<BTreeMap<String, LockedPackage>, String> {
+  let document: toml_edit::DocumentMut = contents
+    .parse()
+    .map_err(|e| format!("Invalid Cargo.lock: {}", e))?;
+  let entries = document
+    .get("package")
+    .and_then(|p| p.as_array_of_tables())
+    .ok_or("Cargo.lock has no packages")?;
+
+  let locked: Vec<(&str, &str)> = entries
+    .iter()
+    .filter_map(|entry| {
+      Some((
+        entry.get("name")?.as_str()?,
+        entry.get("version")?.as_str()?,
+      ))
+    })
+    .collect();
+
+  let mut packages = BTreeMap::new();
+  for entry in entries.iter() {
+    let (Some(name), Some(version)) = (
+      entry.get("name").and_then(|n| n.as_str()),
+      entry.get("version").and_then(|v| v.as_str()),
+    ) else {
+      continue;
+    };
+    let dependencies = entry
+      .get("dependencies")
+      .and_then(|d| d.as_array())
+      .into_iter()
+      .flatten()
+      .filter_map(|dependency| {
+        let mut parts = dependency.as_str()?.split_whitespace();
+        let name = parts.next()?;
+        let version = match parts.next() {
+          Some(version) => version,
+          None => locked.iter().find(|(locked, _)| *locked == name)?.1,
+        };
+        Some(package_id(name, version))
+      })
+      .collect();
+    let integrity = entry
+      .get("checksum")
+      .and_then(|c| c.as_str())
+      .and_then(hex_to_sri);
+    insert_package(
+      &mut packages,
+      name.to_string(),
+      version.to_string(),
+      dependencies,
+      integrity,
+    );
+  }
+  Ok(packages)
+}
+
+fn hex_to_sri(checksum: &str) -> Option<String> {
+  let bytes = (0..checksum.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(checksum.get(i..i + 2)?, 16).ok())
+    .collect::<Option<Vec<u8>>>()?;
+  Some(format!("sha256-{}", STANDARD.encode(bytes)))
+}
+
+fn is_node_modules(location: &str) -> bool {
+  location.starts_with("node_modules/") || location.contains("/node_modules/")
+}
+
+// `a/node_modules/@scope/b` -> `@scope/b`
+fn location_name(location: &str) -> &str {
+  location
+    .rsplit_once("node_modules/")
+    .map_or(location, |(_, name)| name)
+}
+
+// Where node looks next: the enclosing package's location, ending at the root
+fn parent_location(location: &str) -> Option<&str> {
+  if location.is_empty() {
+    return None;
+  }
+  Some(
+    location
+      .rfind("/node_modules/")
+      .map_or("", |index| &location[..index]),
+  )
+}
+
+fn insert_package(
+  packages: &mut BTreeMap<String, LockedPackage>,
+  name: String,
+  version: String,
+  dependencies: Vec<String>,
+  integrity: Option<String>,
+) {
+  let package = packages
+    .entry(package_id(&name, &version))
+    .or_insert_with(|| LockedPackage {
+      name,
+      version,
+      dependencies: Vec::new(),
+      integrity: None,
+    });
+  package.integrity = package.integrity.take().or(integrity);
+  package.dependencies.extend(dependencies);
+  package.dependencies.sort();
+  package.dependencies.dedup();
+}
+
+pub fn package_id(name: &str, version: &str) -> String {
+  format!("{}@{}", name, version)
+}
+
+pub fn split_package_id(id: &str) -> (&str, &str) {
+  match id.get(1..).and_then(|rest| rest.find('@')) {
+    Some(at) => (&id[..at + 1], &id[at + 2..]),
+    None => (id, ""),
+  }
+}
+
+// Lexically joins `target` onto the importer path, as lockfiles store links relative to it
+fn join_relative(base: &str, target: &str) -> String {
+  let mut parts: Vec<String> = Vec::new();
+  for component in Path::new(base).join(target).components() {
+    match component {
+      Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+      Component::ParentDir => {
+        parts.pop();
+      }
+      _ => {}
+    }
+  }
+  if parts.is_empty() {
+    ".".to_string()
+  } else {
+    parts.join("/")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  // Parses with manifests and package directories given in place of a checkout
+  fn parse_with(
+    kind: LockfileKind,
+    contents: &str,
+    manifests: &[(&str, serde_json::Value)],
+  ) -> Lockfile {
+    let read_manifest = |relative: &str| {
+      manifests
+        .iter()
+        .find(|(path, _)| *path == relative)
+        .map(|(_, json)| json.clone())
+    };
+    let expand = |pattern: &str| -> Vec<String> {
+      let pattern = glob::Pattern::new(pattern).unwrap();
+      manifests
+        .iter()
+        .map(|(path, _)| path.to_string())
+        .filter(|path| *path != "." && pattern.matches(path))
+        .collect()
+    };
+    parse(kind, contents, &read_manifest, &expand).unwrap()
+  }
+
+  fn dependency<'a>(lockfile: &'a Lockfile, importer: &str, name: &str) -> &'a ImporterDependency {
+    lockfile
+      .importers
+      .iter()
+      .find(|i| i.path == importer)
+      .and_then(|i| i.dependencies.iter().find(|d| d.name == name))
+      .unwrap_or_else(|| panic!("{} has no dependency {}", importer, name))
+  }
+
+  #[test]
+  fn parses_pnpm_v5_peer_suffixes_and_links() {
+    let contents = r#"
+lockfileVersion: 5.4
+
+importers:
+  .:
+    specifiers:
+      '@scope/lib': ^1.0.0
+      utils: link:packages/utils
+    dependencies:
+      '@scope/lib': 1.2.0_react@18.2.0
+      utils: link:packages/utils
+  packages/utils:
+    specifiers:
+      react: ^18.0.0
+    devDependencies:
+      react: 18.2.0
+
+packages:
+
+  /@scope/lib/1.2.0_react@18.2.0:
+    resolution: {integrity: sha512-lib}
+    dependencies:
+      react: 18.2.0
+
+  /react/18.2.0:
+    resolution: {integrity: sha512-react}
+"#;
+    let lockfile = parse_with(
+      LockfileKind::Pnpm,
+      contents,
+      &[("packages/utils", json!({ "name": "utils" }))],
+    );
+
+    let lib = dependency(&lockfile, ".", "@scope/lib");
+    assert_eq!(lib.id.as_deref(), Some("@scope/lib@1.2.0"));
+    assert_eq!(lib.specifier.as_deref(), Some("^1.0.0"));
+    let utils = dependency(&lockfile, ".", "utils");
+    assert_eq!(utils.link.as_deref(), Some("packages/utils"));
+    assert_eq!(utils.id, None);
+    let react = dependency(&lockfile, "packages/utils", "react");
+    assert_eq!(react.kind, DependencyKind::Dev);
+    assert_eq!(react.id.as_deref(), Some("react@18.2.0"));
+    assert_eq!(lockfile.importers[1].name.as_deref(), Some("utils"));
+
+    let package = &lockfile.packages["@scope/lib@1.2.0"];
+    assert_eq!(package.dependencies, vec!["react@18.2.0"]);
+    assert_eq!(package.integrity.as_deref(), Some("sha512-lib"));
+  }
+
+  #[test]
+  fn parses_pnpm_v6_peer_suffixes_and_aliases() {
+    let contents = r#"
+lockfileVersion: '6.0'
+
+importers:
+  .:
+    dependencies:
+      '@scope/lib':
+        specifier: ^1.0.0
+        version: 1.2.0(react@18.2.0)
+      string-width-cjs:
+        specifier: npm:string-width@^4.2.0
+        version: /string-width@4.2.3
+
+packages:
+
+  '/@scope/lib@1.2.0(react@18.2.0)':
+    resolution: {integrity: sha512-lib}
+    dependencies:
+      react: 18.2.0
+
+  /react@18.2.0:
+    resolution: {integrity: sha512-react}
+
+  /string-width@4.2.3:
+    resolution: {integrity: sha512-sw}
+"#;
+    let lockfile = parse_with(LockfileKind::Pnpm, contents, &[]);
+
+    let lib = dependency(&lockfile, ".", "@scope/lib");
+    assert_eq!(lib.id.as_deref(), Some("@scope/lib@1.2.0"));
+    let alias = dependency(&lockfile, ".", "string-width-cjs");
+    assert_eq!(alias.id.as_deref(), Some("string-width@4.2.3"));
+    assert_eq!(
+      lockfile.packages["@scope/lib@1.2.0"].dependencies,
+      vec!["react@18.2.0"]
+    );
+    assert_eq!(
+      lockfile.packages["string-width@4.2.3"].integrity.as_deref(),
+      Some("sha512-sw")
+    );
+  }
+
+  #[test]
+  fn parses_pnpm_v9_snapshots() {
+    let contents = r#"
+lockfileVersion: '9.0'
+
+importers:
+
+  .:
+    dependencies:
+      '@scope/lib':
+        specifier: ^1.0.0
+        version: 1.2.0(react@18.2.0)
+      string-width-cjs:
+        specifier: npm:string-width@^4.2.0
+        version: string-width@4.2.3
+      utils:
+        specifier: workspace:*
+        version: link:packages/utils
+
+  packages/utils: {}
+
+packages:
+
+  '@scope/lib@1.2.0':
+    resolution: {integrity: sha512-lib}
+
+  react@18.2.0:
+    resolution: {integrity: sha512-react}
+
+  string-width@4.2.3:
+    resolution: {integrity: sha512-sw}
+
+snapshots:
+
+  '@scope/lib@1.2.0(react@18.2.0)':
+    dependencies:
+      react: 18.2.0
+
+  react@18.2.0: {}
+
+  string-width@4.2.3: {}
+"#;
+    let lockfile = parse_with(LockfileKind::Pnpm, contents, &[]);
+
+    assert_eq!(
+      dependency(&lockfile, ".", "@scope/lib").id.as_deref(),
+      Some("@scope/lib@1.2.0")
+    );
+    assert_eq!(
+      dependency(&lockfile, ".", "string-width-cjs").id.as_deref(),
+      Some("string-width@4.2.3")
+    );
+    assert_eq!(
+      dependency(&lockfile, ".", "utils").link.as_deref(),
+      Some("packages/utils")
+    );
+    let lib = &lockfile.packages["@scope/lib@1.2.0"];
+    assert_eq!(lib.dependencies, vec!["react@18.2.0"]);
+    assert_eq!(lib.integrity.as_deref(), Some("sha512-lib"));
+    assert_eq!(lockfile.packages.len(), 3);
+  }
+
+  #[test]
+  fn parses_yarn_classic_with_workspaces_from_the_root_manifest() {
+    let contents = r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@scope/lib@^1.0.0", "@scope/lib@^1.1.0":
+  version "1.2.0"
+  resolved "https://registry.yarnpkg.com/@scope/lib/-/lib-1.2.0.tgz"
+  integrity sha512-lib
+  dependencies:
+    react "^18.0.0"
+
+react@^18.0.0:
+  version "18.2.0"
+  integrity sha512-react
+"#;
+    let lockfile = parse_with(
+      LockfileKind::Yarn,
+      contents,
+      &[
+        (
+          ".",
+          json!({
+            "name": "root",
+            "workspaces": ["packages/*", "!packages/ignored"],
+            "dependencies": { "@scope/lib": "^1.1.0" }
+          }),
+        ),
+        (
+          "packages/app",
+          json!({
+            "name": "app",
+            "dependencies": { "react": "^18.0.0", "utils": "1.0.0" }
+          }),
+        ),
+        ("packages/utils", json!({ "name": "utils" })),
+        ("packages/ignored", json!({ "name": "ignored" })),
+      ],
+    );
+
+    let paths: Vec<&str> = lockfile.importers.iter().map(|i| i.path.as_str()).collect();
+    assert_eq!(paths, vec![".", "packages/app", "packages/utils"]);
+    assert_eq!(
+      dependency(&lockfile, ".", "@scope/lib").id.as_deref(),
+      Some("@scope/lib@1.2.0")
+    );
+    assert_eq!(
+      dependency(&lockfile, "packages/app", "react").id.as_deref(),
+      Some("react@18.2.0")
+    );
+    let utils = dependency(&lockfile, "packages/app", "utils");
+    assert_eq!(utils.link.as_deref(), Some("packages/utils"));
+    assert_eq!(utils.id, None);
+
+    let lib = &lockfile.packages["@scope/lib@1.2.0"];
+    assert_eq!(lib.dependencies, vec!["react@18.2.0"]);
+    assert_eq!(lib.integrity.as_deref(), Some("sha512-lib"));
+  }
+
+  #[test]
+  fn parses_yarn_berry_workspaces_from_the_lockfile() {
+    let contents = r#"# This file is generated by running "yarn install" inside your project.
+
+__metadata:
+  version: 6
+  cacheKey: 8
+
+"@scope/lib@npm:^1.0.0":
+  version: 1.2.0
+  resolution: "@scope/lib@npm:1.2.0"
+  dependencies:
+    react: "npm:^18.0.0"
+  checksum: 0123abcd
+  languageName: node
+  linkType: hard
+
+"app@workspace:packages/app":
+  version: 0.0.0-use.local
+  resolution: "app@workspace:packages/app"
+  dependencies:
+    "@scope/lib": "npm:^1.0.0"
+    utils: "workspace:^"
+  languageName: unknown
+  linkType: soft
+
+"react@npm:^18.0.0":
+  version: 18.2.0
+  resolution: "react@npm:18.2.0"
+  languageName: node
+  linkType: hard
+
+"root@workspace:.":
+  version: 0.0.0-use.local
+  resolution: "root@workspace:."
+  languageName: unknown
+  linkType: soft
+
+"utils@workspace:^, utils@workspace:packages/utils":
+  version: 0.0.0-use.local
+  resolution: "utils@workspace:packages/utils"
+  languageName: unknown
+  linkType: soft
+"#;
+    // No manifests: the lockfile alone lists the workspaces and their dependencies
+    let lockfile = parse_with(LockfileKind::Yarn, contents, &[]);
+
+    let importers: Vec<(&str, Option<&str>)> = lockfile
+      .importers
+      .iter()
+      .map(|i| (i.path.as_str(), i.name.as_deref()))
+      .collect();
+    assert_eq!(
+      importers,
+      vec![
+        (".", Some("root")),
+        ("packages/app", Some("app")),
+        ("packages/utils", Some("utils")),
+      ]
+    );
+    assert_eq!(
+      dependency(&lockfile, "packages/app", "@scope/lib")
+        .id
+        .as_deref(),
+      Some("@scope/lib@1.2.0")
+    );
+    assert_eq!(
+      dependency(&lockfile, "packages/app", "utils")
+        .link
+        .as_deref(),
+      Some("packages/utils")
+    );
+    assert_eq!(
+      lockfile.packages["@scope/lib@1.2.0"].dependencies,
+      vec!["react@18.2.0"]
+    );
+    assert!(!lockfile.packages.keys().any(|id| id.contains("use.local")));
+  }
+
+  #[test]
+  fn parses_npm_nested_installs_aliases_and_links() {
+    let contents = r#"{
+  "name": "root",
+  "lockfileVersion": 3,
+  "packages": {
+    "": {
+      "name": "root",
+      "workspaces": ["packages/*"],
+      "dependencies": {
+        "@scope/lib": "^1.0.0",
+        "string-width-cjs": "npm:string-width@^4.2.0"
+      }
+    },
+    "packages/app": {
+      "name": "app",
+      "version": "1.0.0",
+      "dependencies": { "react": "^17.0.0", "utils": "*" }
+    },
+    "node_modules/@scope/lib": {
+      "version": "1.2.0",
+      "integrity": "sha512-lib",
+      "dependencies": { "react": "^18.0.0" }
+    },
+    "node_modules/app": { "resolved": "packages/app", "link": true },
+    "node_modules/react": { "version": "18.2.0" },
+    "node_modules/string-width-cjs": { "name": "string-width", "version": "4.2.3" },
+    "node_modules/utils": { "resolved": "packages/utils", "link": true },
+    "packages/app/node_modules/react": { "version": "17.0.2" }
+  }
+}"#;
+    let lockfile = parse_with(LockfileKind::Npm, contents, &[]);
+
+    assert_eq!(
+      dependency(&lockfile, ".", "@scope/lib").id.as_deref(),
+      Some("@scope/lib@1.2.0")
+    );
+    assert_eq!(
+      dependency(&lockfile, ".", "string-width-cjs").id.as_deref(),
+      Some("string-width@4.2.3")
+    );
+    assert_eq!(
+      dependency(&lockfile, "packages/app", "react").id.as_deref(),
+      Some("react@17.0.2")
+    );
+    assert_eq!(
+      dependency(&lockfile, "packages/app", "utils")
+        .link
+        .as_deref(),
+      Some("packages/utils")
+    );
+    let lib = &lockfile.packages["@scope/lib@1.2.0"];
+    assert_eq!(lib.dependencies, vec!["react@18.2.0"]);
+    assert_eq!(lib.integrity.as_deref(), Some("sha512-lib"));
+
+    let duplicates = duplicates(&lockfile);
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].name, "react");
+    assert_eq!(duplicates[0].versions, vec!["17.0.2", "18.2.0"]);
+  }
+
+  #[test]
+  fn splits_scoped_package_ids() {
+    assert_eq!(
+      split_package_id("@scope/lib@1.2.0"),
+      ("@scope/lib", "1.2.0")
+    );
+    assert_eq!(split_package_id("react@18.2.0"), ("react", "18.2.0"));
+    assert_eq!(join_relative("packages/app", "../utils"), "packages/utils");
+  }
+}
@@ -0,0 +1,342 @@
+use crate::content;
+use crate::sandbox::{self, FileError};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use encoding_rs::Encoding;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom};
+
+// Largest file the preview pane receives whole; bigger ones are read in chunks
+const PREVIEW_LIMIT: u64 = 2 * 1024 * 1024;
+// Upper bound for a single chunk
+const MAX_CHUNK_BYTES: u64 = 1024 * 1024;
+// Bytes looked at to tell the content type and encoding
+const SNIFF_LEN: u64 = 8192;
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FilePreview {
+  Text {
+    path: String,
+    size: u64,
+    mime: &'static str,
+    encoding: &'static str,
+    content: String,
+  },
+  Image {
+    path: String,
+    size: u64,
+    mime: &'static str,
+    base64: String,
+  },
+  Binary {
+    path: String,
+    size: u64,
+    mime: &'static str,
+  },
+  TooLarge {
+    path: String,
+    size: u64,
+    mime: &'static str,
+    limit: u64,
+  },
+}
+
+#[derive(Serialize)]
+pub struct FileChunk {
+  pub path: String,
+  pub size: u64,
+  pub mime: &'static str,
+  pub binary: bool,
+  pub offset: u64,
+  pub length: u64,
+  // 1-based and inclusive, only set for line range reads
+  pub start_line: Option<usize>,
+  pub end_line: Option<usize>,
+  pub eof: bool,
+  pub encoding: Option<&'static str>,
+  pub content: Option<String>,
+  // Binary chunks are returned base64 encoded
+  pub base64: Option<String>,
+}
+
+struct OpenedFile {
+  file: File,
+  size: u64,
+  mime: &'static str,
+  binary: bool,
+  encoding: &'static Encoding,
+  bom_len: usize,
+}
+
+fn open(path: &str) -> Result<OpenedFile, FileError> {
+  let io_error = |e: std::io::Error| FileError::Io {
+    path: path.to_string(),
+    message: e.to_string(),
+  };
+
+  let resolved = sandbox::resolve(path)?;
+  let mut file = File::open(&resolved).map_err(io_error)?;
+  let size = file.metadata().map_err(io_error)?.len();
+
+  let mut head = Vec::new();
+  (&mut file)
+    .take(SNIFF_LEN)
+    .read_to_end(&mut head)
+    .map_err(io_error)?;
+  file.seek(SeekFrom::Start(0)).map_err(io_error)?;
+
+  let (encoding, bom_len) = content::detect_encoding(&head);
+  Ok(OpenedFile {
+    file,
+    mime: content::sniff_mime(&resolved, &head),
+    binary: content::is_binary(&head),
+    size,
+    encoding,
+    bom_len,
+  })
+}
+
+// Whole-file preview: decoded text, base64 images, or metadata when that is all we can show
+#[tauri::command]
+pub fn preview_file(path: String) -> Result<FilePreview, FileError> {
+  let mut opened = open(&path)?;
+  let (size, mime) = (opened.size, opened.mime);
+  let image = mime.starts_with("image/");
+
+  if opened.binary && !image {
+    return Ok(FilePreview::Binary { path, size, mime });
+  }
+  if size > PREVIEW_LIMIT {
+    return Ok(FilePreview::TooLarge {
+      path,
+      size,
+      mime,
+      limit: PREVIEW_LIMIT,
+    });
+  }
+
+  let mut bytes = Vec::with_capacity(size as usize);
+  opened
+    .file
+    .read_to_end(&mut bytes)
+    .map_err(|e| FileError::Io {
+      path: path.clone(),
+      message: e.to_string(),
+    })?;
+
+  if image {
+    return Ok(FilePreview::Image {
+      path,
+      size,
+      mime,
+      base64: STANDARD.encode(&bytes),
+    });
+  }
+
+  Ok(FilePreview::Text {
+    path,
+    size,
+    mime,
+    encoding: opened.encoding.name(),
+    content: content::decode_with(opened.encoding, &bytes[opened.bom_len..]),
+  })
+}
+
+// Reads part of a file, either `length` bytes from `offset` or the lines `start_line..=end_line`
+#[tauri::command]
+pub fn read_file_chunk(
+  path: String,
+  offset: Option<u64>,
+  length: Option<u64>,
+  start_line: Option<usize>,
+  end_line: Option<usize>,
+) -> Result<FileChunk, FileError> {
+  let opened = open(&path)?;
+  let limit = length.unwrap_or(MAX_CHUNK_BYTES).min(MAX_CHUNK_BYTES);
+
+  if start_line.is_none() && end_line.is_none() {
+    return read_bytes(path, opened, offset.unwrap_or(0), limit);
+  }
+  if opened.binary {
+    return Err(FileError::Io {
+      path,
+      message: "Line ranges are only available for text files".to_string(),
+    });
+  }
+  read_lines(
+    path,
+    opened,
+    start_line.unwrap_or(1).max(1),
+    end_line,
+    limit,
+  )
+}
+
+fn read_bytes(
+  path: String,
+  mut opened: OpenedFile,
+  offset: u64,
+  limit: u64,
+) -> Result<FileChunk, FileError> {
+  let io_error = |e: std::io::Error| FileError::Io {
+    path: path.clone(),
+    message: e.to_string(),
+  };
+
+  // Never start inside the BOM, it is not part of the text, nor inside a code unit
+  let offset = if opened.binary {
+    offset
+  } else {
+    let bom_len = opened.bom_len as u64;
+    let unit = content::code_unit_len(opened.encoding) as u64;
+    bom_len + (offset.max(bom_len) - bom_len) / unit * unit
+  };
+  opened
+    .file
+    .seek(SeekFrom::Start(offset))
+    .map_err(io_error)?;
+  let mut bytes = Vec::with_capacity(limit as usize);
+  (&mut opened.file)
+    .take(limit)
+    .read_to_end(&mut bytes)
+    .map_err(io_error)?;
+
+  let mut eof = offset + bytes.len() as u64 >= opened.size;
+  if !opened.binary && !eof {
+    // Leave a character split by the chunk end for the next read
+    let boundary = content::char_boundary(opened.encoding, &bytes);
+    bytes.truncate(boundary);
+    eof = offset + bytes.len() as u64 >= opened.size;
+  }
+
+  let (content, base64) = if opened.binary {
+    (None, Some(STANDARD.encode(&bytes)))
+  } else {
+    (Some(content::decode_with(opened.encoding, &bytes)), None)
+  };
+
+  Ok(FileChunk {
+    size: opened.size,
+    mime: opened.mime,
+    binary: opened.binary,
+    offset,
+    length: bytes.len() as u64,
+    start_line: None,
+    end_line: None,
+    eof,
+    encoding: (!opened.binary).then(|| opened.encoding.name()),
+    content,
+    base64,
+    path,
+  })
+}
+
+fn read_lines(
+  path: String,
+  opened: OpenedFile,
+  start_line: usize,
+  end_line: Option<usize>,
+  limit: u64,
+) -> Result<FileChunk, FileError> {
+  let io_error = |e: std::io::Error| FileError::Io {
+    path: path.clone(),
+    message: e.to_string(),
+  };
+
+  // Lines are split on the encoded line feed, so UTF-16 lines keep both bytes of each character
+  let newline = content::newline(opened.encoding);
+  let mut reader = BufReader::new(opened.file);
+  // The BOM is not part of the first line
+  let mut offset = opened.bom_len as u64;
+  reader.seek_relative(offset as i64).map_err(io_error)?;
+  let mut number = 0usize;
+
+  // Skip ahead without buffering the lines before the range
+  while number + 1 < start_line {
+    let (read, _) = read_line(&mut reader, newline, u64::MAX, None).map_err(io_error)?;
+    if read == 0 {
+      break;
+    }
+    offset += read;
+    number += 1;
+  }
+
+  let mut bytes = Vec::new();
+  let mut last_line = None;
+  while end_line.is_none_or(|end| number < end) && (bytes.len() as u64) < limit {
+    let remaining = limit - bytes.len() as u64;
+    let (read, complete) =
+      read_line(&mut reader, newline, remaining, Some(&mut bytes)).map_err(io_error)?;
+    if read == 0 {
+      break;
+    }
+    // A line cut off by `limit` is not counted, and only returned when nothing else fits
+    if !complete {
+      if last_line.is_some() {
+        bytes.truncate(bytes.len() - read as usize);
+      }
+      break;
+    }
+    number += 1;
+    last_line = Some(number);
+  }
+
+  let eof = offset + bytes.len() as u64 >= opened.size;
+
+  Ok(FileChunk {
+    size: opened.size,
+    mime: opened.mime,
+    binary: false,
+    offset,
+    length: bytes.len() as u64,
+    start_line: last_line.map(|_| start_line),
+    end_line: last_line,
+    eof,
+    encoding: Some(opened.encoding.name()),
+    content: Some(content::decode_with(opened.encoding, &bytes)),
+    base64: None,
+    path,
+  })
+}
+
+// Reads through the next line feed, at most `max` bytes, into `out` or skipping them. Returns the
+// bytes read and whether the line is complete: ended by a line feed or the end of the file.
+fn read_line<R: BufRead>(
+  reader: &mut R,
+  newline: &[u8],
+  max: u64,
+  mut out: Option<&mut Vec<u8>>,
+) -> std::io::Result<(u64, bool)> {
+  if let [byte] = newline {
+    let mut limited = reader.take(max);
+    return match out {
+      Some(out) => {
+        let read = limited.read_until(*byte, out)? as u64;
+        Ok((read, read < max || out.last() == Some(byte)))
+      }
+      None => Ok((limited.skip_until(*byte)? as u64, true)),
+    };
+  }
+
+  // Multi-byte line feeds only count at code unit boundaries
+  let mut unit = vec![0; newline.len()];
+  let mut read = 0u64;
+  while read + unit.len() as u64 <= max {
+    match reader.read_exact(&mut unit) {
+      Ok(()) => {}
+      // A trailing partial code unit is not text
+      Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok((read, true)),
+      Err(e) => return Err(e),
+    }
+    read += unit.len() as u64;
+    if let Some(out) = out.as_deref_mut() {
+      out.extend_from_slice(&unit);
+    }
+    if unit == newline {
+      return Ok((read, true));
+    }
+  }
+  Ok((read, false))
+}
@@ -0,0 +1,378 @@
+use crate::git::{self, BasicCommit};
+use crate::remote;
+use crate::workspace::{self, Workspace};
+use git2::{Commit, Oid, Repository, Sort};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+// Merge commit subjects left by the hosts' merge buttons
+static GITHUB_MERGE_RE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"^Merge pull request #(\d+) from (\S+)").unwrap());
+static BITBUCKET_MERGE_RE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"^Merged in (\S+) \(pull request #(\d+)\)").unwrap());
+static AZURE_MERGE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^Merged PR (\d+): (.+)").unwrap());
+static GITLAB_BRANCH_RE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"^Merge branch '([^']+)'").unwrap());
+static GITLAB_MERGE_RE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"(?m)^See merge request \S+!(\d+)").unwrap());
+// `Title (#123)`, as written by squash merges
+static SQUASH_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(.+) \(#(\d+)\)$").unwrap());
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeKind {
+  Merge,
+  Squash,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PullRequest {
+  pub number: u64,
+  pub title: String,
+  // Source branch, only known for merge commits
+  pub branch: Option<String>,
+  pub author: String,
+  pub merged_at: i64,
+  pub merge_commit: String,
+  pub kind: MergeKind,
+  // Commits the pull request brought in, newest first
+  pub commits: Vec<BasicCommit>,
+  pub packages: Vec<String>,
+  pub web_url: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PullRequestLog {
+  pub pull_requests: Vec<PullRequest>,
+  // Commits on the mainline that did not come from a pull request
+  pub unmatched: Vec<BasicCommit>,
+}
+
+struct MergeInfo {
+  number: u64,
+  title: Option<String>,
+  branch: Option<String>,
+}
+
+// Pull requests merged in `from..to` (`to` defaults to HEAD), newest first
+#[tauri::command]
+pub fn list_pull_requests(
+  path: String,
+  from: Option<String>,
+  to: Option<String>,
+) -> Result<PullRequestLog, String> {
+  let git_root = git::find_git_root(Path::new(&path)).ok_or("Could not find Git repository")?;
+  group_range(
+    &git_root,
+    from.as_deref(),
+    to.as_deref().unwrap_or("HEAD"),
+    None,
+  )
+}
+
+// Pull requests on `remote/branch` that HEAD does not have yet, the PR view of `get_new_commits`
+#[tauri::command]
+pub fn get_new_pull_requests(
+  path: String,
+  remote: String,
+  branch: String,
+) -> Result<PullRequestLog, String> {
+  let git_root = git::find_git_root(Path::new(&path)).ok_or("Could not find Git repository")?;
+  git::fetch_from_remote(&git_root, &remote)?;
+  group_range(
+    &git_root,
+    Some("HEAD"),
+    &format!("{}/{}", remote, branch),
+    Some(&remote),
+  )
+}
+
+pub fn group_range(
+  git_root: &str,
+  from: Option<&str>,
+  to: &str,
+  remote: Option<&str>,
+) -> Result<PullRequestLog, String> {
+  let repo = Repository::open(git_root).map_err(|e| e.to_string())?;
+  let workspace = workspace::load_workspace(git_root).ok();
+  let web = remote::resolve(git_root, remote);
+
+  let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+  walk
+    .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+    .map_err(|e| e.to_string())?;
+  walk.simplify_first_parent().map_err(|e| e.to_string())?;
+  walk
+    .push(resolve_commit(&repo, to)?)
+    .map_err(|e| e.to_string())?;
+  if let Some(from) = from {
+    walk
+      .hide(resolve_commit(&repo, from)?)
+      .map_err(|e| e.to_string())?;
+  }
+
+  let mut pull_requests: Vec<PullRequest> = Vec::new();
+  let mut by_number: HashMap<u64, usize> = HashMap::new();
+  let mut unmatched = Vec::new();
+
+  for oid in walk {
+    let oid = oid.map_err(|e| e.to_string())?;
+    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+    let message = commit.message().unwrap_or_default();
+
+    let pull_request = if commit.parent_count() > 1 {
+      parse_merge(message)
+        .map(|merge| merge_request(&repo, &commit, merge))
+        .transpose()?
+    } else {
+      parse_squash(message).map(|(number, title)| PullRequest {
+        number,
+        title,
+        branch: None,
+        author: commit.author().name().unwrap_or_default().to_string(),
+        merged_at: commit.time().seconds(),
+        merge_commit: commit.id().to_string(),
+        kind: MergeKind::Squash,
+        commits: vec![basic_commit(&commit)],
+        packages: Vec::new(),
+        web_url: None,
+      })
+    };
+
+    let Some(mut pull_request) = pull_request else {
+      unmatched.push(basic_commit(&commit));
+      continue;
+    };
+    pull_request.packages = touched_packages(&repo, &commit, workspace.as_deref())?;
+    pull_request.web_url = web
+      .as_ref()
+      .map(|web| web.pull_request_url(pull_request.number));
+    git::enrich(git_root, remote, &mut pull_request.commits);
+
+    // A number seen twice (e.g. merged, reverted and merged again) stays one entry
+    match by_number.get(&pull_request.number) {
+      Some(&index) => {
+        let existing = &mut pull_requests[index];
+        existing.commits.extend(pull_request.commits);
+        existing.packages.extend(pull_request.packages);
+        existing.packages.sort();
+        existing.packages.dedup();
+      }
+      None => {
+        by_number.insert(pull_request.number, pull_requests.len());
+        pull_requests.push(pull_request);
+      }
+    }
+  }
+
+  git::enrich(git_root, remote, &mut unmatched);
+  Ok(PullRequestLog {
+    pull_requests,
+    unmatched,
+  })
+}
+
+fn resolve_commit(repo: &Repository, revision: &str) -> Result<Oid, String> {
+  repo
+    .revparse_single(revision)
+    .and_then(|object| object.peel_to_commit())
+    .map(|commit| commit.id())
+    .map_err(|e| format!("Unknown revision {}: {}", revision, e))
+}
+
+fn parse_merge(message: &str) -> Option<MergeInfo> {
+  let subject = message.lines().next().unwrap_or_default().trim();
+  // Hosts put the pull request title on the first line of the body
+  let body_title = message
+    .lines()
+    .skip(1)
+    .map(str::trim)
+    .find(|line| !line.is_empty() && !GITLAB_MERGE_RE.is_match(line))
+    .map(String::from);
+
+  if let Some(captures) = GITHUB_MERGE_RE.captures(subject) {
+    // `owner/branch`, the owner being the fork the branch lives in
+    let source = &captures[2];
+    return Some(MergeInfo {
+      number: captures[1].parse().ok()?,
+      title: body_title,
+      branch: Some(
+        source
+          .split_once('/')
+          .map_or(source, |(_, b)| b)
+          .to_string(),
+      ),
+    });
+  }
+  if let Some(captures) = BITBUCKET_MERGE_RE.captures(subject) {
+    return Some(MergeInfo {
+      number: captures[2].parse().ok()?,
+      title: body_title,
+      branch: Some(captures[1].to_string()),
+    });
+  }
+  if let Some(captures) = AZURE_MERGE_RE.captures(subject) {
+    return Some(MergeInfo {
+      number: captures[1].parse().ok()?,
+      title: Some(captures[2].to_string()),
+      branch: None,
+    });
+  }
+  let number = GITLAB_MERGE_RE.captures(message)?[1].parse().ok()?;
+  Some(MergeInfo {
+    number,
+    title: body_title,
+    branch: GITLAB_BRANCH_RE
+      .captures(subject)
+      .map(|captures| captures[1].to_string()),
+  })
+}
+
+fn parse_squash(message: &str) -> Option<(u64, String)> {
+  let subject = message.lines().next()?.trim();
+  let captures = SQUASH_RE.captures(subject)?;
+  Some((captures[2].parse().ok()?, captures[1].to_string()))
+}
+
+// The merged commits are those reachable from the second parent but not the first
+fn merge_request(
+  repo: &Repository,
+  merge: &Commit,
+  info: MergeInfo,
+) -> Result<PullRequest, String> {
+  let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+  walk
+    .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+    .map_err(|e| e.to_string())?;
+  walk
+    .push(merge.parent_id(1).map_err(|e| e.to_string())?)
+    .map_err(|e| e.to_string())?;
+  walk
+    .hide(merge.parent_id(0).map_err(|e| e.to_string())?)
+    .map_err(|e| e.to_string())?;
+
+  let commits = walk
+    .map(|oid| {
+      let oid = oid.map_err(|e| e.to_string())?;
+      let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+      Ok(basic_commit(&commit))
+    })
+    .collect::<Result<Vec<_>, String>>()?;
+
+  // Whoever wrote the first commit opened the pull request; the merger authored the merge
+  let author = commits
+    .last()
+    .map(|commit| commit.author.clone())
+    .unwrap_or_else(|| merge.author().name().unwrap_or_default().to_string());
+
+  Ok(PullRequest {
+    number: info.number,
+    title: info
+      .title
+      .unwrap_or_else(|| merge.summary().unwrap_or_default().to_string()),
+    branch: info.branch,
+    author,
+    merged_at: merge.time().seconds(),
+    merge_commit: merge.id().to_string(),
+    kind: MergeKind::Merge,
+    commits,
+    packages: Vec::new(),
+    web_url: None,
+  })
+}
+
+// Packages changed on the mainline by the merge or squash commit
+fn touched_packages(
+  repo: &Repository,
+  commit: &Commit,
+  workspace: Option<&Workspace>,
+) -> Result<Vec<String>, String> {
+  let Some(workspace) = workspace else {
+    return Ok(Vec::new());
+  };
+  let mut packages: Vec<String> = git::changed_paths(repo, commit)?
+    .iter()
+    .filter_map(|file| workspace.package_for_path(file))
+    .map(|package| package.name.clone())
+    .collect();
+  packages.sort();
+  packages.dedup();
+  Ok(packages)
+}
+
+fn basic_commit(commit: &Commit) -> BasicCommit {
+  BasicCommit {
+    id: commit.id().to_string(),
+    message: commit.message().unwrap_or_default().trim().to_string(),
+    author: commit.author().name().unwrap_or_default().to_string(),
+    date: commit.time().seconds(),
+    remote_url: None,
+    web_url: None,
+    issues: Vec::new(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_merge_subjects() {
+    let cases: &[(&str, Option<(u64, Option<&str>, Option<&str>)>)] = &[
+      (
+        "Merge pull request #5 from alice/feature/login\n\nAdd login page",
+        Some((5, Some("Add login page"), Some("feature/login"))),
+      ),
+      (
+        "Merge pull request #6 from feature-x",
+        Some((6, None, Some("feature-x"))),
+      ),
+      (
+        "Merged in bugfix/crash (pull request #42)\n\nFix crash on start\n\nApproved-by: Bob",
+        Some((42, Some("Fix crash on start"), Some("bugfix/crash"))),
+      ),
+      ("Merged PR 17: Add search", Some((17, Some("Add search"), None))),
+      (
+        "Merge branch 'feature/api' into 'main'\n\nAdd API client\n\nSee merge request group/app!23",
+        Some((23, Some("Add API client"), Some("feature/api"))),
+      ),
+      (
+        "Merge branch 'fix' into 'main'\n\nSee merge request group/app!24",
+        Some((24, None, Some("fix"))),
+      ),
+      ("Merge branch 'main' into feature", None),
+      ("Update readme", None),
+    ];
+    for (message, expected) in cases {
+      let parsed = parse_merge(message);
+      let parsed = parsed
+        .as_ref()
+        .map(|info| (info.number, info.title.as_deref(), info.branch.as_deref()));
+      assert_eq!(parsed, *expected, "{}", message);
+    }
+  }
+
+  #[test]
+  fn parses_squash_subjects() {
+    let cases: &[(&str, Option<(u64, &str)>)] = &[
+      ("Add login page (#7)", Some((7, "Add login page"))),
+      (
+        "feat(ui): dark mode (#12)\n\n* first commit\n* second commit",
+        Some((12, "feat(ui): dark mode")),
+      ),
+      ("Fix (#7) in the middle", None),
+      ("(#7)", None),
+      ("Bump version", None),
+    ];
+    for (message, expected) in cases {
+      let parsed = parse_squash(message);
+      let parsed = parsed
+        .as_ref()
+        .map(|(number, title)| (*number, title.as_str()));
+      assert_eq!(parsed, *expected, "{}", message);
+    }
+  }
+}
@@ -0,0 +1,254 @@
+use crate::conventional;
+use crate::git;
+use crate::tags::{self, Tag};
+use crate::workspace::{self, DependencyKind, Package, Workspace};
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+  Patch,
+  Minor,
+  Major,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PlannedRelease {
+  pub name: String,
+  pub path: String,
+  pub current_version: String,
+  pub next_version: String,
+  pub bump: Bump,
+  pub last_tag: Option<String>,
+  pub reasons: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct ReleasePlan {
+  pub packages: Vec<PlannedRelease>,
+  pub applied: bool,
+}
+
+#[tauri::command]
+pub fn plan_release(path: String, apply: Option<bool>) -> Result<ReleasePlan, String> {
+  let workspace = workspace::load_workspace(&path)?;
+  let tags = tags::package_tags(&workspace)?;
+
+  let mut bumps: HashMap<String, (Bump, Vec<String>, Option<String>)> = HashMap::new();
+
+  for package in &workspace.packages {
+    let Some(version) = &package.version else {
+      continue;
+    };
+
+    let last_tag = release_tag(&tags, &package.name, version);
+    let commits = git::list_commits_in_range(
+      &workspace.root,
+      last_tag.as_deref(),
+      "HEAD",
+      &package.relative_path,
+    )?;
+
+    let mut bump = None;
+    let mut reasons = Vec::new();
+    for commit in &commits {
+      let Some(parsed) = conventional::parse(&commit.message) else {
+        continue;
+      };
+      let commit_bump = if parsed.breaking {
+        Bump::Major
+      } else {
+        match parsed.kind.as_str() {
+          "feat" => Bump::Minor,
+          "fix" | "perf" | "revert" => Bump::Patch,
+          _ => continue,
+        }
+      };
+
+      bump = bump.max(Some(commit_bump));
+      reasons.push(format!(
+        "{}: {} ({})",
+        parsed.kind,
+        parsed.description,
+        &commit.id[..commit.id.len().min(7)]
+      ));
+    }
+
+    if let Some(bump) = bump {
+      bumps.insert(package.name.clone(), (bump, reasons, last_tag));
+    }
+  }
+
+  propagate_to_dependents(&workspace, &mut bumps, &tags);
+
+  let mut packages = Vec::new();
+  for package in &workspace.packages {
+    let (Some(current), Some((bump, reasons, last_tag))) =
+      (&package.version, bumps.get(&package.name))
+    else {
+      continue;
+    };
+
+    packages.push(PlannedRelease {
+      name: package.name.clone(),
+      path: package.path.clone(),
+      current_version: current.clone(),
+      next_version: next_version(current, *bump)?,
+      bump: *bump,
+      last_tag: last_tag.clone(),
+      reasons: reasons.clone(),
+    });
+  }
+
+  let applied = apply.unwrap_or(false);
+  if applied {
+    apply_plan(&workspace, &packages)?;
+    workspace::clear_cache();
+  }
+
+  Ok(ReleasePlan { packages, applied })
+}
+
+// Dependents of a bumped package get at least a patch bump, transitively. Packages that only
+// have it as a dev dependency do not ship it and are left alone.
+fn propagate_to_dependents(
+  workspace: &Workspace,
+  bumps: &mut HashMap<String, (Bump, Vec<String>, Option<String>)>,
+  tags: &[Tag],
+) {
+  let mut queue: Vec<String> = bumps.keys().cloned().collect();
+
+  while let Some(name) = queue.pop() {
+    let dependents = workspace.dependents(&name).into_iter().filter(|dependent| {
+      dependent
+        .dependencies
+        .iter()
+        .any(|d| d.name == name && d.kind != DependencyKind::Dev)
+    });
+    for dependent in dependents {
+      let Some(version) = &dependent.version else {
+        continue;
+      };
+      let reason = format!("depends on {}", name);
+      match bumps.get_mut(&dependent.name) {
+        Some((_, reasons, _)) => {
+          if !reasons.contains(&reason) {
+            reasons.push(reason);
+          }
+        }
+        None => {
+          let last_tag = release_tag(tags, &dependent.name, version);
+          bumps.insert(
+            dependent.name.clone(),
+            (Bump::Patch, vec![reason], last_tag),
+          );
+          queue.push(dependent.name.clone());
+        }
+      }
+    }
+  }
+}
+
+fn apply_plan(workspace: &Workspace, releases: &[PlannedRelease]) -> Result<(), String> {
+  for release in releases {
+    let package = workspace
+      .find(&release.name)
+      .ok_or_else(|| format!("Unknown package {}", release.name))?;
+    workspace::set_package_version(package, &release.next_version)?;
+
+    for dependent in workspace.dependents(&release.name) {
+      update_dependency_ranges(dependent, release)?;
+    }
+  }
+  Ok(())
+}
+
+// Moves pinned or caret/tilde ranges on the released version forward; protocol ranges like `workspace:` are left alone
+fn update_dependency_ranges(dependent: &Package, release: &PlannedRelease) -> Result<(), String> {
+  for dependency in dependent
+    .dependencies
+    .iter()
+    .filter(|d| d.name == release.name)
+  {
+    let prefix = ["^", "~", "=", ""]
+      .into_iter()
+      .find(|prefix| dependency.range == format!("{}{}", prefix, release.current_version));
+    if let Some(prefix) = prefix {
+      let range = format!("{}{}", prefix, release.next_version);
+      workspace::set_dependency_range(dependent, dependency, &range)?;
+    }
+  }
+  Ok(())
+}
+
+fn next_version(current: &str, bump: Bump) -> Result<String, String> {
+  let mut version =
+    semver::Version::parse(current).map_err(|e| format!("Invalid version {}: {}", current, e))?;
+  // A prerelease already counts as the bump towards its release: releasing `1.0.0-beta.1`
+  // only drops the prerelease, unless the bump asks for a larger component than it changes
+  let prerelease = !version.pre.is_empty();
+  version.pre = semver::Prerelease::EMPTY;
+  version.build = semver::BuildMetadata::EMPTY;
+
+  match bump {
+    Bump::Major if prerelease && version.minor == 0 && version.patch == 0 => {}
+    Bump::Major => {
+      version.major += 1;
+      version.minor = 0;
+      version.patch = 0;
+    }
+    Bump::Minor if prerelease && version.patch == 0 => {}
+    Bump::Minor => {
+      version.minor += 1;
+      version.patch = 0;
+    }
+    Bump::Patch if prerelease => {}
+    Bump::Patch => version.patch += 1,
+  }
+  Ok(version.to_string())
+}
+
+// Tag of the package's current version, falling back to its most recent release
+fn release_tag(tags: &[Tag], name: &str, version: &str) -> Option<String> {
+  let releases = tags::releases_of(tags, name);
+  releases
+    .iter()
+    .find(|t| t.version.as_deref() == Some(version))
+    .or(releases.last())
+    .map(|t| t.name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn bumps_release_versions() {
+    assert_eq!(next_version("1.2.3", Bump::Patch).unwrap(), "1.2.4");
+    assert_eq!(next_version("1.2.3", Bump::Minor).unwrap(), "1.3.0");
+    assert_eq!(next_version("1.2.3", Bump::Major).unwrap(), "2.0.0");
+    assert_eq!(next_version("1.2.3+build.5", Bump::Patch).unwrap(), "1.2.4");
+  }
+
+  #[test]
+  fn releases_prereleases() {
+    assert_eq!(next_version("1.0.0-beta.1", Bump::Patch).unwrap(), "1.0.0");
+    assert_eq!(next_version("1.0.0-beta.1", Bump::Minor).unwrap(), "1.0.0");
+    assert_eq!(next_version("1.0.0-beta.1", Bump::Major).unwrap(), "1.0.0");
+    assert_eq!(next_version("1.2.3-rc.0", Bump::Patch).unwrap(), "1.2.3");
+  }
+
+  #[test]
+  fn bumps_prereleases_past_smaller_components() {
+    assert_eq!(next_version("1.2.3-rc.0", Bump::Minor).unwrap(), "1.3.0");
+    assert_eq!(next_version("1.2.3-rc.0", Bump::Major).unwrap(), "2.0.0");
+    assert_eq!(next_version("1.2.0-rc.0", Bump::Minor).unwrap(), "1.2.0");
+    assert_eq!(next_version("1.2.0-rc.0", Bump::Major).unwrap(), "2.0.0");
+  }
+
+  #[test]
+  fn rejects_invalid_versions() {
+    assert!(next_version("1.2", Bump::Patch).is_err());
+  }
+}
@@ -0,0 +1,200 @@
+use crate::content;
+use crate::git;
+use crate::sandbox;
+use git2::{ObjectType, Repository, Tree, TreeWalkMode, TreeWalkResult};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Serialize)]
+pub struct RevisionFile {
+  pub path: String,
+  pub revision: String,
+  pub commit: String,
+  pub oid: String,
+  pub size: u64,
+  pub binary: bool,
+  pub encoding: Option<&'static str>,
+  // Missing for binary files
+  pub content: Option<String>,
+  pub truncated: bool,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+  File,
+  Directory,
+  Symlink,
+  Submodule,
+}
+
+#[derive(Serialize)]
+pub struct TreeEntry {
+  pub name: String,
+  pub path: String,
+  pub kind: EntryKind,
+  pub oid: String,
+  pub mode: i32,
+  pub size: Option<u64>,
+}
+
+fn open_repo(repo_path: &str) -> Result<Repository, String> {
+  sandbox::resolve(repo_path).map_err(|e| e.to_string())?;
+  let git_root = git::find_git_root(Path::new(repo_path)).ok_or("Could not find Git repository")?;
+  Repository::open(&git_root).map_err(|e| format!("Failed to open repository: {}", e))
+}
+
+// Paths may be given relative to the repository root or as absolute working tree paths
+fn repo_relative(repo: &Repository, path: &str) -> Result<String, String> {
+  let path = Path::new(path);
+  let relative = match repo.workdir() {
+    Some(workdir) if path.is_absolute() => {
+      let workdir = workdir
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.to_path_buf());
+      path
+        .strip_prefix(&workdir)
+        .map_err(|_| "Path is outside the repository".to_string())?
+    }
+    _ => path,
+  };
+  Ok(
+    relative
+      .to_string_lossy()
+      .replace('\\', "/")
+      .trim_matches('/')
+      .to_string(),
+  )
+}
+
+fn revision_tree<'r>(repo: &'r Repository, revision: &str) -> Result<(String, Tree<'r>), String> {
+  let commit = repo
+    .revparse_single(revision)
+    .and_then(|object| object.peel_to_commit())
+    .map_err(|e| format!("Unknown revision {}: {}", revision, e))?;
+  let tree = commit.tree().map_err(|e| e.to_string())?;
+  Ok((commit.id().to_string(), tree))
+}
+
+fn entry_kind(kind: Option<ObjectType>, mode: i32) -> EntryKind {
+  match kind {
+    Some(ObjectType::Tree) => EntryKind::Directory,
+    Some(ObjectType::Commit) => EntryKind::Submodule,
+    _ if mode == 0o120000 => EntryKind::Symlink,
+    _ => EntryKind::File,
+  }
+}
+
+// Contents of a file as of a commit, branch or tag, without touching the working tree
+#[tauri::command]
+pub fn read_file_at_revision(
+  repo_path: String,
+  revision: String,
+  file_path: String,
+  max_bytes: Option<u64>,
+) -> Result<RevisionFile, String> {
+  let repo = open_repo(&repo_path)?;
+  let relative = repo_relative(&repo, &file_path)?;
+  let (commit, tree) = revision_tree(&repo, &revision)?;
+
+  let entry = tree
+    .get_path(Path::new(&relative))
+    .map_err(|_| format!("{} does not exist at {}", relative, revision))?;
+  let blob = entry
+    .to_object(&repo)
+    .and_then(|object| object.peel_to_blob())
+    .map_err(|_| format!("{} is not a file at {}", relative, revision))?;
+
+  let limit = max_bytes
+    .unwrap_or(sandbox::MAX_READ_BYTES)
+    .min(sandbox::MAX_READ_BYTES) as usize;
+  let bytes = blob.content();
+  let truncated = bytes.len() > limit;
+  let bytes = &bytes[..bytes.len().min(limit)];
+
+  let binary = content::is_binary(bytes);
+  let (content, encoding) = if binary {
+    (None, None)
+  } else {
+    let (text, encoding) = content::decode_text(bytes);
+    (Some(text), Some(encoding))
+  };
+
+  Ok(RevisionFile {
+    path: relative,
+    revision,
+    commit,
+    oid: blob.id().to_string(),
+    size: blob.size() as u64,
+    binary,
+    encoding,
+    content,
+    truncated,
+  })
+}
+
+// Directory listing at a revision; `recursive` walks the whole subtree
+#[tauri::command]
+pub fn list_tree_at_revision(
+  repo_path: String,
+  revision: String,
+  path: Option<String>,
+  recursive: Option<bool>,
+) -> Result<Vec<TreeEntry>, String> {
+  let repo = open_repo(&repo_path)?;
+  let relative = repo_relative(&repo, path.as_deref().unwrap_or(""))?;
+  let (_, root) = revision_tree(&repo, &revision)?;
+
+  let tree = if relative.is_empty() {
+    root
+  } else {
+    root
+      .get_path(Path::new(&relative))
+      .and_then(|entry| entry.to_object(&repo))
+      .and_then(|object| object.peel_to_tree())
+      .map_err(|_| format!("{} is not a directory at {}", relative, revision))?
+  };
+
+  let odb = repo.odb().map_err(|e| e.to_string())?;
+  let prefix = if relative.is_empty() {
+    String::new()
+  } else {
+    format!("{}/", relative)
+  };
+  let mut entries = Vec::new();
+  let mut collect = |dir: &str, entry: &git2::TreeEntry| {
+    let name = entry.name().unwrap_or_default().to_string();
+    let kind = entry_kind(entry.kind(), entry.filemode());
+    // Reading the object header gives the size without inflating the blob
+    let size = match kind {
+      EntryKind::File | EntryKind::Symlink => odb
+        .read_header(entry.id())
+        .ok()
+        .map(|(size, _)| size as u64),
+      _ => None,
+    };
+    entries.push(TreeEntry {
+      path: format!("{}{}{}", prefix, dir, name),
+      name,
+      kind,
+      oid: entry.id().to_string(),
+      mode: entry.filemode(),
+      size,
+    });
+  };
+
+  if recursive.unwrap_or(false) {
+    tree
+      .walk(TreeWalkMode::PreOrder, |dir, entry| {
+        collect(dir, entry);
+        TreeWalkResult::Ok
+      })
+      .map_err(|e| e.to_string())?;
+  } else {
+    for entry in tree.iter() {
+      collect("", &entry);
+    }
+  }
+
+  Ok(entries)
+}
@@ -0,0 +1,235 @@
+use crate::content;
+use crate::docs;
+use crate::git;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+
+// Files above this size are refused instead of being sent to the frontend
+pub const MAX_READ_BYTES: u64 = 10 * 1024 * 1024;
+
+// Canonical directories the file commands may read from
+static ALLOWED_ROOTS: Lazy<RwLock<Vec<PathBuf>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+// Repository last picked through the folder dialog
+static REPOSITORY: Lazy<RwLock<Option<String>>> = Lazy::new(|| RwLock::new(None));
+
+// Where the picked repository is kept between runs, under the app config directory
+const REPOSITORY_FILE: &str = "repository";
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FileError {
+  OutsideScope { path: String },
+  NotFound { path: String },
+  TooLarge { path: String, size: u64, limit: u64 },
+  Io { path: String, message: String },
+}
+
+impl fmt::Display for FileError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      FileError::OutsideScope { path } => write!(f, "{} is outside the opened repositories", path),
+      FileError::NotFound { path } => write!(f, "{} does not exist", path),
+      FileError::TooLarge { path, size, limit } => {
+        write!(
+          f,
+          "{} is {} bytes, over the {} byte limit",
+          path, size, limit
+        )
+      }
+      FileError::Io { path, message } => write!(f, "Failed to read {}: {}", path, message),
+    }
+  }
+}
+
+fn io_error(path: &str, error: std::io::Error) -> FileError {
+  match error.kind() {
+    ErrorKind::NotFound => FileError::NotFound {
+      path: path.to_string(),
+    },
+    _ => FileError::Io {
+      path: path.to_string(),
+      message: error.to_string(),
+    },
+  }
+}
+
+// Lets the user pick the monorepo folder and opens it to the file commands. Roots are only
+// registered from here and at startup, never from a path the frontend passes in.
+#[tauri::command]
+pub async fn choose_repository(app: AppHandle) -> Result<Option<String>, String> {
+  let Some(picked) = app.dialog().file().blocking_pick_folder() else {
+    return Ok(None);
+  };
+  let path = picked.into_path().map_err(|e| e.to_string())?;
+  let path = path.to_string_lossy().into_owned();
+
+  register_repository(&app, &path)?;
+  if let Some(file) = repository_file(&app) {
+    let saved = file
+      .parent()
+      .map_or(Ok(()), std::fs::create_dir_all)
+      .and_then(|_| std::fs::write(&file, &path));
+    if let Err(e) = saved {
+      log::warn!("Failed to remember repository {}: {}", path, e);
+    }
+  }
+  *REPOSITORY.write() = Some(path.clone());
+  Ok(Some(path))
+}
+
+// The repository the file commands are currently open to, for the frontend to follow
+#[tauri::command]
+pub fn get_repository() -> Option<String> {
+  REPOSITORY.read().clone()
+}
+
+// Re-opens the repository picked in a previous run
+pub fn restore_repository(app: &AppHandle) {
+  let Some(path) = repository_file(app).and_then(|file| std::fs::read_to_string(file).ok()) else {
+    return;
+  };
+  let path = path.trim().to_string();
+  match register_repository(app, &path) {
+    Ok(()) => *REPOSITORY.write() = Some(path),
+    Err(e) => log::warn!("Failed to reopen repository {}: {}", path, e),
+  }
+}
+
+fn repository_file(app: &AppHandle) -> Option<PathBuf> {
+  app
+    .path()
+    .app_config_dir()
+    .ok()
+    .map(|dir| dir.join(REPOSITORY_FILE))
+}
+
+// Opens a repository to the file commands: its git root and the docs output root
+fn register_repository(app: &AppHandle, path: &str) -> Result<(), String> {
+  let git_root = git::find_git_root(Path::new(path))
+    .ok_or_else(|| "Could not find Git repository".to_string())?;
+  let repo_root = canonical_root(Path::new(&git_root))?;
+
+  let mut roots = vec![repo_root.clone()];
+  match trusted_docs_root(path, &[PathBuf::from(&git_root), repo_root])? {
+    Some(docs_root) => {
+      // Generated docs are previewed through the asset protocol
+      app
+        .asset_protocol_scope()
+        .allow_directory(&docs_root, true)
+        .map_err(|e| e.to_string())?;
+      roots.push(docs_root);
+    }
+    None => log::warn!(
+      "Ignoring docs output root of {}: it is outside the repository and the app directories",
+      path
+    ),
+  }
+
+  let mut allowed = ALLOWED_ROOTS.write();
+  for root in roots {
+    if !allowed.contains(&root) {
+      allowed.push(root);
+    }
+  }
+  Ok(())
+}
+
+// `docs.output_root` comes from the repository's own config, so it is only opened up when it
+// lies inside the repository or the app's directories. Checked before and after resolving
+// symlinks, so neither the config nor a link inside those roots can widen the scope.
+fn trusted_docs_root(path: &str, repo_roots: &[PathBuf]) -> Result<Option<PathBuf>, String> {
+  let docs_root = docs::docs_output_root(path)?;
+  if docs_root.components().any(|c| c == Component::ParentDir) {
+    return Ok(None);
+  }
+
+  let mut trusted = repo_roots.to_vec();
+  trusted.extend(docs::default_output_root().ok());
+  trusted.extend(dirs::data_dir().map(|dir| dir.join("mr-manager")));
+  if !trusted.iter().any(|root| docs_root.starts_with(root)) {
+    return Ok(None);
+  }
+
+  std::fs::create_dir_all(&docs_root)
+    .map_err(|e| format!("Failed to create docs directory: {}", e))?;
+  let docs_root = canonical_root(&docs_root)?;
+  let inside = trusted
+    .iter()
+    .filter_map(|root| root.canonicalize().ok())
+    .any(|root| docs_root.starts_with(root));
+  Ok(inside.then_some(docs_root))
+}
+
+fn canonical_root(path: &Path) -> Result<PathBuf, String> {
+  path
+    .canonicalize()
+    .map_err(|e| format!("Failed to resolve {}: {}", path.display(), e))
+}
+
+fn is_allowed(path: &Path) -> bool {
+  ALLOWED_ROOTS
+    .read()
+    .iter()
+    .any(|root| path.starts_with(root))
+}
+
+// Resolves `path` to a canonical path inside a registered root. Symlinks are followed
+// before the check, so links pointing out of a repository are rejected as well.
+pub fn resolve(path: &str) -> Result<PathBuf, FileError> {
+  let outside = || FileError::OutsideScope {
+    path: path.to_string(),
+  };
+  let requested = Path::new(path);
+  if !requested.is_absolute() || requested.components().any(|c| c == Component::ParentDir) {
+    return Err(outside());
+  }
+
+  match requested.canonicalize() {
+    Ok(resolved) if is_allowed(&resolved) => Ok(resolved),
+    Ok(_) => Err(outside()),
+    Err(e) if e.kind() == ErrorKind::NotFound => {
+      // Missing files are only reported as such when their location is in scope
+      let existing = requested
+        .ancestors()
+        .find_map(|ancestor| ancestor.canonicalize().ok());
+      match existing {
+        Some(ancestor) if is_allowed(&ancestor) => Err(io_error(path, e)),
+        _ => Err(outside()),
+      }
+    }
+    Err(e) => Err(io_error(path, e)),
+  }
+}
+
+#[tauri::command]
+pub fn read_file(path: String) -> Result<String, FileError> {
+  let resolved = resolve(&path)?;
+  let metadata = std::fs::metadata(&resolved).map_err(|e| io_error(&path, e))?;
+  if metadata.len() > MAX_READ_BYTES {
+    return Err(FileError::TooLarge {
+      path,
+      size: metadata.len(),
+      limit: MAX_READ_BYTES,
+    });
+  }
+
+  // Not failing on other encodings; binary content should go through `preview_file`
+  let bytes = std::fs::read(&resolved).map_err(|e| io_error(&path, e))?;
+  Ok(content::decode_text(&bytes).0)
+}
+
+#[tauri::command]
+pub fn file_exists(path: String) -> Result<bool, FileError> {
+  match resolve(&path) {
+    Ok(resolved) => Ok(resolved.exists()),
+    Err(FileError::NotFound { .. }) => Ok(false),
+    Err(e) => Err(e),
+  }
+}
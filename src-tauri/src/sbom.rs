@@ -0,0 +1,524 @@
+use crate::docs;
+use crate::git;
+use crate::licenses::{self, LicenseIndex};
+use crate::lockfile::{self, Lockfile};
+use crate::workspace::{self, DependencyKind, ManifestKind, Package, Workspace};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+const TOOL_NAME: &str = "mr-manager";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SbomOutput {
+  pub package: String,
+  pub components: usize,
+  // Components whose license could not be found offline
+  pub missing_licenses: Vec<String>,
+  pub cyclonedx: String,
+  pub spdx: String,
+}
+
+pub struct Component {
+  pub name: String,
+  pub version: String,
+  pub manifest: ManifestKind,
+  pub license: Option<String>,
+  pub integrity: Option<String>,
+  pub dependencies: Vec<String>,
+}
+
+impl Component {
+  fn purl(&self) -> String {
+    match self.manifest {
+      // The `@` of a scope is percent-encoded in package URLs
+      ManifestKind::Npm => format!(
+        "pkg:npm/{}@{}",
+        self.name.replacen('@', "%40", 1),
+        self.version
+      ),
+      ManifestKind::Cargo => format!("pkg:cargo/{}@{}", self.name, self.version),
+    }
+  }
+}
+
+// Components by `name@version`, starting from the app's own
+pub struct DependencyGraph {
+  pub root: String,
+  pub components: BTreeMap<String, Component>,
+}
+
+// CycloneDX and SPDX documents for an app, or for every deployable app of the workspace: private
+// npm packages and crates with a binary target. Dev dependencies are left out, they do not ship
+#[tauri::command]
+pub fn generate_sbom(path: String, package: Option<String>) -> Result<Vec<SbomOutput>, String> {
+  let workspace = workspace::load_workspace(&path)?;
+  let apps: Vec<&Package> = match package.as_deref() {
+    Some(name) => vec![workspace
+      .find(name)
+      .ok_or_else(|| format!("Unknown package: {}", name))?],
+    None => workspace
+      .packages
+      .iter()
+      .filter(|p| is_deployable(p))
+      .collect(),
+  };
+  if apps.is_empty() {
+    return Err("No deployable apps found".to_string());
+  }
+
+  apps
+    .into_iter()
+    .map(|app| write_documents(app, &dependency_graph(&workspace, app)?))
+    .collect()
+}
+
+// Everything the app ships with: its dependencies without dev dependencies, transitively
+pub fn dependency_graph(workspace: &Workspace, app: &Package) -> Result<DependencyGraph, String> {
+  match app.manifest {
+    ManifestKind::Npm => npm_graph(app),
+    ManifestKind::Cargo => cargo_graph(workspace, app),
+  }
+}
+
+pub fn is_deployable(package: &Package) -> bool {
+  let dir = Path::new(&package.path);
+  match package.manifest {
+    ManifestKind::Npm => package.private,
+    ManifestKind::Cargo => {
+      dir.join("src").join("main.rs").is_file()
+        || workspace::read_toml(&dir.join("Cargo.toml")).is_some_and(|doc| doc.contains_key("bin"))
+    }
+  }
+}
+
+fn npm_graph(app: &Package) -> Result<DependencyGraph, String> {
+  let lockfile = lockfile::load_lockfile(&app.path)?;
+  let lock_dir = Path::new(&lockfile.path)
+    .parent()
+    .ok_or("Invalid lockfile path")?
+    .to_path_buf();
+  let app_importer = Path::new(&app.path)
+    .strip_prefix(&lock_dir)
+    .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+    .ok()
+    .filter(|relative| !relative.is_empty())
+    .unwrap_or_else(|| ".".to_string());
+
+  let mut dirs = vec![lock_dir.clone()];
+  dirs.extend(lockfile.importers.iter().map(|i| lock_dir.join(&i.path)));
+  let index = LicenseIndex::new(&dirs);
+
+  // Importers are queued by path, installed packages by id
+  let mut queue: VecDeque<(bool, String)> = VecDeque::from([(true, app_importer.clone())]);
+  let mut importer_ids: BTreeMap<String, String> = BTreeMap::new();
+  let mut components: BTreeMap<String, Component> = BTreeMap::new();
+  let mut root = None;
+
+  while let Some((is_importer, key)) = queue.pop_front() {
+    if !is_importer {
+      if components.contains_key(&key) {
+        continue;
+      }
+      let Some(package) = lockfile.packages.get(&key) else {
+        continue;
+      };
+      queue.extend(package.dependencies.iter().map(|id| (false, id.clone())));
+      components.insert(
+        key,
+        Component {
+          name: package.name.clone(),
+          version: package.version.clone(),
+          manifest: ManifestKind::Npm,
+          license: index.npm(&package.name, &package.version),
+          integrity: package.integrity.clone(),
+          dependencies: package.dependencies.clone(),
+        },
+      );
+      continue;
+    }
+
+    if importer_ids.contains_key(&key) {
+      continue;
+    }
+    let (id, component) = importer_component(&lockfile, &lock_dir, &key)?;
+    for dependency in &component.dependencies {
+      queue.push_back(match dependency.strip_prefix("link:") {
+        Some(link) => (true, link.to_string()),
+        None => (false, dependency.clone()),
+      });
+    }
+    importer_ids.insert(key, id.clone());
+    root.get_or_insert_with(|| id.clone());
+    components.insert(id, component);
+  }
+
+  // Workspace links were recorded by importer path until every importer had an id
+  for component in components.values_mut() {
+    for dependency in &mut component.dependencies {
+      if let Some(id) = dependency
+        .strip_prefix("link:")
+        .and_then(|link| importer_ids.get(link))
+      {
+        *dependency = id.clone();
+      }
+    }
+    component
+      .dependencies
+      .retain(|dependency| !dependency.starts_with("link:"));
+  }
+
+  Ok(DependencyGraph {
+    root: root.ok_or("App not found in lockfile")?,
+    components,
+  })
+}
+
+fn importer_component(
+  lockfile: &Lockfile,
+  lock_dir: &Path,
+  path: &str,
+) -> Result<(String, Component), String> {
+  let importer = lockfile
+    .importers
+    .iter()
+    .find(|i| i.path == path)
+    .ok_or_else(|| format!("{} is not in the lockfile", path))?;
+  let dir = lock_dir.join(path);
+  let manifest = workspace::read_json(&dir.join("package.json"));
+  let name = importer.name.clone().unwrap_or_else(|| path.to_string());
+  let version = manifest
+    .as_ref()
+    .and_then(|json| json.get("version")?.as_str().map(String::from))
+    .unwrap_or_else(|| "0.0.0".to_string());
+
+  let dependencies = importer
+    .dependencies
+    .iter()
+    .filter(|d| d.kind != DependencyKind::Dev)
+    .filter_map(|d| match &d.link {
+      Some(link) => Some(format!("link:{}", link)),
+      None => d.id.clone(),
+    })
+    .collect();
+
+  Ok((
+    lockfile::package_id(&name, &version),
+    Component {
+      name,
+      version,
+      manifest: ManifestKind::Npm,
+      license: licenses::package_license(&dir, ManifestKind::Npm),
+      integrity: None,
+      dependencies,
+    },
+  ))
+}
+
+// Cargo.lock lists dev dependencies alongside the others, so workspace crates only keep the
+// dependencies their manifest declares for normal and build use
+fn cargo_graph(workspace: &Workspace, app: &Package) -> Result<DependencyGraph, String> {
+  let lock_path = find_cargo_lock(&app.path).ok_or("No Cargo.lock found")?;
+  let contents = std::fs::read_to_string(&lock_path).map_err(|e| e.to_string())?;
+  let packages = lockfile::parse_cargo_lock(&contents)?;
+  let index = LicenseIndex::new(&[]);
+
+  let version = app
+    .version
+    .as_deref()
+    .ok_or_else(|| format!("{} has no version", app.name))?;
+  let root = lockfile::package_id(&app.name, version);
+  if !packages.contains_key(&root) {
+    return Err(format!("{} is not in Cargo.lock", root));
+  }
+
+  let mut components: BTreeMap<String, Component> = BTreeMap::new();
+  let mut queue = VecDeque::from([root.clone()]);
+  while let Some(id) = queue.pop_front() {
+    if components.contains_key(&id) {
+      continue;
+    }
+    let Some(package) = packages.get(&id) else {
+      continue;
+    };
+    let member = workspace.packages.iter().find(|p| {
+      p.manifest == ManifestKind::Cargo
+        && p.name == package.name
+        && p.version.as_deref() == Some(package.version.as_str())
+    });
+
+    let dependencies: Vec<String> = match member {
+      Some(member) => package
+        .dependencies
+        .iter()
+        .filter(|id| {
+          let (name, _) = lockfile::split_package_id(id);
+          member
+            .dependencies
+            .iter()
+            .any(|d| d.name == name && d.kind != DependencyKind::Dev)
+        })
+        .cloned()
+        .collect(),
+      None => package.dependencies.clone(),
+    };
+    queue.extend(dependencies.iter().cloned());
+
+    let license = match member {
+      Some(member) => licenses::package_license(Path::new(&member.path), ManifestKind::Cargo),
+      None => index.cargo(&package.name, &package.version),
+    };
+    components.insert(
+      id,
+      Component {
+        name: package.name.clone(),
+        version: package.version.clone(),
+        manifest: ManifestKind::Cargo,
+        license,
+        integrity: package.integrity.clone(),
+        dependencies,
+      },
+    );
+  }
+
+  Ok(DependencyGraph { root, components })
+}
+
+fn find_cargo_lock(path: &str) -> Option<PathBuf> {
+  let git_root = git::find_git_root(Path::new(path)).map(PathBuf::from);
+  for dir in Path::new(path).ancestors() {
+    let lock = dir.join("Cargo.lock");
+    if lock.is_file() {
+      return Some(lock);
+    }
+    if git_root.as_deref() == Some(dir) {
+      break;
+    }
+  }
+  None
+}
+
+fn write_documents(app: &Package, graph: &DependencyGraph) -> Result<SbomOutput, String> {
+  let dir = docs::sbom_output_dir(&app.path)?;
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+  let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+  let serial = document_uuid(graph, &timestamp);
+
+  let cyclonedx_path = dir.join("sbom.cdx.json");
+  let spdx_path = dir.join("sbom.spdx.json");
+  for (path, document) in [
+    (&cyclonedx_path, cyclonedx(graph, &timestamp, &serial)),
+    (&spdx_path, spdx(graph, &timestamp, &serial)),
+  ] {
+    let contents = serde_json::to_string_pretty(&document).map_err(|e| e.to_string())?;
+    std::fs::write(path, contents).map_err(|e| e.to_string())?;
+  }
+
+  Ok(SbomOutput {
+    package: app.name.clone(),
+    components: graph.components.len() - 1,
+    missing_licenses: graph
+      .components
+      .iter()
+      .filter(|(id, component)| **id != graph.root && component.license.is_none())
+      .map(|(id, _)| id.clone())
+      .collect(),
+    cyclonedx: cyclonedx_path.to_string_lossy().into_owned(),
+    spdx: spdx_path.to_string_lossy().into_owned(),
+  })
+}
+
+fn cyclonedx(graph: &DependencyGraph, timestamp: &str, serial: &str) -> serde_json::Value {
+  let root = &graph.components[&graph.root];
+  let component = |c: &Component, kind: &str| {
+    let mut value = json!({
+      "type": kind,
+      "bom-ref": c.purl(),
+      "name": c.name,
+      "version": c.version,
+      "purl": c.purl(),
+    });
+    if let Some(license) = &c.license {
+      // Licenses are normalized to SPDX: listed identifiers go in `license.id`, a lone
+      // reference to an unlisted license in `license.name`, compound expressions in `expression`
+      value["licenses"] = if license.contains([' ', '(']) {
+        json!([{ "expression": license }])
+      } else if let Some(name) = license.strip_prefix("LicenseRef-") {
+        json!([{ "license": { "name": name } }])
+      } else {
+        json!([{ "license": { "id": license } }])
+      };
+    }
+    if let Some((algorithm, hash)) = c.integrity.as_deref().and_then(decode_integrity) {
+      value["hashes"] = json!([{ "alg": algorithm.replace("SHA", "SHA-"), "content": hash }]);
+    }
+    value
+  };
+
+  let purls: BTreeMap<&String, String> = graph
+    .components
+    .iter()
+    .map(|(id, c)| (id, c.purl()))
+    .collect();
+  json!({
+    "bomFormat": "CycloneDX",
+    "specVersion": "1.5",
+    "serialNumber": format!("urn:uuid:{}", serial),
+    "version": 1,
+    "metadata": {
+      "timestamp": timestamp,
+      "tools": { "components": [{ "type": "application", "name": TOOL_NAME }] },
+      "component": component(root, "application"),
+    },
+    "components": graph
+      .components
+      .iter()
+      .filter(|(id, _)| **id != graph.root)
+      .map(|(_, c)| component(c, "library"))
+      .collect::<Vec<_>>(),
+    "dependencies": graph
+      .components
+      .iter()
+      .map(|(id, c)| json!({
+        "ref": purls[id],
+        "dependsOn": c
+          .dependencies
+          .iter()
+          .filter_map(|dependency| purls.get(dependency))
+          .collect::<Vec<_>>(),
+      }))
+      .collect::<Vec<_>>(),
+  })
+}
+
+fn spdx(graph: &DependencyGraph, timestamp: &str, serial: &str) -> serde_json::Value {
+  let spdx_ids: BTreeMap<&String, String> = graph
+    .components
+    .keys()
+    .enumerate()
+    .map(|(index, id)| (id, format!("SPDXRef-Package-{}", index)))
+    .collect();
+  let root = &graph.components[&graph.root];
+
+  let packages: Vec<serde_json::Value> = graph
+    .components
+    .iter()
+    .map(|(id, c)| {
+      let mut value = json!({
+        "name": c.name,
+        "SPDXID": spdx_ids[id],
+        "versionInfo": c.version,
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "licenseConcluded": "NOASSERTION",
+        "licenseDeclared": c.license.as_deref().unwrap_or("NOASSERTION"),
+        "copyrightText": "NOASSERTION",
+        "externalRefs": [{
+          "referenceCategory": "PACKAGE-MANAGER",
+          "referenceType": "purl",
+          "referenceLocator": c.purl(),
+        }],
+      });
+      if let Some((algorithm, hash)) = c.integrity.as_deref().and_then(decode_integrity) {
+        value["checksums"] = json!([{ "algorithm": algorithm, "checksumValue": hash }]);
+      }
+      value
+    })
+    .collect();
+
+  // Every `LicenseRef-` used has to be defined in the document
+  let references: BTreeSet<&str> = graph
+    .components
+    .values()
+    .filter_map(|c| c.license.as_deref())
+    .flat_map(|license| license.split(|c: char| c.is_whitespace() || c == '(' || c == ')'))
+    .filter(|id| id.starts_with("LicenseRef-"))
+    .collect();
+  let extracted: Vec<serde_json::Value> = references
+    .into_iter()
+    .map(|id| {
+      let name = id.trim_start_matches("LicenseRef-");
+      json!({
+        "licenseId": id,
+        "name": name,
+        "extractedText": format!("Not on the SPDX license list, declared as {}", name),
+      })
+    })
+    .collect();
+
+  let mut relationships = vec![json!({
+    "spdxElementId": "SPDXRef-DOCUMENT",
+    "relationshipType": "DESCRIBES",
+    "relatedSpdxElement": spdx_ids[&graph.root],
+  })];
+  for (id, component) in &graph.components {
+    for dependency in &component.dependencies {
+      if let Some(related) = spdx_ids.get(dependency) {
+        relationships.push(json!({
+          "spdxElementId": spdx_ids[id],
+          "relationshipType": "DEPENDS_ON",
+          "relatedSpdxElement": related,
+        }));
+      }
+    }
+  }
+
+  json!({
+    "spdxVersion": "SPDX-2.3",
+    "dataLicense": "CC0-1.0",
+    "SPDXID": "SPDXRef-DOCUMENT",
+    "name": format!("{}@{}", root.name, root.version),
+    "documentNamespace": format!(
+      "https://spdx.org/spdxdocs/{}-{}-{}",
+      root.name.trim_start_matches('@').replace('/', "-"),
+      root.version,
+      serial
+    ),
+    "creationInfo": {
+      "created": timestamp,
+      "creators": [format!("Tool: {}", TOOL_NAME)],
+    },
+    "packages": packages,
+    "hasExtractedLicensingInfos": extracted,
+    "relationships": relationships,
+  })
+}
+
+// `sha512-<base64>` -> (`SHA512`, hex digest); the first of several hashes is used
+fn decode_integrity(integrity: &str) -> Option<(String, String)> {
+  let (algorithm, digest) = integrity.split_whitespace().next()?.split_once('-')?;
+  if !matches!(algorithm, "sha1" | "sha256" | "sha384" | "sha512") {
+    return None;
+  }
+  let bytes = STANDARD.decode(digest).ok()?;
+  let hex = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+  Some((algorithm.to_uppercase(), hex))
+}
+
+// Version 4 style UUID derived from the document contents, so no random source is needed
+fn document_uuid(graph: &DependencyGraph, timestamp: &str) -> String {
+  let mut hasher = Sha256::new();
+  hasher.update(timestamp.as_bytes());
+  for id in graph.components.keys() {
+    hasher.update(id.as_bytes());
+    hasher.update([0]);
+  }
+  let mut bytes: [u8; 16] = hasher.finalize()[..16].try_into().unwrap_or_default();
+  bytes[6] = (bytes[6] & 0x0f) | 0x40;
+  bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+  let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+  format!(
+    "{}-{}-{}-{}-{}",
+    &hex[..8],
+    &hex[8..12],
+    &hex[12..16],
+    &hex[16..20],
+    &hex[20..]
+  )
+}
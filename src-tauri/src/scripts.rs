@@ -0,0 +1,339 @@
+use crate::git;
+use crate::jobs;
+use crate::sandbox;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::AppHandle;
+use toml_edit::{DocumentMut, Item};
+
+// Lockfiles in detection order, for repositories without a `packageManager` field
+const LOCKFILES: &[(&str, PackageManager)] = &[
+  ("pnpm-lock.yaml", PackageManager::Pnpm),
+  ("yarn.lock", PackageManager::Yarn),
+  ("bun.lockb", PackageManager::Bun),
+  ("bun.lock", PackageManager::Bun),
+  ("package-lock.json", PackageManager::Npm),
+  ("npm-shrinkwrap.json", PackageManager::Npm),
+];
+
+const MAKEFILES: &[&str] = &["GNUmakefile", "makefile", "Makefile"];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptSource {
+  Npm,
+  Cargo,
+  Make,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+  Npm,
+  Pnpm,
+  Yarn,
+  Bun,
+}
+
+impl PackageManager {
+  pub fn binary(self) -> &'static str {
+    match self {
+      PackageManager::Npm => "npm",
+      PackageManager::Pnpm => "pnpm",
+      PackageManager::Yarn => "yarn",
+      PackageManager::Bun => "bun",
+    }
+  }
+
+  fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "npm" => Some(PackageManager::Npm),
+      "pnpm" => Some(PackageManager::Pnpm),
+      "yarn" => Some(PackageManager::Yarn),
+      "bun" => Some(PackageManager::Bun),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Script {
+  pub name: String,
+  pub source: ScriptSource,
+  // Script body, alias expansion or recipe, for display
+  pub command: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PackageScripts {
+  pub path: String,
+  // Only set for packages with a `package.json`
+  pub package_manager: Option<PackageManager>,
+  pub scripts: Vec<Script>,
+}
+
+#[tauri::command]
+pub fn list_scripts(path: String) -> Result<PackageScripts, String> {
+  sandbox::resolve(&path).map_err(|e| e.to_string())?;
+  package_scripts(path)
+}
+
+fn package_scripts(path: String) -> Result<PackageScripts, String> {
+  let dir = Path::new(&path);
+  if !dir.is_dir() {
+    return Err("Path does not exist".to_string());
+  }
+
+  let mut scripts = npm_scripts(dir);
+  let package_manager = dir
+    .join("package.json")
+    .is_file()
+    .then(|| detect_package_manager(dir));
+  scripts.extend(cargo_aliases(dir));
+  scripts.extend(make_targets(dir));
+
+  Ok(PackageScripts {
+    path,
+    package_manager,
+    scripts,
+  })
+}
+
+// Runs a script as a background job; output streams as `job-log` events and `cancel_job` stops it.
+// Without `source`, package.json scripts win over Cargo aliases and Make targets of the same name.
+#[tauri::command]
+pub fn run_script(
+  app: AppHandle,
+  path: String,
+  name: String,
+  source: Option<ScriptSource>,
+  args: Option<Vec<String>>,
+) -> Result<String, String> {
+  sandbox::resolve(&path).map_err(|e| e.to_string())?;
+  let (script, package_manager) =
+    find_script(&path, &name, source)?.ok_or_else(|| format!("Unknown script: {}", name))?;
+  let dir = PathBuf::from(&path);
+  let command = build_command(&dir, &script, package_manager, &args.unwrap_or_default());
+
+  let folder = dir
+    .file_name()
+    .map(|n| n.to_string_lossy().into_owned())
+    .unwrap_or_else(|| path.clone());
+  let label = format!("{}: {}", folder, name);
+  Ok(jobs::spawn(&app, "script", &label, move |app, job_id| {
+    let output = jobs::run_command(app, job_id, command)?;
+    if !output.status.success() {
+      return Err(exit_error(&output.status));
+    }
+    Ok(None)
+  }))
+}
+
+pub fn find_script(
+  path: &str,
+  name: &str,
+  source: Option<ScriptSource>,
+) -> Result<Option<(Script, Option<PackageManager>)>, String> {
+  let listed = package_scripts(path.to_string())?;
+  Ok(
+    listed
+      .scripts
+      .into_iter()
+      .find(|s| s.name == name && source.is_none_or(|source| s.source == source))
+      .map(|script| (script, listed.package_manager)),
+  )
+}
+
+pub fn build_command(
+  dir: &Path,
+  script: &Script,
+  package_manager: Option<PackageManager>,
+  args: &[String],
+) -> Command {
+  let mut command = match script.source {
+    ScriptSource::Npm => {
+      let manager = package_manager.unwrap_or(PackageManager::Npm);
+      let mut command = Command::new(manager.binary());
+      command.args(["run", &script.name]);
+      // npm hands arguments after `--` to the script, the others pass them through as is
+      if manager == PackageManager::Npm && !args.is_empty() {
+        command.arg("--");
+      }
+      command
+    }
+    ScriptSource::Cargo => {
+      let mut command = Command::new("cargo");
+      command.arg(&script.name);
+      command
+    }
+    ScriptSource::Make => {
+      let mut command = Command::new("make");
+      command.arg(&script.name);
+      command
+    }
+  };
+  command.args(args).current_dir(dir);
+  command
+}
+
+pub fn exit_error(status: &std::process::ExitStatus) -> String {
+  match status.code() {
+    Some(code) => format!("Script exited with code {}", code),
+    None => "Script was terminated".to_string(),
+  }
+}
+
+// `packageManager` in the nearest package.json wins, then the first lockfile found walking up
+// to the repository root
+pub fn detect_package_manager(dir: &Path) -> PackageManager {
+  let git_root = git::find_git_root(dir).map(PathBuf::from);
+  for ancestor in dir.ancestors() {
+    let declared = std::fs::read_to_string(ancestor.join("package.json"))
+      .ok()
+      .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+      .and_then(|json| {
+        let field = json.get("packageManager")?.as_str()?;
+        PackageManager::from_name(field.split('@').next()?)
+      });
+    if let Some(manager) = declared {
+      return manager;
+    }
+    if let Some((_, manager)) = LOCKFILES
+      .iter()
+      .find(|(lockfile, _)| ancestor.join(lockfile).is_file())
+    {
+      return *manager;
+    }
+    if git_root.as_deref() == Some(ancestor) {
+      break;
+    }
+  }
+  PackageManager::Npm
+}
+
+fn npm_scripts(dir: &Path) -> Vec<Script> {
+  let Some(json) = std::fs::read_to_string(dir.join("package.json"))
+    .ok()
+    .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+  else {
+    return Vec::new();
+  };
+  let Some(scripts) = json.get("scripts").and_then(|s| s.as_object()) else {
+    return Vec::new();
+  };
+
+  scripts
+    .iter()
+    .filter_map(|(name, command)| {
+      Some(Script {
+        name: name.clone(),
+        source: ScriptSource::Npm,
+        command: command.as_str()?.to_string(),
+      })
+    })
+    .collect()
+}
+
+// Aliases from `.cargo/config.toml` files between the crate and the repository root,
+// the nearest definition winning like it does for cargo
+fn cargo_aliases(dir: &Path) -> Vec<Script> {
+  if !dir.join("Cargo.toml").is_file() {
+    return Vec::new();
+  }
+  let git_root = git::find_git_root(dir).map(PathBuf::from);
+
+  let mut aliases: Vec<Script> = Vec::new();
+  for ancestor in dir.ancestors() {
+    for file in ["config.toml", "config"] {
+      let Some(document) = std::fs::read_to_string(ancestor.join(".cargo").join(file))
+        .ok()
+        .and_then(|contents| contents.parse::<DocumentMut>().ok())
+      else {
+        continue;
+      };
+      let Some(table) = document.get("alias").and_then(Item::as_table_like) else {
+        continue;
+      };
+      for (name, value) in table.iter() {
+        if aliases.iter().any(|alias| alias.name == name) {
+          continue;
+        }
+        let command = match value.as_str() {
+          Some(command) => command.to_string(),
+          None => match value.as_array() {
+            Some(parts) => parts
+              .iter()
+              .filter_map(|part| part.as_str())
+              .collect::<Vec<_>>()
+              .join(" "),
+            None => continue,
+          },
+        };
+        aliases.push(Script {
+          name: name.to_string(),
+          source: ScriptSource::Cargo,
+          command,
+        });
+      }
+    }
+    if git_root.as_deref() == Some(ancestor) {
+      break;
+    }
+  }
+  aliases
+}
+
+// Explicit targets of the package's Makefile with their recipes; special and pattern rules are left out
+fn make_targets(dir: &Path) -> Vec<Script> {
+  let Some(contents) = MAKEFILES
+    .iter()
+    .find_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+  else {
+    return Vec::new();
+  };
+
+  let mut targets: Vec<Script> = Vec::new();
+  let mut current: Vec<usize> = Vec::new();
+  for line in contents.lines() {
+    if let Some(recipe) = line.strip_prefix('\t') {
+      for &index in &current {
+        let command = &mut targets[index].command;
+        if !command.is_empty() {
+          command.push('\n');
+        }
+        command.push_str(recipe.trim());
+      }
+      continue;
+    }
+    if line.trim().is_empty() || line.trim_start().starts_with('#') {
+      continue;
+    }
+    current.clear();
+
+    // `targets: prerequisites`, not a `:=` or `::=` assignment
+    let Some((names, rest)) = line.split_once(':') else {
+      continue;
+    };
+    if rest.starts_with('=') || rest.starts_with(":=") || names.contains('=') {
+      continue;
+    }
+    for name in names.split_whitespace() {
+      if name.starts_with('.') || name.contains('%') || name.contains('$') {
+        continue;
+      }
+      match targets.iter().position(|target| target.name == name) {
+        Some(index) => current.push(index),
+        None => {
+          current.push(targets.len());
+          targets.push(Script {
+            name: name.to_string(),
+            source: ScriptSource::Make,
+            command: String::new(),
+          });
+        }
+      }
+    }
+  }
+  targets
+}
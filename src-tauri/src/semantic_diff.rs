@@ -0,0 +1,380 @@
+use crate::lockfile::{self, Lockfile, LockfileKind};
+use crate::workspace::DependencyKind;
+use git2::{ObjectType, Oid, Repository, Tree, TreeWalkMode, TreeWalkResult};
+use moka::sync::Cache;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::cell::{OnceCell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const MANIFEST_SECTIONS: &[(&str, DependencyKind)] = &[
+  ("dependencies", DependencyKind::Normal),
+  ("devDependencies", DependencyKind::Dev),
+  ("peerDependencies", DependencyKind::Peer),
+  ("optionalDependencies", DependencyKind::Optional),
+];
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+  Added,
+  Removed,
+  Changed,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ValueChange {
+  pub change: ChangeKind,
+  pub old: Option<String>,
+  pub new: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DependencyChange {
+  pub name: String,
+  pub kind: DependencyKind,
+  #[serde(flatten)]
+  pub range: ValueChange,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ScriptChange {
+  pub name: String,
+  #[serde(flatten)]
+  pub command: ValueChange,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ManifestDiff {
+  pub name: Option<String>,
+  pub version: Option<ValueChange>,
+  pub dependencies: Vec<DependencyChange>,
+  pub scripts: Vec<ScriptChange>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ResolutionChange {
+  pub name: String,
+  // Whether the importer depends on the package directly
+  pub direct: bool,
+  pub old: Vec<String>,
+  pub new: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ImporterDiff {
+  pub path: String,
+  pub name: Option<String>,
+  pub changes: Vec<ResolutionChange>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LockfileDiff {
+  pub lockfile: LockfileKind,
+  pub importers: Vec<ImporterDiff>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SemanticDiff {
+  Manifest(ManifestDiff),
+  Lockfile(LockfileDiff),
+}
+
+// Manifests a parse read, with their blob at the time (None when missing)
+type ManifestReads = Vec<(PathBuf, Option<Oid>)>;
+
+// Parsed lockfiles by blob, reused while the manifests they read are unchanged. Consecutive
+// commits mostly share the lockfile, so each side is usually parsed once.
+static PARSED_LOCKFILES: Lazy<Cache<Oid, Arc<(ManifestReads, Arc<Lockfile>)>>> =
+  Lazy::new(|| Cache::new(50));
+
+// Semantic diff of a package.json or lockfile between two trees; None for other files, or when
+// neither side parses
+pub fn diff_file(
+  repo: &Repository,
+  old_tree: Option<&Tree>,
+  new_tree: &Tree,
+  file: &str,
+) -> Option<SemanticDiff> {
+  let path = Path::new(file);
+  let file_name = path.file_name()?.to_str()?;
+
+  if file_name == "package.json" {
+    let old = old_tree.and_then(|tree| read_json(repo, tree, path));
+    let new = read_json(repo, new_tree, path);
+    if old.is_none() && new.is_none() {
+      return None;
+    }
+    return Some(SemanticDiff::Manifest(manifest_diff(
+      old.as_ref(),
+      new.as_ref(),
+    )));
+  }
+
+  let (_, kind) = lockfile::LOCKFILES
+    .iter()
+    .find(|(name, _)| *name == file_name)?;
+  let dir = path.parent().unwrap_or(Path::new(""));
+  let old = old_tree.and_then(|tree| parse_lockfile(repo, tree, path, dir, *kind));
+  let new = parse_lockfile(repo, new_tree, path, dir, *kind);
+  if old.is_none() && new.is_none() {
+    return None;
+  }
+  Some(SemanticDiff::Lockfile(lockfile_diff(
+    *kind,
+    old.as_deref(),
+    new.as_deref(),
+  )))
+}
+
+// Parses the lockfile at `path` with its importer manifests read from the same revision
+fn parse_lockfile(
+  repo: &Repository,
+  tree: &Tree,
+  path: &Path,
+  dir: &Path,
+  kind: LockfileKind,
+) -> Option<Arc<Lockfile>> {
+  let oid = tree.get_path(path).ok()?.id();
+  if let Some(cached) = PARSED_LOCKFILES.get(&oid) {
+    let (reads, lockfile) = cached.as_ref();
+    let unchanged = reads
+      .iter()
+      .all(|(manifest, blob)| tree.get_path(manifest).ok().map(|e| e.id()) == *blob);
+    if unchanged {
+      return Some(lockfile.clone());
+    }
+  }
+
+  let contents = read_blob(repo, tree, path)?;
+  let reads = RefCell::new(ManifestReads::new());
+  let read_manifest = |relative: &str| {
+    let manifest = if relative == "." {
+      dir.join("package.json")
+    } else {
+      dir.join(relative).join("package.json")
+    };
+    let blob = tree.get_path(&manifest).ok().map(|entry| entry.id());
+    reads.borrow_mut().push((manifest.clone(), blob));
+    read_json(repo, tree, &manifest)
+  };
+  let package_dirs = OnceCell::new();
+  let expand = |pattern: &str| -> Vec<String> {
+    let Ok(pattern) = glob::Pattern::new(pattern) else {
+      return Vec::new();
+    };
+    let options = glob::MatchOptions {
+      require_literal_separator: true,
+      ..Default::default()
+    };
+    package_dirs
+      .get_or_init(|| package_dirs_at(repo, tree, dir))
+      .iter()
+      .filter(|package_dir| pattern.matches_with(package_dir, options))
+      .cloned()
+      .collect()
+  };
+
+  let lockfile = Arc::new(lockfile::parse(kind, &contents, &read_manifest, &expand).ok()?);
+  PARSED_LOCKFILES.insert(oid, Arc::new((reads.into_inner(), lockfile.clone())));
+  Some(lockfile)
+}
+
+// Directories below `dir` holding a package.json, relative to it
+fn package_dirs_at(repo: &Repository, tree: &Tree, dir: &Path) -> Vec<String> {
+  let subtree = if dir.as_os_str().is_empty() {
+    Some(tree.clone())
+  } else {
+    tree
+      .get_path(dir)
+      .and_then(|entry| entry.to_object(repo))
+      .and_then(|object| object.peel_to_tree())
+      .ok()
+  };
+  let Some(subtree) = subtree else {
+    return Vec::new();
+  };
+
+  let mut dirs = Vec::new();
+  let _ = subtree.walk(TreeWalkMode::PreOrder, |parent, entry| {
+    match (entry.kind(), entry.name()) {
+      (Some(ObjectType::Tree), Some("node_modules")) => return TreeWalkResult::Skip,
+      (Some(ObjectType::Blob), Some("package.json")) if !parent.is_empty() => {
+        dirs.push(parent.trim_end_matches('/').to_string());
+      }
+      _ => {}
+    }
+    TreeWalkResult::Ok
+  });
+  dirs
+}
+
+fn manifest_diff(old: Option<&serde_json::Value>, new: Option<&serde_json::Value>) -> ManifestDiff {
+  let string = |json: Option<&serde_json::Value>, key: &str| {
+    json
+      .and_then(|json| json.get(key))
+      .and_then(|value| value.as_str())
+      .map(String::from)
+  };
+
+  let mut dependencies = Vec::new();
+  for (section, kind) in MANIFEST_SECTIONS {
+    for (name, range) in map_changes(old, new, section) {
+      dependencies.push(DependencyChange {
+        name,
+        kind: *kind,
+        range,
+      });
+    }
+  }
+
+  ManifestDiff {
+    name: string(new, "name").or_else(|| string(old, "name")),
+    version: value_change(string(old, "version"), string(new, "version")),
+    dependencies,
+    scripts: map_changes(old, new, "scripts")
+      .into_iter()
+      .map(|(name, command)| ScriptChange { name, command })
+      .collect(),
+  }
+}
+
+// Changed entries of a string map such as `dependencies` or `scripts`, by key
+fn map_changes(
+  old: Option<&serde_json::Value>,
+  new: Option<&serde_json::Value>,
+  section: &str,
+) -> Vec<(String, ValueChange)> {
+  let entries = |json: Option<&serde_json::Value>| -> BTreeMap<String, String> {
+    json
+      .and_then(|json| json.get(section))
+      .and_then(|section| section.as_object())
+      .into_iter()
+      .flatten()
+      .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+      .collect()
+  };
+  let old = entries(old);
+  let new = entries(new);
+
+  let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+  keys
+    .into_iter()
+    .filter_map(|key| {
+      let change = value_change(old.get(key).cloned(), new.get(key).cloned())?;
+      Some((key.clone(), change))
+    })
+    .collect()
+}
+
+fn value_change(old: Option<String>, new: Option<String>) -> Option<ValueChange> {
+  let change = match (&old, &new) {
+    (None, None) => return None,
+    (None, Some(_)) => ChangeKind::Added,
+    (Some(_), None) => ChangeKind::Removed,
+    (Some(old), Some(new)) if old == new => return None,
+    (Some(_), Some(_)) => ChangeKind::Changed,
+  };
+  Some(ValueChange { change, old, new })
+}
+
+// Per importer, the versions of every package it resolves to, directly or transitively
+fn lockfile_diff(
+  kind: LockfileKind,
+  old: Option<&Lockfile>,
+  new: Option<&Lockfile>,
+) -> LockfileDiff {
+  let paths: BTreeSet<&str> = old
+    .into_iter()
+    .chain(new)
+    .flat_map(|lockfile| lockfile.importers.iter().map(|i| i.path.as_str()))
+    .collect();
+
+  let mut importers = Vec::new();
+  for path in paths {
+    let old_versions = old.map(|l| resolved_versions(l, path)).unwrap_or_default();
+    let new_versions = new.map(|l| resolved_versions(l, path)).unwrap_or_default();
+    let direct: HashSet<&str> = old
+      .into_iter()
+      .chain(new)
+      .filter_map(|lockfile| lockfile.importers.iter().find(|i| i.path == path))
+      .flat_map(|importer| importer.dependencies.iter().map(|d| d.name.as_str()))
+      .collect();
+
+    let names: BTreeSet<&String> = old_versions.keys().chain(new_versions.keys()).collect();
+    let mut changes: Vec<ResolutionChange> = names
+      .into_iter()
+      .filter_map(|name| {
+        let old = old_versions.get(name).cloned().unwrap_or_default();
+        let new = new_versions.get(name).cloned().unwrap_or_default();
+        (old != new).then(|| ResolutionChange {
+          name: name.clone(),
+          direct: direct.contains(name.as_str()),
+          old: old.into_iter().collect(),
+          new: new.into_iter().collect(),
+        })
+      })
+      .collect();
+    if changes.is_empty() {
+      continue;
+    }
+    changes.sort_by_key(|change| !change.direct);
+
+    let name = new
+      .into_iter()
+      .chain(old)
+      .filter_map(|lockfile| lockfile.importers.iter().find(|i| i.path == path))
+      .find_map(|importer| importer.name.clone());
+    importers.push(ImporterDiff {
+      path: path.to_string(),
+      name,
+      changes,
+    });
+  }
+
+  LockfileDiff {
+    lockfile: kind,
+    importers,
+  }
+}
+
+fn resolved_versions(lockfile: &Lockfile, path: &str) -> BTreeMap<String, BTreeSet<String>> {
+  let mut versions: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+  let Some(importer) = lockfile.importers.iter().find(|i| i.path == path) else {
+    return versions;
+  };
+
+  let mut seen: HashSet<&str> = HashSet::new();
+  let mut stack: Vec<&str> = importer
+    .dependencies
+    .iter()
+    .filter_map(|d| d.id.as_deref())
+    .collect();
+  while let Some(id) = stack.pop() {
+    if !seen.insert(id) {
+      continue;
+    }
+    let Some(package) = lockfile.packages.get(id) else {
+      continue;
+    };
+    versions
+      .entry(package.name.clone())
+      .or_default()
+      .insert(package.version.clone());
+    stack.extend(package.dependencies.iter().map(String::as_str));
+  }
+  versions
+}
+
+fn read_blob(repo: &Repository, tree: &Tree, path: &Path) -> Option<String> {
+  let entry = tree.get_path(path).ok()?;
+  let blob = repo.find_blob(entry.id()).ok()?;
+  String::from_utf8(blob.content().to_vec()).ok()
+}
+
+fn read_json(repo: &Repository, tree: &Tree, path: &Path) -> Option<serde_json::Value> {
+  serde_json::from_str(&read_blob(repo, tree, path)?).ok()
+}
@@ -0,0 +1,192 @@
+use crate::config;
+use crate::git::{self, BasicCommit};
+use crate::workspace::{self, Package, Workspace};
+use git2::Repository;
+use regex::Regex;
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Tag {
+  pub name: String,
+  pub commit: String,
+  pub date: i64,
+  pub package: Option<String>,
+  pub version: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Release {
+  pub tag: String,
+  pub version: String,
+  pub date: i64,
+  pub commit: String,
+  pub commits: Vec<BasicCommit>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PackageTimeline {
+  pub name: String,
+  pub path: String,
+  pub releases: Vec<Release>,
+  pub unreleased: Vec<BasicCommit>,
+}
+
+#[tauri::command]
+pub fn list_tags(path: String) -> Result<Vec<Tag>, String> {
+  let workspace = workspace::load_workspace(&path)?;
+  package_tags(&workspace)
+}
+
+#[tauri::command]
+pub fn get_package_timeline(
+  path: String,
+  package: Option<String>,
+) -> Result<Vec<PackageTimeline>, String> {
+  let workspace = workspace::load_workspace(&path)?;
+  let tags = package_tags(&workspace)?;
+
+  workspace
+    .packages
+    .iter()
+    .filter(|p| package.as_ref().is_none_or(|name| &p.name == name))
+    .map(|p| timeline(&workspace, p, &tags))
+    .collect()
+}
+
+// All tags in the repository, mapped to workspace packages through the configured patterns
+pub fn package_tags(workspace: &Workspace) -> Result<Vec<Tag>, String> {
+  let config = config::load_config(&workspace.root)?;
+  let patterns: Vec<Regex> = config
+    .tags
+    .patterns
+    .iter()
+    .map(|p| pattern_regex(p))
+    .collect::<Result<_, _>>()?;
+
+  let repo = Repository::discover(&workspace.root)
+    .map_err(|e| format!("Failed to open repository: {}", e))?;
+  let names = repo.tag_names(None).map_err(|e| e.to_string())?;
+
+  let mut tags = Vec::new();
+  for name in names.iter().flatten() {
+    let Ok(object) = repo.revparse_single(&format!("refs/tags/{}", name)) else {
+      continue;
+    };
+    let Ok(commit) = object.peel_to_commit() else {
+      continue;
+    };
+    // Annotated tags carry their own date, lightweight ones use the commit's
+    let date = object
+      .as_tag()
+      .and_then(|t| t.tagger())
+      .map(|s| s.when().seconds())
+      .unwrap_or_else(|| commit.time().seconds());
+
+    let (package, version) = match match_tag(name, &patterns, workspace) {
+      Some((package, version)) => (Some(package), Some(version)),
+      None => (None, None),
+    };
+
+    tags.push(Tag {
+      name: name.to_string(),
+      commit: commit.id().to_string(),
+      date,
+      package,
+      version,
+    });
+  }
+
+  tags.sort_by_key(|t| std::cmp::Reverse(t.date));
+  Ok(tags)
+}
+
+// Release tags of a package, oldest version first
+pub fn releases_of<'a>(tags: &'a [Tag], package: &str) -> Vec<&'a Tag> {
+  let mut releases: Vec<&Tag> = tags
+    .iter()
+    .filter(|t| t.package.as_deref() == Some(package))
+    .collect();
+  releases.sort_by_cached_key(|t| {
+    t.version
+      .as_deref()
+      .and_then(|v| semver::Version::parse(v).ok())
+  });
+  releases
+}
+
+fn timeline(
+  workspace: &Workspace,
+  package: &Package,
+  tags: &[Tag],
+) -> Result<PackageTimeline, String> {
+  let mut releases = Vec::new();
+  let mut previous: Option<&str> = None;
+
+  for tag in releases_of(tags, &package.name) {
+    let commits =
+      git::list_commits_in_range(&workspace.root, previous, &tag.name, &package.relative_path)?;
+    releases.push(Release {
+      tag: tag.name.clone(),
+      version: tag.version.clone().unwrap_or_default(),
+      date: tag.date,
+      commit: tag.commit.clone(),
+      commits,
+    });
+    previous = Some(&tag.name);
+  }
+
+  let unreleased =
+    git::list_commits_in_range(&workspace.root, previous, "HEAD", &package.relative_path)?;
+  releases.reverse();
+
+  Ok(PackageTimeline {
+    name: package.name.clone(),
+    path: package.path.clone(),
+    releases,
+    unreleased,
+  })
+}
+
+fn pattern_regex(pattern: &str) -> Result<Regex, String> {
+  let escaped = regex::escape(pattern)
+    .replace(r"\{name\}", "(?P<name>.+?)")
+    .replace(
+      r"\{version\}",
+      r"(?P<version>\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?)",
+    );
+  Regex::new(&format!("^{}$", escaped))
+    .map_err(|e| format!("Invalid tag pattern {}: {}", pattern, e))
+}
+
+fn match_tag(tag: &str, patterns: &[Regex], workspace: &Workspace) -> Option<(String, String)> {
+  for pattern in patterns {
+    let Some(captures) = pattern.captures(tag) else {
+      continue;
+    };
+    let version = captures.name("version")?.as_str().to_string();
+
+    let package = match captures.name("name") {
+      Some(name) => find_package(workspace, name.as_str()),
+      // Patterns without `{name}` (e.g. `v{version}`) only make sense for a single package
+      None => match workspace.packages.as_slice() {
+        [only] => Some(only),
+        packages => packages.iter().find(|p| p.relative_path.is_empty()),
+      },
+    };
+
+    if let Some(package) = package {
+      return Some((package.name.clone(), version));
+    }
+  }
+  None
+}
+
+fn find_package<'a>(workspace: &'a Workspace, name: &str) -> Option<&'a Package> {
+  workspace.find(name).or_else(|| {
+    workspace.packages.iter().find(|p| {
+      let unscoped = p.name.rsplit('/').next().unwrap_or(&p.name);
+      let folder = p.relative_path.rsplit('/').next().unwrap_or_default();
+      unscoped == name || folder == name
+    })
+  })
+}
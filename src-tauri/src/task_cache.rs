@@ -0,0 +1,432 @@
+use crate::config::{self, TaskConfig};
+use crate::docs;
+use crate::git;
+use crate::jobs;
+use crate::scripts::{PackageManager, Script};
+use crate::workspace::{Package, Workspace};
+use git2::{ObjectType, Oid, Repository, Status, StatusOptions};
+use moka::sync::Cache;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::process::Output;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const META_FILE: &str = "meta.json";
+const STDOUT_FILE: &str = "stdout.log";
+const STDERR_FILE: &str = "stderr.log";
+const OUTPUTS_DIR: &str = "outputs";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CacheEntry {
+  pub hash: String,
+  pub package: String,
+  pub script: String,
+  pub created_at: i64,
+  pub last_used: i64,
+  pub duration_ms: u64,
+  // Bytes on disk, logs included
+  pub size: u64,
+  // Restored output files, relative to the package
+  pub outputs: Vec<String>,
+}
+
+// In-memory layer over the entries' `meta.json`, keyed by entry directory
+static ENTRY_CACHE: Lazy<Cache<PathBuf, Arc<CacheEntry>>> = Lazy::new(|| {
+  Cache::builder()
+    .time_to_live(Duration::from_secs(600))
+    .max_capacity(1000)
+    .build()
+});
+
+pub fn clear_cache() {
+  ENTRY_CACHE.invalidate_all();
+}
+
+// Removes every cached task result of the repository, returning how many there were
+#[tauri::command]
+pub fn clear_task_cache(path: String) -> Result<usize, String> {
+  let tasks = config::load_config(&path)?.tasks.clone();
+  let dir = cache_dir(&path, &tasks)?;
+  let entries = read_entries(&dir);
+  for (entry_dir, _) in &entries {
+    std::fs::remove_dir_all(entry_dir).map_err(|e| e.to_string())?;
+  }
+  clear_cache();
+  Ok(entries.len())
+}
+
+pub fn cache_dir(path: &str, tasks: &TaskConfig) -> Result<PathBuf, String> {
+  match tasks.cache_dir.as_deref() {
+    Some(dir) => {
+      let git_root = git::find_git_root(Path::new(path)).map(PathBuf::from);
+      docs::resolve_root(dir, git_root.as_deref())
+    }
+    None => Ok(
+      dirs::cache_dir()
+        .ok_or_else(|| "Could not find cache directory".to_string())?
+        .join("mr-manager")
+        .join("tasks"),
+    ),
+  }
+}
+
+// Per package: its files as the index and working tree have them (ignored files aside),
+// combined with the hashes of the workspace packages it depends on
+pub fn input_hashes(workspace: &Workspace) -> Result<HashMap<String, String>, String> {
+  let git_root =
+    git::find_git_root(Path::new(&workspace.root)).ok_or("Could not find Git repository")?;
+  let root = Path::new(&git_root);
+  let repo = Repository::open(root).map_err(|e| e.to_string())?;
+  let owner = |relative: &str| {
+    workspace
+      .package_containing(&root.join(relative))
+      .map(|package| package.name.as_str())
+  };
+
+  let mut files: HashMap<&str, BTreeMap<String, String>> = HashMap::new();
+  let index = repo.index().map_err(|e| e.to_string())?;
+  for entry in index.iter() {
+    let path = String::from_utf8_lossy(&entry.path).into_owned();
+    if let Some(name) = owner(&path) {
+      files
+        .entry(name)
+        .or_default()
+        .insert(path, entry.id.to_string());
+    }
+  }
+
+  // Uncommitted edits count too, hashed the way git would store them
+  let mut options = StatusOptions::new();
+  options
+    .include_untracked(true)
+    .recurse_untracked_dirs(true)
+    .include_ignored(false);
+  let statuses = repo
+    .statuses(Some(&mut options))
+    .map_err(|e| e.to_string())?;
+  for status in statuses.iter() {
+    let Some(path) = status.path() else {
+      continue;
+    };
+    let Some(name) = owner(path) else {
+      continue;
+    };
+    let package_files = files.entry(name).or_default();
+    if status.status().contains(Status::WT_DELETED) {
+      package_files.remove(path);
+    } else if status
+      .status()
+      .intersects(Status::WT_NEW | Status::WT_MODIFIED | Status::WT_TYPECHANGE | Status::WT_RENAMED)
+    {
+      match Oid::hash_file(ObjectType::Blob, root.join(path)) {
+        Ok(oid) => package_files.insert(path.to_string(), oid.to_string()),
+        Err(_) => package_files.remove(path),
+      };
+    }
+  }
+
+  let mut hashes = HashMap::new();
+  for package in &workspace.packages {
+    combine(workspace, package, &files, &mut hashes, &mut Vec::new());
+  }
+  Ok(hashes)
+}
+
+fn combine(
+  workspace: &Workspace,
+  package: &Package,
+  files: &HashMap<&str, BTreeMap<String, String>>,
+  hashes: &mut HashMap<String, String>,
+  visiting: &mut Vec<String>,
+) -> String {
+  if let Some(hash) = hashes.get(&package.name) {
+    return hash.clone();
+  }
+  visiting.push(package.name.clone());
+
+  let mut hasher = Sha256::new();
+  for (path, oid) in files.get(package.name.as_str()).into_iter().flatten() {
+    hasher.update(format!("file {} {}\n", path, oid));
+  }
+  let mut dependencies = workspace.internal_dependencies(package);
+  dependencies.sort_by(|a, b| a.name.cmp(&b.name));
+  for dependency in dependencies {
+    // A cycle adds nothing more than the packages already being hashed
+    if visiting.contains(&dependency.name) {
+      continue;
+    }
+    let hash = combine(workspace, dependency, files, hashes, visiting);
+    hasher.update(format!("dependency {} {}\n", dependency.name, hash));
+  }
+
+  visiting.pop();
+  let hash = to_hex(&hasher.finalize());
+  hashes.insert(package.name.clone(), hash.clone());
+  hash
+}
+
+// Hash of running `script` on inputs hashing to `input_hash`, under the tasks config that
+// decides what a cached result holds
+pub fn task_hash(
+  input_hash: &str,
+  script: &Script,
+  package_manager: Option<PackageManager>,
+  tasks: &TaskConfig,
+) -> String {
+  let mut hasher = Sha256::new();
+  hasher.update(format!("inputs {}\n", input_hash));
+  hasher.update(format!(
+    "script {:?} {} {}\n{}\n",
+    script.source,
+    package_manager.map_or("", |manager| manager.binary()),
+    script.name,
+    script.command
+  ));
+  let mut env: Vec<&String> = tasks.env.iter().collect();
+  env.sort();
+  env.dedup();
+  for name in env {
+    hasher.update(format!(
+      "env {}={}\n",
+      name,
+      std::env::var(name).unwrap_or_default()
+    ));
+  }
+  // Entries only hold the files matching the outputs they were stored with
+  let mut outputs: Vec<&String> = tasks.outputs.iter().collect();
+  outputs.sort();
+  outputs.dedup();
+  for glob in outputs {
+    hasher.update(format!("output {}\n", glob));
+  }
+  to_hex(&hasher.finalize())
+}
+
+pub fn lookup(dir: &Path, hash: &str) -> Option<Arc<CacheEntry>> {
+  let entry_dir = dir.join(hash);
+  // Entries evicted behind our back must not be served from memory
+  if !entry_dir.join(META_FILE).is_file() {
+    ENTRY_CACHE.invalidate(&entry_dir);
+    return None;
+  }
+  if let Some(entry) = ENTRY_CACHE.get(&entry_dir) {
+    return Some(entry);
+  }
+  let entry = Arc::new(read_meta(&entry_dir)?);
+  ENTRY_CACHE.insert(entry_dir, entry.clone());
+  Some(entry)
+}
+
+// Records a successful run: its logs and the package files matching the configured outputs
+pub fn store(
+  dir: &Path,
+  hash: &str,
+  package: &Package,
+  script: &str,
+  tasks: &TaskConfig,
+  output: &Output,
+  duration: Duration,
+) -> Result<(), String> {
+  // Written next to the entry and moved into place once complete
+  let staging = dir.join(format!(".{}-{}", hash, std::process::id()));
+  let _ = std::fs::remove_dir_all(&staging);
+  let outputs = match write_staging(&staging, Path::new(&package.path), tasks, output) {
+    Ok(outputs) => outputs,
+    Err(error) => {
+      let _ = std::fs::remove_dir_all(&staging);
+      return Err(error);
+    }
+  };
+
+  let now = chrono::Utc::now().timestamp();
+  let entry = CacheEntry {
+    hash: hash.to_string(),
+    package: package.name.clone(),
+    script: script.to_string(),
+    created_at: now,
+    last_used: now,
+    duration_ms: duration.as_millis() as u64,
+    size: dir_size(&staging),
+    outputs,
+  };
+  write_meta(&staging, &entry)?;
+
+  let entry_dir = dir.join(hash);
+  let _ = std::fs::remove_dir_all(&entry_dir);
+  std::fs::rename(&staging, &entry_dir).map_err(|e| e.to_string())?;
+  ENTRY_CACHE.insert(entry_dir, Arc::new(entry));
+
+  evict(dir, tasks.max_cache_mb * 1024 * 1024);
+  Ok(())
+}
+
+// Writes the logs and copies the outputs, returning the copied paths relative to the package
+fn write_staging(
+  staging: &Path,
+  package_dir: &Path,
+  tasks: &TaskConfig,
+  output: &Output,
+) -> Result<Vec<String>, String> {
+  std::fs::create_dir_all(staging.join(OUTPUTS_DIR)).map_err(|e| e.to_string())?;
+  std::fs::write(staging.join(STDOUT_FILE), &output.stdout).map_err(|e| e.to_string())?;
+  std::fs::write(staging.join(STDERR_FILE), &output.stderr).map_err(|e| e.to_string())?;
+
+  let mut copied = Vec::new();
+  for pattern in &tasks.outputs {
+    // `dir/**` only matches directories in `glob`, their files are meant
+    let pattern = match pattern.strip_suffix("**") {
+      Some(prefix) => package_dir.join(format!("{}**/*", prefix)),
+      None => package_dir.join(pattern),
+    };
+    let matches = glob::glob(&pattern.to_string_lossy())
+      .map_err(|e| format!("Invalid output pattern {}: {}", pattern.display(), e))?;
+    for file in matches.flatten().filter(|file| file.is_file()) {
+      let Ok(relative) = file.strip_prefix(package_dir) else {
+        continue;
+      };
+      let target = staging.join(OUTPUTS_DIR).join(relative);
+      if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+      }
+      std::fs::copy(&file, &target).map_err(|e| e.to_string())?;
+      copied.push(relative.to_string_lossy().replace('\\', "/"));
+    }
+  }
+  copied.sort();
+  copied.dedup();
+  Ok(copied)
+}
+
+// Replays a cached run: logs as job output, outputs back into the package in place of the
+// current ones, so files the cached run did not produce do not linger
+pub fn replay(
+  app: &AppHandle,
+  job_id: &str,
+  dir: &Path,
+  tasks: &TaskConfig,
+  entry: &CacheEntry,
+  package_dir: &Path,
+) -> Result<(), String> {
+  let entry_dir = dir.join(&entry.hash);
+  for (file, stream) in [(STDOUT_FILE, "stdout"), (STDERR_FILE, "stderr")] {
+    let log = std::fs::read(entry_dir.join(file)).unwrap_or_default();
+    for line in String::from_utf8_lossy(&log).lines() {
+      jobs::emit_log(app, job_id, stream, line);
+    }
+  }
+
+  clear_outputs(package_dir, tasks)?;
+  for relative in &entry.outputs {
+    let target = package_dir.join(relative);
+    if let Some(parent) = target.parent() {
+      std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::copy(entry_dir.join(OUTPUTS_DIR).join(relative), &target)
+      .map_err(|e| format!("Failed to restore {}: {}", relative, e))?;
+  }
+
+  let entry = CacheEntry {
+    last_used: chrono::Utc::now().timestamp(),
+    ..entry.clone()
+  };
+  write_meta(&entry_dir, &entry)?;
+  ENTRY_CACHE.insert(entry_dir, Arc::new(entry));
+  Ok(())
+}
+
+// Removes what the output patterns match: whole directories for `dir/**`, files otherwise
+fn clear_outputs(package_dir: &Path, tasks: &TaskConfig) -> Result<(), String> {
+  for pattern in &tasks.outputs {
+    let (pattern, whole_dirs) = match pattern.strip_suffix("/**") {
+      Some(prefix) => (prefix, true),
+      None => (pattern.as_str(), false),
+    };
+    // Patterns reaching out of the package are not cleared
+    if Path::new(pattern)
+      .components()
+      .any(|c| !matches!(c, Component::Normal(_)))
+    {
+      continue;
+    }
+
+    let pattern = package_dir.join(pattern);
+    let matches = glob::glob(&pattern.to_string_lossy())
+      .map_err(|e| format!("Invalid output pattern {}: {}", pattern.display(), e))?;
+    for path in matches.flatten() {
+      let removed = if whole_dirs && path.is_dir() {
+        std::fs::remove_dir_all(&path)
+      } else if path.is_file() {
+        std::fs::remove_file(&path)
+      } else {
+        continue;
+      };
+      removed.map_err(|e| format!("Failed to clear {}: {}", path.display(), e))?;
+    }
+  }
+  Ok(())
+}
+
+// Drops least recently used entries until the cache fits in `max_bytes`
+fn evict(dir: &Path, max_bytes: u64) {
+  let mut entries = read_entries(dir);
+  let mut total: u64 = entries.iter().map(|(_, entry)| entry.size).sum();
+  entries.sort_by_key(|(_, entry)| entry.last_used);
+  for (entry_dir, entry) in entries {
+    if total <= max_bytes {
+      break;
+    }
+    if std::fs::remove_dir_all(&entry_dir).is_ok() {
+      total = total.saturating_sub(entry.size);
+      ENTRY_CACHE.invalidate(&entry_dir);
+    }
+  }
+}
+
+fn read_entries(dir: &Path) -> Vec<(PathBuf, CacheEntry)> {
+  let Ok(children) = std::fs::read_dir(dir) else {
+    return Vec::new();
+  };
+  children
+    .flatten()
+    .map(|child| child.path())
+    .filter(|path| {
+      !path
+        .file_name()
+        .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+    })
+    .filter_map(|path| read_meta(&path).map(|entry| (path, entry)))
+    .collect()
+}
+
+fn read_meta(entry_dir: &Path) -> Option<CacheEntry> {
+  let contents = std::fs::read_to_string(entry_dir.join(META_FILE)).ok()?;
+  serde_json::from_str(&contents).ok()
+}
+
+fn write_meta(entry_dir: &Path, entry: &CacheEntry) -> Result<(), String> {
+  let json = serde_json::to_string_pretty(entry).map_err(|e| e.to_string())?;
+  std::fs::write(entry_dir.join(META_FILE), json).map_err(|e| e.to_string())
+}
+
+fn dir_size(dir: &Path) -> u64 {
+  let Ok(children) = std::fs::read_dir(dir) else {
+    return 0;
+  };
+  children
+    .flatten()
+    .map(|child| match child.metadata() {
+      Ok(metadata) if metadata.is_dir() => dir_size(&child.path()),
+      Ok(metadata) => metadata.len(),
+      Err(_) => 0,
+    })
+    .sum()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,484 @@
+use crate::config::{self, TaskConfig};
+use crate::git;
+use crate::jobs::{self, JobStatus};
+use crate::scripts::{self, PackageManager, Script};
+use crate::task_cache::{self, CacheEntry};
+use crate::workspace::{self, Package, Workspace};
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use git2::{DiffOptions, Repository};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+pub const TASK_PROGRESS_EVENT: &str = "task-progress";
+
+const DEFAULT_PARALLELISM: usize = 2;
+
+// Cancellation flags of the runs in progress, by run id
+static RUNS: Lazy<DashMap<String, Arc<AtomicBool>>> = Lazy::new(DashMap::new);
+static RUN_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TaskSelection {
+  All,
+  // Packages changed since `since` (committed or not), plus everything depending on them
+  Affected { since: String },
+  WithDependents { package: String },
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+  Succeeded,
+  Failed,
+  // Not run: the package has no such script or a dependency did not succeed
+  Skipped,
+  Cancelled,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PackageTaskResult {
+  pub name: String,
+  pub path: String,
+  pub status: TaskStatus,
+  // Job streaming the package's output, for packages that ran
+  pub job_id: Option<String>,
+  pub duration_ms: Option<u64>,
+  pub exit_code: Option<i32>,
+  pub error: Option<String>,
+  // Task hash, when caching is on
+  pub hash: Option<String>,
+  // Replayed from the cache instead of run
+  pub cached: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TaskRunSummary {
+  pub run_id: String,
+  pub script: String,
+  // Results in the order packages finished
+  pub packages: Vec<PackageTaskResult>,
+  pub succeeded: usize,
+  pub failed: usize,
+  pub skipped: usize,
+  pub cancelled: usize,
+  pub cached: usize,
+  pub duration_ms: u64,
+}
+
+pub struct RunOptions {
+  pub parallelism: usize,
+  pub continue_on_error: bool,
+  pub cache: bool,
+}
+
+struct RunningTask<'a> {
+  package: &'a Package,
+  job_id: String,
+  started: Instant,
+  hash: Option<String>,
+  cached: bool,
+}
+
+// Cache location and input hashes of a run with caching on
+struct TaskCache {
+  dir: PathBuf,
+  inputs: HashMap<String, String>,
+  config: TaskConfig,
+}
+
+// Runs `script` in the selected packages, dependencies first. Each package runs as its own job,
+// so output streams and single packages can be cancelled; finished packages are emitted as
+// `task-progress` events. Without `continue_on_error` the first failure cancels the rest.
+// Up to `parallelism` packages run at once, independently of other background jobs, and
+// `cancel_task_run` with `run_id` (generated when not given) stops the whole run.
+// With `cache` set, packages whose inputs did not change replay their last successful run,
+// for scripts the repository lists in `tasks.cache` only.
+#[tauri::command]
+pub async fn run_task(
+  app: AppHandle,
+  path: String,
+  script: String,
+  selection: TaskSelection,
+  parallelism: Option<usize>,
+  continue_on_error: Option<bool>,
+  cache: Option<bool>,
+  run_id: Option<String>,
+) -> Result<TaskRunSummary, String> {
+  let run_id =
+    run_id.unwrap_or_else(|| format!("run-{}", RUN_COUNTER.fetch_add(1, Ordering::Relaxed)));
+  let cancelled = Arc::new(AtomicBool::new(false));
+  match RUNS.entry(run_id.clone()) {
+    Entry::Occupied(_) => return Err(format!("Task run {} is already in progress", run_id)),
+    Entry::Vacant(entry) => entry.insert(cancelled.clone()),
+  };
+
+  let id = run_id.clone();
+  let summary = tauri::async_runtime::spawn_blocking(move || {
+    let workspace = workspace::load_workspace(&path)?;
+    let selected = select_packages(&workspace, &selection)?;
+    Ok(run(
+      &app,
+      &workspace,
+      &selected,
+      &script,
+      &RunOptions {
+        parallelism: parallelism.unwrap_or(DEFAULT_PARALLELISM).max(1),
+        continue_on_error: continue_on_error.unwrap_or(false),
+        cache: cache.unwrap_or(false),
+      },
+      &id,
+      &cancelled,
+    ))
+  })
+  .await
+  .map_err(|e| e.to_string());
+  RUNS.remove(&run_id);
+
+  summary?
+}
+
+// Stops a task run: running packages are cancelled and the rest never start
+#[tauri::command]
+pub fn cancel_task_run(run_id: String) -> Result<(), String> {
+  let cancelled = RUNS
+    .get(&run_id)
+    .map(|flag| flag.clone())
+    .ok_or_else(|| format!("Unknown task run: {}", run_id))?;
+  cancelled.store(true, Ordering::SeqCst);
+  Ok(())
+}
+
+pub fn select_packages<'a>(
+  workspace: &'a Workspace,
+  selection: &TaskSelection,
+) -> Result<Vec<&'a Package>, String> {
+  let mut selected: Vec<&Package> = match selection {
+    TaskSelection::All => return Ok(workspace.packages.iter().collect()),
+    TaskSelection::WithDependents { package } => {
+      let package = workspace
+        .packages
+        .iter()
+        .find(|p| &p.name == package || &p.relative_path == package || &p.path == package)
+        .ok_or_else(|| format!("Unknown package: {}", package))?;
+      vec![package]
+    }
+    TaskSelection::Affected { since } => changed_packages(workspace, since)?,
+  };
+
+  for package in selected.clone() {
+    for dependent in workspace.transitive_dependents(&package.name) {
+      if !selected.iter().any(|p| p.name == dependent.name) {
+        selected.push(dependent);
+      }
+    }
+  }
+  Ok(selected)
+}
+
+// Packages with files that differ between `since` and the working tree
+fn changed_packages<'a>(workspace: &'a Workspace, since: &str) -> Result<Vec<&'a Package>, String> {
+  let git_root =
+    git::find_git_root(Path::new(&workspace.root)).ok_or("Could not find Git repository")?;
+  let repo = Repository::open(&git_root).map_err(|e| e.to_string())?;
+  let tree = repo
+    .revparse_single(since)
+    .and_then(|object| object.peel_to_tree())
+    .map_err(|e| format!("Unknown revision {}: {}", since, e))?;
+
+  let mut options = DiffOptions::new();
+  options.include_untracked(true).recurse_untracked_dirs(true);
+  let diff = repo
+    .diff_tree_to_workdir_with_index(Some(&tree), Some(&mut options))
+    .map_err(|e| e.to_string())?;
+
+  let mut changed: Vec<&Package> = Vec::new();
+  for delta in diff.deltas() {
+    for file in [delta.old_file().path(), delta.new_file().path()]
+      .into_iter()
+      .flatten()
+    {
+      if let Some(package) = workspace.package_containing(&Path::new(&git_root).join(file)) {
+        if !changed.iter().any(|p| p.name == package.name) {
+          changed.push(package);
+        }
+      }
+    }
+  }
+  Ok(changed)
+}
+
+pub fn run(
+  app: &AppHandle,
+  workspace: &Workspace,
+  selected: &[&Package],
+  script: &str,
+  options: &RunOptions,
+  run_id: &str,
+  cancelled: &AtomicBool,
+) -> TaskRunSummary {
+  let started = Instant::now();
+  // Without hashes the run simply goes uncached
+  let cache = options
+    .cache
+    .then(|| {
+      let config = config::load_config(&workspace.root).ok()?.tasks.clone();
+      if !config.cache.iter().any(|cached| cached == script) {
+        return None;
+      }
+      Some(TaskCache {
+        dir: task_cache::cache_dir(&workspace.root, &config).ok()?,
+        inputs: task_cache::input_hashes(workspace).ok()?,
+        config,
+      })
+    })
+    .flatten();
+  let selected_names: HashSet<&str> = selected.iter().map(|p| p.name.as_str()).collect();
+
+  let mut results: Vec<PackageTaskResult> = Vec::new();
+  // Dependents may start once a package is done; a broken one blocks them
+  let mut done: HashSet<String> = HashSet::new();
+  let mut broken: HashSet<String> = HashSet::new();
+  let mut pending: Vec<&Package> = selected.to_vec();
+  pending.sort_by(|a, b| a.name.cmp(&b.name));
+  let mut running: Vec<RunningTask> = Vec::new();
+  let mut stopping = false;
+  let mut cancelling = false;
+
+  let mut finish =
+    |result: PackageTaskResult, done: &mut HashSet<String>, broken: &mut HashSet<String>| {
+      match result.status {
+        TaskStatus::Succeeded => done.insert(result.name.clone()),
+        TaskStatus::Skipped if result.error.is_none() => done.insert(result.name.clone()),
+        _ => broken.insert(result.name.clone()),
+      };
+      let _ = app.emit(TASK_PROGRESS_EVENT, result.clone());
+      results.push(result);
+    };
+
+  loop {
+    if cancelled.load(Ordering::SeqCst) {
+      stopping = true;
+    }
+
+    // Collect finished packages
+    let mut index = 0;
+    while index < running.len() {
+      let task = &running[index];
+      let info = jobs::get_job(task.job_id.clone()).ok();
+      let status = info.as_ref().map_or(JobStatus::Failed, |info| info.status);
+      if !status.is_finished() {
+        index += 1;
+        continue;
+      }
+      let task = running.remove(index);
+      let status = match status {
+        JobStatus::Succeeded => TaskStatus::Succeeded,
+        JobStatus::Cancelled => TaskStatus::Cancelled,
+        _ => TaskStatus::Failed,
+      };
+      if status == TaskStatus::Failed && !options.continue_on_error {
+        stopping = true;
+      }
+      finish(
+        PackageTaskResult {
+          name: task.package.name.clone(),
+          path: task.package.path.clone(),
+          status,
+          job_id: Some(task.job_id),
+          duration_ms: Some(task.started.elapsed().as_millis() as u64),
+          exit_code: info.as_ref().and_then(|info| info.exit_code),
+          error: info.and_then(|info| info.error),
+          hash: task.hash,
+          cached: task.cached,
+        },
+        &mut done,
+        &mut broken,
+      );
+    }
+
+    if stopping && !cancelling {
+      for task in &running {
+        let _ = jobs::cancel_job(app.clone(), task.job_id.clone(), None);
+      }
+      cancelling = true;
+    }
+
+    // Start packages whose dependencies are done
+    let mut index = 0;
+    while !stopping && index < pending.len() && running.len() < options.parallelism {
+      let package = pending[index];
+      let dependencies: Vec<&Package> = workspace
+        .internal_dependencies(package)
+        .into_iter()
+        .filter(|dependency| selected_names.contains(dependency.name.as_str()))
+        .collect();
+
+      if let Some(blocker) = dependencies.iter().find(|d| broken.contains(&d.name)) {
+        pending.remove(index);
+        finish(
+          skipped(package, Some(format!("{} did not succeed", blocker.name))),
+          &mut done,
+          &mut broken,
+        );
+        continue;
+      }
+      if !dependencies.iter().all(|d| done.contains(&d.name)) {
+        index += 1;
+        continue;
+      }
+
+      pending.remove(index);
+      let Some((found, package_manager)) = scripts::find_script(&package.path, script, None)
+        .ok()
+        .flatten()
+      else {
+        finish(skipped(package, None), &mut done, &mut broken);
+        continue;
+      };
+      let hash = cache.as_ref().and_then(|cache| {
+        let inputs = cache.inputs.get(&package.name)?;
+        Some(task_cache::task_hash(
+          inputs,
+          &found,
+          package_manager,
+          &cache.config,
+        ))
+      });
+      let hit = cache
+        .as_ref()
+        .zip(hash.as_deref())
+        .and_then(|(cache, hash)| task_cache::lookup(&cache.dir, hash));
+      running.push(RunningTask {
+        package,
+        job_id: start_package(
+          app,
+          package,
+          &found,
+          package_manager,
+          cache.as_ref().zip(hash.clone()),
+          hit.clone(),
+        ),
+        started: Instant::now(),
+        hash,
+        cached: hit.is_some(),
+      });
+    }
+
+    if running.is_empty() {
+      if stopping {
+        break;
+      }
+      // Nothing running and nothing startable: the rest wait on each other
+      for package in pending.drain(..) {
+        finish(
+          PackageTaskResult {
+            status: TaskStatus::Failed,
+            error: Some("Dependency cycle".to_string()),
+            ..skipped(package, None)
+          },
+          &mut done,
+          &mut broken,
+        );
+      }
+      break;
+    }
+    std::thread::sleep(Duration::from_millis(100));
+  }
+
+  for package in pending {
+    finish(
+      PackageTaskResult {
+        status: TaskStatus::Cancelled,
+        ..skipped(package, None)
+      },
+      &mut done,
+      &mut broken,
+    );
+  }
+
+  let count = |status: TaskStatus| results.iter().filter(|r| r.status == status).count();
+  TaskRunSummary {
+    run_id: run_id.to_string(),
+    script: script.to_string(),
+    succeeded: count(TaskStatus::Succeeded),
+    failed: count(TaskStatus::Failed),
+    skipped: count(TaskStatus::Skipped),
+    cancelled: count(TaskStatus::Cancelled),
+    cached: results.iter().filter(|r| r.cached).count(),
+    duration_ms: started.elapsed().as_millis() as u64,
+    packages: results,
+  }
+}
+
+// Starts the package's job, which replays `hit` or runs the script and caches its result
+fn start_package(
+  app: &AppHandle,
+  package: &Package,
+  script: &Script,
+  package_manager: Option<PackageManager>,
+  store: Option<(&TaskCache, String)>,
+  hit: Option<Arc<CacheEntry>>,
+) -> String {
+  let dir = PathBuf::from(&package.path);
+  let command = scripts::build_command(&dir, script, package_manager, &[]);
+  let label = format!("{}: {}", package.name, script.name);
+  let package = package.clone();
+  let script = script.name.clone();
+  let store = store.map(|(cache, hash)| (cache.dir.clone(), cache.config.clone(), hash));
+
+  // Concurrency is `run`'s business, so tasks do not queue behind unrelated jobs
+  jobs::spawn_unqueued(app, "task", &label, move |app, job_id| {
+    if let (Some((cache_dir, config, _)), Some(entry)) = (&store, &hit) {
+      task_cache::replay(app, job_id, cache_dir, config, entry, &dir)?;
+      return Ok(Some("Replayed from cache".to_string()));
+    }
+
+    let started = Instant::now();
+    let output = jobs::run_command(app, job_id, command)?;
+    if !output.status.success() {
+      return Err(scripts::exit_error(&output.status));
+    }
+    if let Some((cache_dir, config, hash)) = &store {
+      let stored = task_cache::store(
+        cache_dir,
+        hash,
+        &package,
+        &script,
+        config,
+        &output,
+        started.elapsed(),
+      );
+      if let Err(error) = stored {
+        jobs::emit_log(
+          app,
+          job_id,
+          "stderr",
+          &format!("Result not cached: {}", error),
+        );
+      }
+    }
+    Ok(None)
+  })
+}
+
+fn skipped(package: &Package, error: Option<String>) -> PackageTaskResult {
+  PackageTaskResult {
+    name: package.name.clone(),
+    path: package.path.clone(),
+    status: TaskStatus::Skipped,
+    job_id: None,
+    duration_ms: None,
+    exit_code: None,
+    error,
+    hash: None,
+    cached: false,
+  }
+}
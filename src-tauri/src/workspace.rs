@@ -0,0 +1,653 @@
+use crate::git;
+use moka::sync::Cache;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestKind {
+  Npm,
+  Cargo,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyKind {
+  Normal,
+  Dev,
+  Peer,
+  Optional,
+  Build,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Dependency {
+  pub name: String,
+  // Key in the manifest section; differs from `name` for renamed Cargo dependencies
+  pub key: String,
+  pub range: String,
+  pub kind: DependencyKind,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Package {
+  pub name: String,
+  pub version: Option<String>,
+  pub path: String,
+  pub relative_path: String,
+  pub manifest: ManifestKind,
+  pub private: bool,
+  pub dependencies: Vec<Dependency>,
+}
+
+impl Package {
+  pub fn manifest_path(&self) -> PathBuf {
+    match self.manifest {
+      ManifestKind::Npm => Path::new(&self.path).join("package.json"),
+      ManifestKind::Cargo => Path::new(&self.path).join("Cargo.toml"),
+    }
+  }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Workspace {
+  pub root: String,
+  pub packages: Vec<Package>,
+}
+
+impl Workspace {
+  pub fn find(&self, name: &str) -> Option<&Package> {
+    self.packages.iter().find(|p| p.name == name)
+  }
+
+  pub fn dependents(&self, name: &str) -> Vec<&Package> {
+    self
+      .packages
+      .iter()
+      .filter(|p| p.name != name && p.dependencies.iter().any(|d| d.name == name))
+      .collect()
+  }
+
+  // Workspace packages the package depends on, of any dependency kind
+  pub fn internal_dependencies(&self, package: &Package) -> Vec<&Package> {
+    self
+      .packages
+      .iter()
+      .filter(|p| p.name != package.name && package.dependencies.iter().any(|d| d.name == p.name))
+      .collect()
+  }
+
+  // Direct and indirect dependents, nearest first
+  pub fn transitive_dependents(&self, name: &str) -> Vec<&Package> {
+    let mut found: Vec<&Package> = Vec::new();
+    let mut queue = vec![name.to_string()];
+    while let Some(current) = queue.pop() {
+      for dependent in self.dependents(&current) {
+        if dependent.name != name && !found.iter().any(|p| p.name == dependent.name) {
+          queue.insert(0, dependent.name.clone());
+          found.push(dependent);
+        }
+      }
+    }
+    found
+  }
+
+  // Innermost package containing a path relative to the workspace root
+  pub fn package_for_path(&self, relative: &str) -> Option<&Package> {
+    self
+      .packages
+      .iter()
+      .filter(|p| {
+        p.relative_path.is_empty()
+          || relative == p.relative_path
+          || relative
+            .strip_prefix(p.relative_path.as_str())
+            .is_some_and(|rest| rest.starts_with('/'))
+      })
+      .max_by_key(|p| p.relative_path.len())
+  }
+
+  // Innermost package containing an absolute path
+  pub fn package_containing(&self, path: &Path) -> Option<&Package> {
+    self
+      .packages
+      .iter()
+      .filter(|p| path.starts_with(&p.path))
+      .max_by_key(|p| p.path.len())
+  }
+}
+
+static WORKSPACE_CACHE: Lazy<Cache<String, Arc<Workspace>>> = Lazy::new(|| {
+  Cache::builder()
+    .time_to_live(Duration::from_secs(60))
+    .initial_capacity(10)
+    .build()
+});
+
+pub fn clear_cache() {
+  WORKSPACE_CACHE.invalidate_all();
+}
+
+#[tauri::command]
+pub fn list_packages(path: String) -> Result<Vec<Package>, String> {
+  Ok(load_workspace(&path)?.packages.clone())
+}
+
+pub fn load_workspace(path: &str) -> Result<Arc<Workspace>, String> {
+  let root = find_workspace_root(Path::new(path))?;
+  if let Some(cached) = WORKSPACE_CACHE.get(&root) {
+    return Ok(cached);
+  }
+
+  let workspace = Arc::new(discover(&root)?);
+  WORKSPACE_CACHE.insert(root, workspace.clone());
+  Ok(workspace)
+}
+
+// Nearest ancestor declaring workspaces, bounded by the git root
+fn find_workspace_root(start: &Path) -> Result<String, String> {
+  let git_root = git::find_git_root(start).ok_or("Could not find Git repository")?;
+
+  let mut current = start.to_path_buf();
+  loop {
+    if !workspace_patterns(&current).is_empty() {
+      return Ok(current.to_string_lossy().into_owned());
+    }
+    if current == Path::new(&git_root) || !current.pop() {
+      return Ok(git_root);
+    }
+  }
+}
+
+fn workspace_patterns(root: &Path) -> Vec<(String, ManifestKind)> {
+  let mut patterns = Vec::new();
+
+  if let Some(json) = read_json(&root.join("package.json")) {
+    let workspaces = json
+      .get("workspaces")
+      .map(|w| w.get("packages").unwrap_or(w))
+      .and_then(|w| w.as_array());
+    for pattern in workspaces.into_iter().flatten().filter_map(|p| p.as_str()) {
+      patterns.push((pattern.to_string(), ManifestKind::Npm));
+    }
+  }
+
+  if let Ok(contents) = std::fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+    if let Ok(yaml) = serde_yaml::from_str::<serde_yaml::Value>(&contents) {
+      let packages = yaml.get("packages").and_then(|p| p.as_sequence());
+      for pattern in packages.into_iter().flatten().filter_map(|p| p.as_str()) {
+        patterns.push((pattern.to_string(), ManifestKind::Npm));
+      }
+    }
+  }
+
+  if let Some(doc) = read_toml(&root.join("Cargo.toml")) {
+    let workspace = doc.get("workspace");
+    for key in ["members", "exclude"] {
+      let entries = workspace
+        .and_then(|w| w.get(key))
+        .and_then(|m| m.as_array());
+      for pattern in entries.into_iter().flatten().filter_map(|p| p.as_str()) {
+        let pattern = if key == "exclude" {
+          format!("!{}", pattern)
+        } else {
+          pattern.to_string()
+        };
+        patterns.push((pattern, ManifestKind::Cargo));
+      }
+    }
+  }
+
+  patterns
+}
+
+fn discover(root: &str) -> Result<Workspace, String> {
+  let root_path = Path::new(root);
+  let mut patterns = workspace_patterns(root_path);
+
+  if patterns.is_empty() {
+    // Layout used by the app's folder views: packages/<view>/<package>
+    for pattern in ["packages/*", "packages/*/*", "apps/*", "libs/*"] {
+      patterns.push((pattern.to_string(), ManifestKind::Npm));
+      patterns.push((pattern.to_string(), ManifestKind::Cargo));
+    }
+  }
+
+  let excluded: Vec<glob::Pattern> = patterns
+    .iter()
+    .filter_map(|(p, _)| p.strip_prefix('!'))
+    .filter_map(|p| glob::Pattern::new(p.trim_end_matches('/')).ok())
+    .collect();
+
+  let mut packages = Vec::new();
+  let mut seen = HashSet::new();
+
+  // A Cargo workspace root may itself be a package
+  if root_path.join("Cargo.toml").exists() {
+    if let Some(package) = read_package(root_path, root_path, ManifestKind::Cargo) {
+      seen.insert((PathBuf::from(root), ManifestKind::Cargo));
+      packages.push(package);
+    }
+  }
+
+  for (pattern, kind) in patterns.iter().filter(|(p, _)| !p.starts_with('!')) {
+    let full_pattern = root_path.join(pattern.trim_end_matches('/'));
+    let entries = match glob::glob(&full_pattern.to_string_lossy()) {
+      Ok(entries) => entries,
+      Err(_) => continue,
+    };
+
+    for dir in entries.filter_map(Result::ok).filter(|p| p.is_dir()) {
+      let relative = dir.strip_prefix(root_path).unwrap_or(&dir);
+      if relative
+        .components()
+        .any(|c| c.as_os_str() == "node_modules")
+        || excluded.iter().any(|p| p.matches_path(relative))
+      {
+        continue;
+      }
+      if !seen.insert((dir.clone(), *kind)) {
+        continue;
+      }
+      if let Some(package) = read_package(root_path, &dir, *kind) {
+        packages.push(package);
+      }
+    }
+  }
+
+  packages.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+  Ok(Workspace {
+    root: root.to_string(),
+    packages,
+  })
+}
+
+fn read_package(root: &Path, dir: &Path, kind: ManifestKind) -> Option<Package> {
+  let relative_path = dir
+    .strip_prefix(root)
+    .ok()?
+    .to_string_lossy()
+    .replace('\\', "/");
+
+  match kind {
+    ManifestKind::Npm => {
+      let json = read_json(&dir.join("package.json"))?;
+      let name = json.get("name")?.as_str()?.to_string();
+      let mut dependencies = Vec::new();
+      for (section, kind) in NPM_SECTIONS {
+        let entries = json.get(*section).and_then(|d| d.as_object());
+        for (dep, range) in entries.into_iter().flatten() {
+          dependencies.push(Dependency {
+            name: dep.clone(),
+            key: dep.clone(),
+            range: range.as_str().unwrap_or_default().to_string(),
+            kind: *kind,
+          });
+        }
+      }
+
+      Some(Package {
+        name,
+        version: json
+          .get("version")
+          .and_then(|v| v.as_str())
+          .map(String::from),
+        path: dir.to_string_lossy().into_owned(),
+        relative_path,
+        manifest: ManifestKind::Npm,
+        private: json
+          .get("private")
+          .and_then(|p| p.as_bool())
+          .unwrap_or(false),
+        dependencies,
+      })
+    }
+    ManifestKind::Cargo => {
+      let doc = read_toml(&dir.join("Cargo.toml"))?;
+      let package = doc.get("package")?;
+      let name = package.get("name")?.as_str()?.to_string();
+
+      let version = match package.get("version") {
+        Some(v) if v.is_str() => v.as_str().map(String::from),
+        // `version.workspace = true` inherits from the root manifest
+        Some(_) => read_toml(&root.join("Cargo.toml")).and_then(|root_doc| {
+          root_doc
+            .get("workspace")?
+            .get("package")?
+            .get("version")?
+            .as_str()
+            .map(String::from)
+        }),
+        None => None,
+      };
+
+      let mut dependencies = Vec::new();
+      for (section, kind) in CARGO_SECTIONS {
+        let entries = doc.get(section).and_then(|d| d.as_table_like());
+        for (dep, spec) in entries.into_iter().flat_map(|t| t.iter()) {
+          let range = match spec.as_str() {
+            Some(range) => range.to_string(),
+            None => spec
+              .get("version")
+              .and_then(|v| v.as_str())
+              .map(String::from)
+              .or_else(|| spec.get("path").map(|_| "path".to_string()))
+              .or_else(|| spec.get("workspace").map(|_| "workspace".to_string()))
+              .unwrap_or_default(),
+          };
+          // Renamed dependencies point at the real crate via `package`
+          let name = spec
+            .get("package")
+            .and_then(|p| p.as_str())
+            .unwrap_or(dep)
+            .to_string();
+          dependencies.push(Dependency {
+            name,
+            key: dep.to_string(),
+            range,
+            kind: *kind,
+          });
+        }
+      }
+
+      Some(Package {
+        name,
+        version,
+        path: dir.to_string_lossy().into_owned(),
+        relative_path,
+        manifest: ManifestKind::Cargo,
+        private: package
+          .get("publish")
+          .and_then(|p| p.as_bool())
+          .is_some_and(|publish| !publish),
+        dependencies,
+      })
+    }
+  }
+}
+
+const NPM_SECTIONS: &[(&str, DependencyKind)] = &[
+  ("dependencies", DependencyKind::Normal),
+  ("devDependencies", DependencyKind::Dev),
+  ("peerDependencies", DependencyKind::Peer),
+  ("optionalDependencies", DependencyKind::Optional),
+];
+
+const CARGO_SECTIONS: &[(&str, DependencyKind)] = &[
+  ("dependencies", DependencyKind::Normal),
+  ("dev-dependencies", DependencyKind::Dev),
+  ("build-dependencies", DependencyKind::Build),
+];
+
+fn section_name(manifest: ManifestKind, kind: DependencyKind) -> Option<&'static str> {
+  let sections = match manifest {
+    ManifestKind::Npm => NPM_SECTIONS,
+    ManifestKind::Cargo => CARGO_SECTIONS,
+  };
+  sections.iter().find(|(_, k)| *k == kind).map(|(s, _)| *s)
+}
+
+pub fn read_json(path: &Path) -> Option<serde_json::Value> {
+  let contents = std::fs::read_to_string(path).ok()?;
+  serde_json::from_str(&contents).ok()
+}
+
+pub fn read_toml(path: &Path) -> Option<toml_edit::DocumentMut> {
+  let contents = std::fs::read_to_string(path).ok()?;
+  contents.parse().ok()
+}
+
+// Rewrites the package version in its manifest, keeping the rest of the file untouched
+pub fn set_package_version(package: &Package, version: &str) -> Result<(), String> {
+  let path = package.manifest_path();
+  let contents = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+
+  let updated = match package.manifest {
+    ManifestKind::Npm => set_json_string(&contents, &["version"], version),
+    ManifestKind::Cargo => set_toml_string(&contents, &["package", "version"], version),
+  };
+
+  match updated {
+    Some(updated) => std::fs::write(&path, updated).map_err(|e| e.to_string()),
+    // `version.workspace = true`: the version lives in the workspace root manifest
+    None if package.manifest == ManifestKind::Cargo => {
+      for dir in Path::new(&package.path).ancestors().skip(1) {
+        let root_manifest = dir.join("Cargo.toml");
+        let Ok(contents) = std::fs::read_to_string(&root_manifest) else {
+          continue;
+        };
+        if let Some(updated) =
+          set_toml_string(&contents, &["workspace", "package", "version"], version)
+        {
+          return std::fs::write(&root_manifest, updated).map_err(|e| e.to_string());
+        }
+      }
+      Err(format!("No version field in {}", path.display()))
+    }
+    None => Err(format!("No version field in {}", path.display())),
+  }
+}
+
+pub fn set_dependency_range(
+  package: &Package,
+  dependency: &Dependency,
+  range: &str,
+) -> Result<(), String> {
+  let path = package.manifest_path();
+  let contents = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let section = section_name(package.manifest, dependency.kind)
+    .ok_or_else(|| format!("Unsupported dependency kind for {}", path.display()))?;
+
+  let updated = match package.manifest {
+    ManifestKind::Npm => set_json_string(&contents, &[section, &dependency.key], range),
+    ManifestKind::Cargo => set_toml_string(&contents, &[section, &dependency.key], range)
+      .or_else(|| set_toml_string(&contents, &[section, &dependency.key, "version"], range)),
+  }
+  .ok_or_else(|| format!("{} not found in {}", dependency.key, path.display()))?;
+
+  std::fs::write(&path, updated).map_err(|e| e.to_string())
+}
+
+fn set_toml_string(contents: &str, path: &[&str], value: &str) -> Option<String> {
+  let mut doc: toml_edit::DocumentMut = contents.parse().ok()?;
+  let (last, parents) = path.split_last()?;
+
+  let mut table = doc.as_table_mut() as &mut dyn toml_edit::TableLike;
+  for key in parents {
+    table = table.get_mut(key)?.as_table_like_mut()?;
+  }
+
+  let current = table.get_mut(last)?.as_value_mut()?;
+  current.as_str()?;
+  let decor = current.decor().clone();
+  *current = value.into();
+  *current.decor_mut() = decor;
+
+  // toml_edit writes `\n` after every line, so CRLF manifests would come back mixed
+  let updated = doc.to_string();
+  if contents.contains("\r\n") {
+    Some(updated.replace("\r\n", "\n").replace('\n', "\r\n"))
+  } else {
+    Some(updated)
+  }
+}
+
+// Replaces a string value at `path` in a JSON document without reformatting it
+pub fn set_json_string(contents: &str, path: &[&str], value: &str) -> Option<String> {
+  let (start, end) = json_value_span(contents, path)?;
+  if !contents[start..end].starts_with('"') {
+    return None;
+  }
+
+  let encoded = serde_json::to_string(value).ok()?;
+  Some(format!(
+    "{}{}{}",
+    &contents[..start],
+    encoded,
+    &contents[end..]
+  ))
+}
+
+fn json_value_span(contents: &str, path: &[&str]) -> Option<(usize, usize)> {
+  let bytes = contents.as_bytes();
+  let mut pos = skip_ws(bytes, 0);
+
+  for key in path {
+    if bytes.get(pos) != Some(&b'{') {
+      return None;
+    }
+    pos += 1;
+    loop {
+      pos = skip_ws(bytes, pos);
+      if bytes.get(pos) != Some(&b'"') {
+        return None;
+      }
+      let key_end = skip_json_value(bytes, pos)?;
+      let found: String = serde_json::from_slice(&bytes[pos..key_end]).ok()?;
+      pos = skip_ws(bytes, key_end);
+      if bytes.get(pos) != Some(&b':') {
+        return None;
+      }
+      pos = skip_ws(bytes, pos + 1);
+      if found == *key {
+        break;
+      }
+      pos = skip_ws(bytes, skip_json_value(bytes, pos)?);
+      if bytes.get(pos) != Some(&b',') {
+        return None;
+      }
+      pos += 1;
+    }
+  }
+
+  Some((pos, skip_json_value(bytes, pos)?))
+}
+
+fn skip_ws(bytes: &[u8], mut pos: usize) -> usize {
+  while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+    pos += 1;
+  }
+  pos
+}
+
+fn skip_json_value(bytes: &[u8], start: usize) -> Option<usize> {
+  let mut pos = start;
+  let mut depth = 0usize;
+  let mut in_string = false;
+
+  while pos < bytes.len() {
+    let byte = bytes[pos];
+    if in_string {
+      match byte {
+        b'\\' => pos += 1,
+        b'"' => {
+          in_string = false;
+          if depth == 0 {
+            return Some(pos + 1);
+          }
+        }
+        _ => {}
+      }
+    } else {
+      match byte {
+        b'"' => in_string = true,
+        b'{' | b'[' => depth += 1,
+        b'}' | b']' if depth > 0 => {
+          depth -= 1;
+          if depth == 0 {
+            return Some(pos + 1);
+          }
+        }
+        b',' | b'}' | b']' if depth == 0 => return Some(pos),
+        b if depth == 0 && b.is_ascii_whitespace() => return Some(pos),
+        _ => {}
+      }
+    }
+    pos += 1;
+  }
+
+  if depth == 0 && !in_string && pos > start {
+    Some(pos)
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sets_nested_json_strings_in_place() {
+    let contents = "{\r\n\t\"name\": \"app\",\r\n\t\"scripts\": { \"react\": \"echo\" },\r\n\t\"dependencies\": {\r\n\t\t\"react\": \"^17.0.0\",\r\n\t\t\"left-pad\": \"1.0.0\"\r\n\t}\r\n}\r\n";
+    assert_eq!(
+      set_json_string(contents, &["dependencies", "react"], "^18.2.0").unwrap(),
+      contents.replace("^17.0.0", "^18.2.0")
+    );
+    assert_eq!(
+      set_json_string(contents, &["name"], "say \"hi\"").unwrap(),
+      contents.replace("\"app\"", "\"say \\\"hi\\\"\"")
+    );
+  }
+
+  #[test]
+  fn skips_json_values_containing_delimiters() {
+    let contents = r#"{"description": "a \"quoted\", {braced} [text]", "files": ["a", "b"], "version": "1.0.0"}"#;
+    assert_eq!(
+      set_json_string(contents, &["version"], "1.1.0").unwrap(),
+      contents.replace("1.0.0", "1.1.0")
+    );
+  }
+
+  #[test]
+  fn only_replaces_existing_json_strings() {
+    let contents = r#"{"version": 1, "files": ["a"], "dependencies": {}}"#;
+    assert_eq!(set_json_string(contents, &["version"], "2"), None);
+    assert_eq!(set_json_string(contents, &["files"], "b"), None);
+    assert_eq!(
+      set_json_string(contents, &["dependencies", "react"], "^18"),
+      None
+    );
+    assert_eq!(set_json_string(contents, &["missing"], "x"), None);
+  }
+
+  #[test]
+  fn sets_toml_strings_keeping_comments_and_indentation() {
+    let contents = "[package]\nname = \"app\"\n\n[dependencies]\n  serde = \"1.0\"   # pinned\n  foo = { package = \"bar\", version = \"0.2\", features = [\"x\"] }\n";
+    assert_eq!(
+      set_toml_string(contents, &["dependencies", "serde"], "1.0.200").unwrap(),
+      contents.replace("\"1.0\"", "\"1.0.200\"")
+    );
+    assert_eq!(
+      set_toml_string(contents, &["dependencies", "foo", "version"], "0.3").unwrap(),
+      contents.replace("\"0.2\"", "\"0.3\"")
+    );
+    // Tables and non-string values are left alone
+    assert_eq!(
+      set_toml_string(contents, &["dependencies", "foo"], "0.3"),
+      None
+    );
+    assert_eq!(
+      set_toml_string(contents, &["dependencies", "foo", "features"], "y"),
+      None
+    );
+    assert_eq!(
+      set_toml_string(contents, &["dependencies", "missing"], "1"),
+      None
+    );
+  }
+
+  #[test]
+  fn keeps_crlf_line_endings_in_toml() {
+    let contents = "[package]\r\nname = \"app\"\r\nversion = \"0.1.0\"\r\n\r\n# Runtime dependencies\r\n[dependencies]\r\nserde = \"1.0\"\r\n";
+    assert_eq!(
+      set_toml_string(contents, &["package", "version"], "0.2.0").unwrap(),
+      contents.replace("0.1.0", "0.2.0")
+    );
+  }
+}
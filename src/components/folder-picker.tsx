@@ -1,21 +1,30 @@
 // FolderPicker.jsx
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
 import { FolderPlus } from "lucide-react";
 import useStore from '../store';
-import { open } from "@tauri-apps/plugin-dialog";
+import { invoke } from '@tauri-apps/api/core';
 import { Button } from './ui/button';
 
 const FolderPicker = () => {
-  const { monoRepoPath, setMonoRepoPath } = useStore();
+  const { monoRepoPath, setMonoRepoPath, resetMonoRepoPath } = useStore();
   const [error, setError] = useState('');
 
+  // The backend decides which repository the file commands are open to
+  useEffect(() => {
+    invoke<string | null>('get_repository')
+      .then((repository) => {
+        if (!repository) {
+          resetMonoRepoPath();
+        } else if (repository !== monoRepoPath) {
+          setMonoRepoPath(repository);
+        }
+      })
+      .catch((err) => console.error(err));
+  }, []);
 
   const handleFolderSelect = async () => {
     try {
-      const selected = await open({
-        directory: true,
-        multiple: false,
-      });
+      const selected = await invoke<string | null>('choose_repository');
 
       if (selected) {
         setMonoRepoPath(selected);
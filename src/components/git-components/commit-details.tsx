@@ -4,6 +4,7 @@ import { cn } from "@/lib/utils";
 import { Loader2, GitCommit as GitCommitIcon, FileIcon, Github, Copy, ChevronRight } from "lucide-react";
 import { getCommitUrl } from '@/utils/git';
 import DiffViewer from '@/components/git-components/diff-viewer';
+import SemanticDiff from '@/components/git-components/semantic-diff';
 import { useEffect, useState } from "react";
 import { DetailedCommit } from "@/types";
 import { Tooltip, TooltipContent } from "../ui/tooltip";
@@ -91,6 +92,8 @@ const CommitDetails: React.FC<CommitDetailsProps> = ({ commit, repoPath, onNextC
     }
   };
 
+  const selectedChange = commit.changes.find((change) => change.file === selectedFile);
+
   useEffect(() => {
     if (commit.changes.length > 0) {
       handleFileClick(commit.changes[0].file);
@@ -121,16 +124,33 @@ const CommitDetails: React.FC<CommitDetailsProps> = ({ commit, repoPath, onNextC
           <span>{commit.author}</span>
           <span>•</span>
           <span>{new Date(commit.date * 1000).toLocaleDateString()}</span>
-          <a
-            className="flex items-center gap-1 text-sm text-muted-foreground ml-auto"
-            href={getCommitUrl(commit.remote_url, commit.id)}
-            target="_blank"
-            rel="noopener noreferrer"
-          >
-            <Github className="w-4 h-4" />
-            <span>Open in GitHub</span>
-          </a>
+          {(commit.web_url || commit.remote_url) && (
+            <a
+              className="flex items-center gap-1 text-sm text-muted-foreground ml-auto"
+              href={commit.web_url || getCommitUrl(commit.remote_url || '', commit.id)}
+              target="_blank"
+              rel="noopener noreferrer"
+            >
+              <Github className="w-4 h-4" />
+              <span>Open on remote</span>
+            </a>
+          )}
         </div>
+        {commit.issues.length > 0 && (
+          <div className="flex flex-wrap items-center gap-2">
+            {commit.issues.map((issue) => (
+              <Badge key={issue.key} variant={issue.closes ? "default" : "secondary"}>
+                {issue.url ? (
+                  <a href={issue.url} target="_blank" rel="noopener noreferrer">
+                    {issue.key}
+                  </a>
+                ) : (
+                  issue.key
+                )}
+              </Badge>
+            ))}
+          </div>
+        )}
       </div>
 
       <div className="grid grid-cols-5 gap-4 h-[calc(100%-5rem)]">
@@ -191,11 +211,14 @@ const CommitDetails: React.FC<CommitDetailsProps> = ({ commit, repoPath, onNextC
               <Loader2 className="w-6 h-6 animate-spin" />
             </div>
           ) : diffContent && selectedFile ? (
-            <DiffViewer
-              oldContent={diffContent.old}
-              newContent={diffContent.new}
-              language={getFileLanguage(selectedFile)}
-            />
+            <>
+              {selectedChange?.semantic && <SemanticDiff diff={selectedChange.semantic} />}
+              <DiffViewer
+                oldContent={diffContent.old}
+                newContent={diffContent.new}
+                language={getFileLanguage(selectedFile)}
+              />
+            </>
           ) : (
             <div className="flex justify-center items-center h-[800px] text-muted-foreground">
               Select a file to view changes
@@ -119,6 +119,7 @@ const CommitMonitor: React.FC = () => {
           const details = await invoke('get_new_commits_details', {
             repoPath: folderPath,
             commitId: commit.id,
+            remote: 'upstream',
           }) as { changes: any[]; files: { path: string }[] };
 
           if (enableAllFolderNotifications || 
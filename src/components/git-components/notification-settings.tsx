@@ -1,6 +1,7 @@
 // components/notification-settings.tsx
 import { useState, useMemo } from 'react';
-import { Bell, Settings2, FolderGit2, Search, Volume2, VolumeX } from 'lucide-react';
+import { Bell, Settings2, FolderGit2, Search, Volume2, VolumeX, ShieldAlert } from 'lucide-react';
+import { open } from '@tauri-apps/plugin-dialog';
 import { Dialog, DialogContent, DialogHeader, DialogTitle, DialogTrigger } from "@/components/ui/dialog";
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
@@ -20,6 +21,7 @@ import {
 import type { NotificationSettings } from '@/store';
 import useStore from "@/store";
 import FolderToggle from '../folder-toggle';
+import { useAdvisoryImport } from '@/hooks/useAdvisoryImport';
 
 interface NotificationSettingsProps {
   folders?: { name: string }[];
@@ -35,6 +37,9 @@ const NotificationSettings = ({
   const [error, setError] = useState('');
   const { updateNotificationSettings, notificationSettings } = useStore();
   const { monitoredFolders, checkInterval, isEnabled, soundEnabled, selectedSound, enableAllFolderNotifications } = notificationSettings;
+  const { importAdvisories } = useAdvisoryImport();
+  const [importStatus, setImportStatus] = useState('');
+  const [isImporting, setIsImporting] = useState(false);
 
   // Available notification sounds
   const availableSounds = [
@@ -138,6 +143,30 @@ const NotificationSettings = ({
     });
   };
 
+  // OSV exports come as a zip (such as `all.zip`) or a folder of JSON records
+  const handleImportAdvisories = async (directory: boolean) => {
+    const source = await open({
+      directory,
+      multiple: false,
+      filters: directory ? undefined : [{ name: 'OSV export', extensions: ['zip'] }],
+    });
+    if (!source) return;
+
+    setIsImporting(true);
+    setImportStatus('');
+    try {
+      const summary = await importAdvisories(source);
+      setImportStatus(
+        `Imported ${summary.imported} advisories (${summary.new.length} new), ` +
+        `${summary.findings.length} affecting monitored folders`
+      );
+    } catch (err) {
+      setImportStatus(`Failed to import advisories: ${err}`);
+    } finally {
+      setIsImporting(false);
+    }
+  };
+
   const toggleSound = () => {
     updateNotificationSettings({
       ...notificationSettings,
@@ -287,6 +316,28 @@ const NotificationSettings = ({
               )}
             </CardContent>
           </Card>
+          <Card>
+            <CardHeader>
+              <CardTitle className="text-lg flex items-center gap-2">
+                <ShieldAlert className="h-5 w-5" />
+                Security Advisories
+              </CardTitle>
+              <CardDescription>Import an OSV export; new advisories affecting monitored folders are notified</CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="flex items-center gap-2">
+                <Button variant="outline" disabled={isImporting} onClick={() => handleImportAdvisories(false)}>
+                  Import zip
+                </Button>
+                <Button variant="outline" disabled={isImporting} onClick={() => handleImportAdvisories(true)}>
+                  Import folder
+                </Button>
+              </div>
+              {importStatus && (
+                <p className="text-sm text-muted-foreground">{importStatus}</p>
+              )}
+            </CardContent>
+          </Card>
           <Card>
             <CardHeader>
               <div className='flex justify-between w-full'>
@@ -0,0 +1,134 @@
+import React, { useState } from 'react';
+import { ChevronDown, ChevronRight, GitCommitIcon, GitMerge, GitPullRequest } from "lucide-react";
+import { Badge } from "@/components/ui/badge";
+import { BasicCommit, PullRequest } from "@/types";
+
+const formatDate = (timestamp: number) =>
+  new Date(timestamp * 1000).toLocaleString('en-US', {
+    month: 'numeric',
+    day: 'numeric',
+    year: 'numeric',
+    hour: '2-digit',
+    minute: '2-digit',
+    hour12: false
+  });
+
+const PullRequestRow = ({
+  pullRequest,
+  onCommitClick,
+}: {
+  pullRequest: PullRequest;
+  onCommitClick: (commit: BasicCommit) => void;
+}) => {
+  const [expanded, setExpanded] = useState(false);
+  const Icon = pullRequest.kind === 'merge' ? GitMerge : GitPullRequest;
+
+  return (
+    <div className="p-4 bg-muted rounded-lg">
+      <div
+        className="flex items-start gap-3 cursor-pointer"
+        onClick={() => setExpanded(prev => !prev)}
+      >
+        <Icon className="w-5 h-5 mt-1 text-muted-foreground" />
+        <div className="min-w-0 flex-1">
+          <div className="font-medium break-words">
+            {pullRequest.title}{' '}
+            {pullRequest.web_url ? (
+              <a
+                href={pullRequest.web_url}
+                target="_blank"
+                rel="noopener noreferrer"
+                className="text-muted-foreground hover:underline"
+                onClick={(e) => e.stopPropagation()}
+              >
+                #{pullRequest.number}
+              </a>
+            ) : (
+              <span className="text-muted-foreground">#{pullRequest.number}</span>
+            )}
+          </div>
+          <div className="flex flex-wrap items-center gap-2 text-sm text-muted-foreground mt-1">
+            {pullRequest.branch && (
+              <>
+                <span>{pullRequest.branch}</span>
+                <span>•</span>
+              </>
+            )}
+            <span>{pullRequest.author}</span>
+            <span>•</span>
+            <span>{formatDate(pullRequest.merged_at)}</span>
+            <span>•</span>
+            <span>{pullRequest.commits.length} commit{pullRequest.commits.length === 1 ? '' : 's'}</span>
+          </div>
+          {pullRequest.packages.length > 0 && (
+            <div className="flex flex-wrap gap-1 mt-2">
+              {pullRequest.packages.map((name) => (
+                <Badge key={name} variant="secondary">{name}</Badge>
+              ))}
+            </div>
+          )}
+        </div>
+        {expanded ? (
+          <ChevronDown className="w-4 h-4 mt-1 text-muted-foreground" />
+        ) : (
+          <ChevronRight className="w-4 h-4 mt-1 text-muted-foreground" />
+        )}
+      </div>
+
+      {expanded && (
+        <div className="mt-3 ml-8 space-y-1">
+          {pullRequest.commits.map((commit) => (
+            <div
+              key={commit.id}
+              className="flex items-center gap-2 text-sm p-2 rounded cursor-pointer hover:bg-background/60"
+              onClick={() => onCommitClick(commit)}
+            >
+              <GitCommitIcon className="w-4 h-4 text-muted-foreground" />
+              <span className="font-mono text-muted-foreground">{commit.id.slice(0, 7)}</span>
+              <span className="truncate">{commit.message.split('\n')[0]}</span>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+};
+
+const PullRequestList: React.FC<{
+  pullRequests: PullRequest[];
+  unmatched: BasicCommit[];
+  onCommitClick: (commit: BasicCommit) => void;
+}> = ({ pullRequests, unmatched, onCommitClick }) => {
+  if (!pullRequests?.length && !unmatched?.length) return null;
+
+  return (
+    <div className="space-y-2 px-2 pt-2">
+      {pullRequests.map((pullRequest) => (
+        <PullRequestRow
+          key={pullRequest.number}
+          pullRequest={pullRequest}
+          onCommitClick={onCommitClick}
+        />
+      ))}
+      {unmatched.length > 0 && (
+        <>
+          <h4 className="text-sm font-medium text-muted-foreground pt-4">Commits outside pull requests</h4>
+          {unmatched.map((commit) => (
+            <div
+              key={commit.id}
+              className="flex items-center gap-2 text-sm p-3 bg-muted rounded-lg cursor-pointer hover:bg-muted/80"
+              onClick={() => onCommitClick(commit)}
+            >
+              <GitCommitIcon className="w-4 h-4 text-muted-foreground" />
+              <span className="font-mono text-muted-foreground">{commit.id.slice(0, 7)}</span>
+              <span className="truncate">{commit.message.split('\n')[0]}</span>
+              <span className="ml-auto text-muted-foreground">{commit.author}</span>
+            </div>
+          ))}
+        </>
+      )}
+    </div>
+  );
+};
+
+export default PullRequestList;
@@ -0,0 +1,92 @@
+import React from 'react';
+import { Badge } from "@/components/ui/badge";
+import { SemanticDiff as SemanticDiffData, ValueChange } from "@/types";
+
+const changeColor = (change: ValueChange['change']) => {
+  switch (change) {
+    case 'added':
+      return 'text-green-600';
+    case 'removed':
+      return 'text-red-600';
+    default:
+      return 'text-yellow-600';
+  }
+};
+
+const formatChange = ({ old, new: next }: ValueChange) => {
+  if (old && next) return `${old} → ${next}`;
+  return next ?? old ?? '';
+};
+
+const formatVersions = (versions: string[]) => versions.length ? versions.join(', ') : '–';
+
+const ChangeRow = ({ label, change, kind }: { label: string; change: ValueChange; kind?: string }) => (
+  <div className="flex items-center gap-2 text-sm">
+    <span className={`w-16 ${changeColor(change.change)}`}>{change.change}</span>
+    <span className="font-mono">{label}</span>
+    {kind && kind !== 'normal' && <Badge variant="secondary">{kind}</Badge>}
+    <span className="font-mono text-muted-foreground truncate">{formatChange(change)}</span>
+  </div>
+);
+
+const SemanticDiff: React.FC<{ diff: SemanticDiffData }> = ({ diff }) => {
+  if (diff.kind === 'manifest') {
+    if (!diff.version && !diff.dependencies.length && !diff.scripts.length) return null;
+    return (
+      <div className="space-y-3 p-4 mb-4 bg-muted rounded-lg">
+        {diff.version && (
+          <div className="text-sm">
+            <span className="font-medium">{diff.name ?? 'Version'}</span>{' '}
+            <span className="font-mono text-muted-foreground">{formatChange(diff.version)}</span>
+          </div>
+        )}
+        {diff.dependencies.length > 0 && (
+          <div className="space-y-1">
+            <h5 className="text-sm font-medium">Dependencies</h5>
+            {diff.dependencies.map((dependency) => (
+              <ChangeRow
+                key={`${dependency.kind}:${dependency.name}`}
+                label={dependency.name}
+                change={dependency}
+                kind={dependency.kind}
+              />
+            ))}
+          </div>
+        )}
+        {diff.scripts.length > 0 && (
+          <div className="space-y-1">
+            <h5 className="text-sm font-medium">Scripts</h5>
+            {diff.scripts.map((script) => (
+              <ChangeRow key={script.name} label={script.name} change={script} />
+            ))}
+          </div>
+        )}
+      </div>
+    );
+  }
+
+  if (!diff.importers.length) return null;
+  return (
+    <div className="space-y-3 p-4 mb-4 bg-muted rounded-lg max-h-80 overflow-y-auto">
+      {diff.importers.map((importer) => (
+        <div key={importer.path} className="space-y-1">
+          <h5 className="text-sm font-medium">
+            {importer.name ?? importer.path}{' '}
+            <span className="text-muted-foreground font-normal">({importer.changes.length} resolution changes)</span>
+          </h5>
+          {importer.changes.map((change) => (
+            <div key={change.name} className="flex items-center gap-2 text-sm">
+              <span className="font-mono">{change.name}</span>
+              {change.direct && <Badge variant="secondary">direct</Badge>}
+              <span className="font-mono text-muted-foreground">
+                {formatVersions(change.old)} → {formatVersions(change.new)}
+              </span>
+            </div>
+          ))}
+        </div>
+      ))}
+    </div>
+  );
+};
+
+export default SemanticDiff;
@@ -3,13 +3,13 @@ import { Card } from '@/components/ui/card';
 import useStore from '@/store';
 import { Alert, AlertDescription, AlertTitle } from '@/components/ui/alert';
 import { convertFileSrc, invoke } from '@tauri-apps/api/core';
-import { homeDir, join } from '@tauri-apps/api/path';
 import { Loader2, Folder, FileWarning, ArrowLeft } from "lucide-react";
 import { Button } from "@/components/ui/button";
+import { BatchDocsResult } from '@/types';
 
 const PreviewDocs = () => {
   const [loading, setLoading] = useState(false);
-  const { error, setError, selectedFolder, currentGeneratedFolder } = useStore();
+  const { error, setError, selectedFolder, currentGeneratedFolder, monoRepoPath, getPackagePath } = useStore();
   const [assetUrl, setAssetUrl] = useState('');
   const iframeRef = useRef<HTMLIFrameElement>(null);
 
@@ -17,12 +17,18 @@ const PreviewDocs = () => {
     setLoading(true);
     setError('');
     try {
-      const homeDirPath = await homeDir();
-      const folderPath = await join(homeDirPath, 'mr-manager', selectedFolder || '');
+      // Without a selected package, fall back to the combined monorepo landing page
+      const folderPath = selectedFolder
+        ? await invoke<string>('get_docs_output_dir', { path: `${getPackagePath(monoRepoPath)}/${selectedFolder}` })
+        : await invoke<string>('get_docs_index_dir', { path: monoRepoPath });
       const assetUrl = convertFileSrc(folderPath);
       const isExist = await invoke('file_exists', { path: `${folderPath}/index.html` });
       if (!isExist) {
-        setError('Please generate documentation first.');
+        if (selectedFolder) {
+          setError('Please generate documentation first.');
+        }
+        setAssetUrl('');
+        return;
       }
       setAssetUrl(assetUrl);
     } catch (err) {
@@ -33,6 +39,20 @@ const PreviewDocs = () => {
     }
   };
 
+  const handleBuildAll = async () => {
+    setLoading(true);
+    setError('');
+    try {
+      await invoke<BatchDocsResult>('generate_all_docs', { path: monoRepoPath });
+    } catch (err) {
+      setError('Failed to generate documentation');
+      console.error(err);
+      setLoading(false);
+      return;
+    }
+    await handleFolderSelect();
+  };
+
   const handleBackClick = () => {
     if (iframeRef.current) {
       console.log('iframeRef.current.window>>', iframeRef.current.contentWindow?.top?.history)
@@ -48,10 +68,10 @@ const PreviewDocs = () => {
   };
 
   useEffect(() => {
-    if (selectedFolder) {
+    if (selectedFolder || monoRepoPath) {
       handleFolderSelect();
     }
-  }, [selectedFolder, currentGeneratedFolder]);
+  }, [selectedFolder, currentGeneratedFolder, monoRepoPath]);
 
   return (
     <Card className="w-full h-full p-2 bg-background rounded-none border-none col-span-3">
@@ -72,7 +92,7 @@ const PreviewDocs = () => {
             </Alert>
           </div>
         </div>
-      ) : !selectedFolder ? (
+      ) : !selectedFolder && !assetUrl ? (
         <Card className="w-full h-full flex items-center justify-center p-8 bg-background rounded-none border-none">
           <div className="text-center space-y-4">
             <Folder className="w-12 h-12 text-gray-400 mx-auto" />
@@ -80,6 +100,11 @@ const PreviewDocs = () => {
             <AlertDescription className="text-gray-500">
               Please select a folder to preview the documentation
             </AlertDescription>
+            {monoRepoPath && (
+              <Button variant="outline" size="sm" onClick={handleBuildAll}>
+                Build docs for all packages
+              </Button>
+            )}
           </div>
         </Card>
       ) : (
@@ -0,0 +1,41 @@
+import { useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { isPermissionGranted, requestPermission, sendNotification } from '@tauri-apps/plugin-notification';
+import useStore from '@/store';
+import { notificationSound } from '@/lib/notification-sound';
+import { AdvisoryImportSummary, NewAuditFinding } from '@/types';
+
+export const useAdvisoryImport = () => {
+  const { monoRepoPath, getPackagePath, notificationSettings } = useStore();
+  const { isEnabled, soundEnabled, selectedSound, monitoredFolders } = notificationSettings;
+
+  const notifyFindings = useCallback(async (findings: NewAuditFinding[]) => {
+    if (!isEnabled || findings.length === 0) return;
+
+    let permissionGranted = await isPermissionGranted();
+    if (!permissionGranted) {
+      permissionGranted = await requestPermission() === 'granted';
+    }
+    if (!permissionGranted) return;
+
+    if (soundEnabled) {
+      await notificationSound(selectedSound);
+    }
+    for (const finding of findings) {
+      await sendNotification({ title: finding.title, body: finding.body });
+    }
+  }, [isEnabled, soundEnabled, selectedSound]);
+
+  // Imports an OSV dump and announces new advisories affecting the monitored folders
+  const importAdvisories = useCallback(async (source: string) => {
+    const basePath = getPackagePath(monoRepoPath);
+    const summary = await invoke<AdvisoryImportSummary>('import_advisories', {
+      source,
+      monitored: monitoredFolders.map((folder) => `${basePath}/${folder}`),
+    });
+    await notifyFindings(summary.findings);
+    return summary;
+  }, [getPackagePath, monoRepoPath, monitoredFolders, notifyFindings]);
+
+  return { importAdvisories };
+};
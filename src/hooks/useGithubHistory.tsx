@@ -3,7 +3,7 @@ import { isPermissionGranted, requestPermission, sendNotification } from '@tauri
 
 import { invoke } from '@tauri-apps/api/core';
 import useAppStore, { NotificationSettings } from "@/store";
-import { BasicCommit, DetailedCommit } from "@/types";
+import { BasicCommit, DetailedCommit, PullRequest, PullRequestLog } from "@/types";
 // @ts-ignore
 import FuzzySearch from 'fuzzy-search';
 import Fuse from 'fuse.js';
@@ -13,6 +13,7 @@ const useGitHistory = () => {
   const [detailsLoading, setDetailsLoading] = useState(false);
   const [error, setError] = useState('');
   const [commits, setCommits] = useState<BasicCommit[]>([]);
+  const [pullRequests, setPullRequests] = useState<PullRequest[]>([]);
   const [selectedCommit, setSelectedCommit] = useState<DetailedCommit | null>(null);
   const [isDetailsOpen, setIsDetailsOpen] = useState(false);
   const [currentRepoPath, setCurrentRepoPath] = useState('');
@@ -140,6 +141,29 @@ const useGitHistory = () => {
     }
   };
 
+  const handleLoadNewPullRequests = async () => {
+    setLoading(true);
+    setPullRequests([]);
+    setCurrentRepoPath(monoRepoPath)
+
+    try {
+      const log = await invoke<PullRequestLog>('get_new_pull_requests', {
+        path: monoRepoPath,
+        remote,
+        branch
+      });
+
+      setPullRequests(log.pull_requests);
+      setCommits(log.unmatched);
+      setError('');
+    } catch (error) {
+      console.error('Failed to load pull requests:', error);
+      setError(`Failed to load pull requests: ${error instanceof Error ? error.message : error}`);
+    } finally {
+      setLoading(false);
+    }
+  };
+
   const loadMore = async () => {
     setLoading(true);
     try {
@@ -187,6 +211,7 @@ const useGitHistory = () => {
       const details = await invoke<DetailedCommit>('get_new_commits_details', {
         repoPath: currentRepoPath,
         commitId: commit.id,
+        remote,
       });
       setSelectedCommit(details);
       setIsDetailsOpen(true);
@@ -209,6 +234,17 @@ const useGitHistory = () => {
     return fuse.search(searchQuery).map((result) => result.item);
   }, [commits, searchQuery]);
 
+  const searchedPullRequests = useMemo(() => {
+    if (!searchQuery) return pullRequests;
+
+    const fuse = new Fuse(pullRequests, {
+      keys: ['title', 'author', 'branch', 'number', 'packages'],
+      includeScore: true,
+    });
+
+    return fuse.search(searchQuery).map((result) => result.item);
+  }, [pullRequests, searchQuery]);
+
   const handleSearch = (e: ChangeEvent<HTMLInputElement>) => {
     setSearchQuery(e.target.value);
   };
@@ -278,6 +314,7 @@ const useGitHistory = () => {
     detailsLoading,
     error,
     commits: searchedCommits, // Return filtered commits instead of raw commits
+    pullRequests: searchedPullRequests,
     selectedCommit,
     isDetailsOpen,
     currentRepoPath,
@@ -300,6 +337,7 @@ const useGitHistory = () => {
     setRemote,
     handleSettingsChange,
     handleFolderClickForNewCommits,
+    handleLoadNewPullRequests,
     handleNextCommit
   };
 };
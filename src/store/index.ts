@@ -26,7 +26,6 @@ interface StoreState {
   searchQuery: string;
   currentGeneratedFolder: string;
   folders: Folder[];
-  docFolderName: string;
   favorites: string[];
   notificationSettings: NotificationSettings;
   packageFolders: string[]; // New field to store available package folders
@@ -55,7 +54,6 @@ const initialState: StoreState = {
   searchQuery: '',
   currentGeneratedFolder: '',
   folders: [],
-  docFolderName: '.mr-manager',
   favorites: [],
   packageFolders: [], // Initialize empty package folders array
   notificationSettings: {
@@ -2,6 +2,8 @@
 export interface GitChange {
   status: string;
   file: string;
+  web_url?: string | null;
+  semantic?: SemanticDiff | null;
 }
 
 export interface GitCommit {
@@ -18,9 +20,23 @@ export interface FolderItem {
   isSelected: boolean;
 }
 
+export interface FolderEntry {
+  name: string;
+  path: string;
+  relative_path: string;
+  depth: number;
+  entry_type: 'file' | 'directory' | 'symlink' | 'other' | null;
+  size: number | null;
+  modified: number | null;
+  ignored: boolean;
+  manifest: string | null;
+  language: string | null;
+  error: string | null;
+}
+
 // Tauri command interfaces
 export interface TauriCommands {
-  list_folders: (path: string) => Promise<string[]>;
+  list_folders: (path: string, includeFiles?: boolean) => Promise<FolderEntry[]>;
   get_git_history: (path: string) => Promise<GitCommit[]>;
 }
 
@@ -33,18 +49,50 @@ export interface GitHistoryProps {
   className?: string;
 }
 
+export interface IssueRef {
+  key: string;
+  tracker: string | null;
+  number: number;
+  closes: boolean;
+  url: string | null;
+}
+
 export interface BasicCommit {
   id: string;
   message: string;
   author: string;
   date: number;
-  remote_url: string;
+  remote_url: string | null;
+  web_url: string | null;
+  issues: IssueRef[];
 }
 
 export interface DetailedCommit extends BasicCommit {
   changes: GitChange[];
 }
 
+export interface IssueCommit extends BasicCommit {
+  packages: string[];
+}
+
+export interface PullRequest {
+  number: number;
+  title: string;
+  branch: string | null;
+  author: string;
+  merged_at: number;
+  merge_commit: string;
+  kind: 'merge' | 'squash';
+  commits: BasicCommit[];
+  packages: string[];
+  web_url: string | null;
+}
+
+export interface PullRequestLog {
+  pull_requests: PullRequest[];
+  unmatched: BasicCommit[];
+}
+
 export type NotificationSettings = {
 
   isEnabled: boolean;
@@ -54,3 +102,183 @@ export type NotificationSettings = {
   selectedFolders: string[];
 
 };
+
+export type ScriptSource = 'npm' | 'cargo' | 'make';
+
+export interface Script {
+  name: string;
+  source: ScriptSource;
+  command: string;
+}
+
+export interface PackageScripts {
+  path: string;
+  package_manager: 'npm' | 'pnpm' | 'yarn' | 'bun' | null;
+  scripts: Script[];
+}
+
+export type TaskSelection =
+  | { kind: 'all' }
+  | { kind: 'affected'; since: string }
+  | { kind: 'with_dependents'; package: string };
+
+export interface PackageTaskResult {
+  name: string;
+  path: string;
+  status: 'succeeded' | 'failed' | 'skipped' | 'cancelled';
+  job_id: string | null;
+  duration_ms: number | null;
+  exit_code: number | null;
+  error: string | null;
+  hash: string | null;
+  cached: boolean;
+}
+
+export interface TaskRunSummary {
+  run_id: string;
+  script: string;
+  packages: PackageTaskResult[];
+  succeeded: number;
+  failed: number;
+  skipped: number;
+  cancelled: number;
+  cached: number;
+  duration_ms: number;
+}
+
+export type DependencyKind = 'normal' | 'dev' | 'peer' | 'optional' | 'build';
+
+export interface LockedPackage {
+  name: string;
+  version: string;
+  dependencies: string[];
+  integrity: string | null;
+}
+
+export interface ImporterDependency {
+  name: string;
+  kind: DependencyKind;
+  specifier: string | null;
+  id: string | null;
+  link: string | null;
+}
+
+export interface LockfileImporter {
+  path: string;
+  name: string | null;
+  dependencies: ImporterDependency[];
+}
+
+export interface ResolvedDependencies {
+  kind: 'pnpm' | 'yarn' | 'npm';
+  path: string;
+  importers: LockfileImporter[];
+  packages: Record<string, LockedPackage>;
+  duplicates: { name: string; versions: string[] }[];
+}
+
+export interface DependencyChain {
+  importer: string;
+  kind: DependencyKind;
+  chain: string[];
+}
+
+export interface ValueChange {
+  change: 'added' | 'removed' | 'changed';
+  old: string | null;
+  new: string | null;
+}
+
+export interface ManifestDiff {
+  kind: 'manifest';
+  name: string | null;
+  version: ValueChange | null;
+  dependencies: (ValueChange & { name: string; kind: DependencyKind })[];
+  scripts: (ValueChange & { name: string })[];
+}
+
+export interface LockfileDiff {
+  kind: 'lockfile';
+  lockfile: 'pnpm' | 'yarn' | 'npm';
+  importers: {
+    path: string;
+    name: string | null;
+    changes: { name: string; direct: boolean; old: string[]; new: string[] }[];
+  }[];
+}
+
+export type SemanticDiff = ManifestDiff | LockfileDiff;
+
+export type DocsBuildOutcome = 'built' | 'skipped' | 'failed' | 'unsupported';
+
+export interface PackageDocsResult {
+  name: string;
+  path: string;
+  docs_path: string | null;
+  generator: string | null;
+  outcome: DocsBuildOutcome;
+  error: string | null;
+}
+
+export interface BatchDocsResult {
+  index: string;
+  packages: PackageDocsResult[];
+}
+
+export interface SbomOutput {
+  package: string;
+  components: number;
+  missing_licenses: string[];
+  cyclonedx: string;
+  spdx: string;
+}
+
+export type AdvisorySeverity = 'unknown' | 'low' | 'moderate' | 'high' | 'critical';
+
+export interface AdvisoryImportSummary {
+  imported: number;
+  total: number;
+  new: string[];
+  database: string;
+  findings: NewAuditFinding[];
+}
+
+export interface AuditFinding {
+  package: string;
+  dependency: string;
+  version: string;
+  advisory: string;
+  aliases: string[];
+  summary: string;
+  severity: AdvisorySeverity;
+  fixed: string[];
+  path: string[];
+}
+
+export interface NewAuditFinding extends AuditFinding {
+  folder: string;
+  title: string;
+  body: string;
+}
+
+export interface AuditReport {
+  advisories: number;
+  findings: AuditFinding[];
+}
+
+export type LicenseStatus = 'allowed' | 'denied' | 'unlisted' | 'unknown' | 'exempt';
+
+export interface LicenseEntry {
+  name: string;
+  version: string;
+  license: string | null;
+  status: LicenseStatus;
+}
+
+export interface ComplianceReport {
+  package: string;
+  dependencies: number;
+  licenses: Record<string, number>;
+  violations: LicenseEntry[];
+  unknown: LicenseEntry[];
+}
@@ -1,7 +1,7 @@
 import { useEffect, useRef, useCallback } from 'react';
 import FolderList from "@/components/folder-list";
 import useStore from "@/store";
-import { FolderItem } from "@/types";
+import { FolderEntry, FolderItem } from "@/types";
 import { TimerReset } from "lucide-react";
 import { Terminal } from 'xterm';
 import { FitAddon } from 'xterm-addon-fit';
@@ -28,8 +28,8 @@ const TerminalComponent = ({ currentFolder, monoRepoPath }: TerminalComponentPro
       const path = `${monoRepoPath}/packages/${currentFolder}`;
 
       if (command === 'ls') {
-        const folders = await invoke('list_folders', { path });
-        term.writeln('\r\n' + (folders as string[]).join('  '));
+        const entries = await invoke<FolderEntry[]>('list_folders', { path });
+        term.writeln('\r\n' + entries.map((entry) => entry.entry_type === 'directory' ? `${entry.name}/` : entry.name).join('  '));
       }
       else if (command.startsWith('git ')) {
         const gitCommand = command.substring(4);
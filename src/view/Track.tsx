@@ -1,15 +1,17 @@
-import React, { useCallback } from 'react';
+import React, { useCallback, useState } from 'react';
 import { Alert, AlertDescription } from "@/components/ui/alert";
 import { Loader2, GitCommit as GitCommitIcon, RefreshCcw, Search } from "lucide-react";
 import { cn } from "@/lib/utils";
-import { BasicCommit } from '@/types';
+import { BasicCommit, PullRequest } from '@/types';
 import CommitList from '@/components/git-components/commit-list';
+import PullRequestList from '@/components/git-components/pull-request-list';
 import useGitHistory from '@/hooks/useGithubHistory';
 import CommitDetailsDialog from '@/components/git-components/commit-details-dialog';
 import SubHeader from '@/components/sub-header';
 import { Input } from '@/components/ui/input';
 import { Tooltip, TooltipContent, TooltipTrigger } from '@/components/ui/tooltip';
 import { Button } from '@/components/ui/button';
+import { ToggleGroup, ToggleGroupItem } from '@/components/ui/toggle-group';
 import NotificationSettings from '@/components/git-components/notification-settings';
 import useStore from '@/store';
 
@@ -41,9 +43,13 @@ const LoadingSpinner = ({ message }: { message?: string }) => (
   </div>
 );
 
+type TrackMode = 'commits' | 'pulls';
+
 type CommitListSection = {
   loading: boolean;
+  mode: TrackMode;
   commits: BasicCommit[];
+  pullRequests: PullRequest[];
   selectedFolder: string | null;
   onCommitClick: (commit: BasicCommit) => void;
   onLoadMore: () => void;
@@ -51,12 +57,12 @@ type CommitListSection = {
   initializeNewCommits: () => void;
 };
 
-const CommitListSection = ({ loading, commits, selectedFolder, onCommitClick, initializeNewCommits }: CommitListSection) => {
+const CommitListSection = ({ loading, mode, commits, pullRequests, selectedFolder, onCommitClick, initializeNewCommits }: CommitListSection) => {
   if (loading) {
     return <LoadingSpinner message="Loading commits..." />;
   }
 
-  if (commits?.length === 0) {
+  if (commits?.length === 0 && (mode === 'commits' || pullRequests?.length === 0)) {
     return (
       <div className="flex flex-col items-center justify-center h-full text-muted-foreground">
         <RefreshCcw className="w-20 h-20 mb-4 cursor-pointer" onClick={initializeNewCommits} />
@@ -66,6 +72,10 @@ const CommitListSection = ({ loading, commits, selectedFolder, onCommitClick, in
     );
   }
 
+  if (mode === 'pulls') {
+    return <PullRequestList pullRequests={pullRequests} unmatched={commits} onCommitClick={onCommitClick} />;
+  }
+
   return <CommitList commits={commits} onCommitClick={onCommitClick} />;
 };
 
@@ -75,6 +85,7 @@ const GitHistory = ({ className }: { className?: string }) => {
     detailsLoading,
     error,
     commits,
+    pullRequests,
     setCommits,
     selectedCommit,
     isDetailsOpen,
@@ -82,6 +93,7 @@ const GitHistory = ({ className }: { className?: string }) => {
     folders,
     selectedFolder,
     handleFolderClickForNewCommits,
+    handleLoadNewPullRequests,
     handleCommitClickForNew,
     setIsDetailsOpen,
     searchQuery,
@@ -94,15 +106,26 @@ const GitHistory = ({ className }: { className?: string }) => {
   } = useGitHistory();
 
   const { monoRepoPath } = useStore();
+  const [mode, setMode] = useState<TrackMode>('commits');
 
-  const initializeNewCommits = useCallback(async () => {
+  const initializeNewCommits = useCallback(async (nextMode: TrackMode = mode) => {
     if (!monoRepoPath) return;
     try {
-      await handleFolderClickForNewCommits();
+      if (nextMode === 'pulls') {
+        await handleLoadNewPullRequests();
+      } else {
+        await handleFolderClickForNewCommits();
+      }
     } catch (error) {
       console.error('Failed to check for new commits:', error);
     }
-  }, [monoRepoPath, setCommits]);
+  }, [monoRepoPath, setCommits, mode]);
+
+  const handleModeChange = (value: string) => {
+    if (value !== 'commits' && value !== 'pulls') return;
+    setMode(value);
+    initializeNewCommits(value);
+  };
 
   return (
     <div className={cn("", className)}>
@@ -114,11 +137,15 @@ const GitHistory = ({ className }: { className?: string }) => {
 
       <SubHeader title='Track' icon='view'>
         <div className='ml-auto mr-5 flex gap-2'>
+          <ToggleGroup type="single" variant="outline" value={mode} onValueChange={handleModeChange}>
+            <ToggleGroupItem value="commits">Commits</ToggleGroupItem>
+            <ToggleGroupItem value="pulls">Pull requests</ToggleGroupItem>
+          </ToggleGroup>
           <CommitSearchInput value={searchQuery} onChange={handleSearch} />
           <Button
             variant="outline"
             size="icon"
-            onClick={initializeNewCommits}
+            onClick={() => initializeNewCommits()}
             disabled={loading}
             className={className}
           >
@@ -137,12 +164,14 @@ const GitHistory = ({ className }: { className?: string }) => {
         <div className="p-4 col-span-4 overflow-scroll max-h-[calc(100vh-136px)] scrollbar-hide">
           <CommitListSection
             loading={loading}
+            mode={mode}
             commits={commits}
+            pullRequests={pullRequests}
             selectedFolder={selectedFolder}
             onCommitClick={handleCommitClickForNew}
             onLoadMore={loadMore}
             hasMore={hasMore}
-            initializeNewCommits={initializeNewCommits}
+            initializeNewCommits={() => initializeNewCommits()}
           />
         </div>
       </div>